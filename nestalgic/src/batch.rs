@@ -0,0 +1,121 @@
+//! Run many independent consoles in parallel.
+//!
+//! This is intended for headless workloads like reinforcement learning, soak testing and
+//! compatibility reports where thousands of consoles need to be stepped as fast as possible.
+
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{Buttons, Nestalgic, Player};
+
+/// The result of running a single frame on a `BatchInstance`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct FrameOutput {
+    /// The frame that was completed.
+    pub frame: u64,
+
+    pub state_hash: u64,
+}
+
+/// A console owned by a `BatchRunner` along with its input and output queues.
+pub struct BatchInstance {
+    pub nestalgic: Nestalgic,
+
+    /// The buttons to hold on each controller, one entry per frame. When the queue is empty the
+    /// controllers keep whatever buttons they were last given.
+    pub inputs: VecDeque<[Buttons; 2]>,
+
+    /// One entry is pushed for every frame run. The caller is responsible for draining this.
+    pub outputs: VecDeque<FrameOutput>,
+}
+
+impl BatchInstance {
+    fn run_frame(&mut self) {
+        if let Some([player_one, player_two]) = self.inputs.pop_front() {
+            self.nestalgic.controller_mut(Player::One).buttons = player_one;
+            self.nestalgic.controller_mut(Player::Two).buttons = player_two;
+        }
+
+        self.nestalgic.run_frame();
+
+        self.outputs.push_back(FrameOutput {
+            frame: self.nestalgic.ppu.frame,
+            state_hash: self.nestalgic.state_hash(),
+        });
+    }
+}
+
+/// `BatchRunner` owns a set of independent consoles and steps them across a pool of threads.
+pub struct BatchRunner {
+    instances: Vec<BatchInstance>,
+    threads: usize,
+}
+
+impl BatchRunner {
+    /// Create a runner using one thread per available CPU core.
+    pub fn new() -> BatchRunner {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        BatchRunner { instances: Vec::new(), threads }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> BatchRunner {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Add `nestalgic` to the batch, returning the index of its `BatchInstance`.
+    pub fn push(&mut self, nestalgic: Nestalgic) -> usize {
+        self.instances.push(BatchInstance {
+            nestalgic,
+            inputs: VecDeque::new(),
+            outputs: VecDeque::new(),
+        });
+        self.instances.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    pub fn instance(&self, index: usize) -> &BatchInstance {
+        &self.instances[index]
+    }
+
+    pub fn instance_mut(&mut self, index: usize) -> &mut BatchInstance {
+        &mut self.instances[index]
+    }
+
+    pub fn instances_mut(&mut self) -> impl Iterator<Item = &mut BatchInstance> {
+        self.instances.iter_mut()
+    }
+
+    /// Run `frames` frames on every instance, splitting the instances evenly between threads.
+    pub fn run_frames(&mut self, frames: u32) {
+        if self.instances.is_empty() {
+            return;
+        }
+
+        let chunk_size = self.instances.len().div_ceil(self.threads);
+
+        thread::scope(|scope| {
+            for chunk in self.instances.chunks_mut(chunk_size) {
+                scope.spawn(move || {
+                    for _ in 0..frames {
+                        chunk.iter_mut().for_each(BatchInstance::run_frame);
+                    }
+                });
+            }
+        });
+    }
+}
+
+impl Default for BatchRunner {
+    fn default() -> Self {
+        BatchRunner::new()
+    }
+}
@@ -0,0 +1,70 @@
+use crate::nes_bus::Bus;
+
+/// A `BusInterceptor` sits between the CPU and the rest of the console and can observe or change
+/// every read and write the CPU makes.
+///
+/// This is intended for instrumentation (tracing, coverage, profiling), fault injection and
+/// bus-level cheats. Interceptors are registered with `Nestalgic::add_bus_interceptor` and are
+/// called in the order they were added.
+///
+/// Interceptors must be `Send` so that a `Nestalgic` can be moved to another thread.
+pub trait BusInterceptor: Send {
+    /// Called before the CPU reads from `address`. Returning `Some` skips the read entirely and
+    /// gives the CPU the returned value instead, so side effects such as clearing the vblank
+    /// flag won't happen.
+    fn before_read(&mut self, _address: u16) -> Option<u8> {
+        None
+    }
+
+    /// Called after the CPU reads `value` from `address`. The returned value is what the CPU sees.
+    fn after_read(&mut self, _address: u16, value: u8) -> u8 {
+        value
+    }
+
+    /// Called before the CPU writes `data` to `address`. The returned value is what will be
+    /// written, or `None` to drop the write.
+    fn before_write(&mut self, _address: u16, data: u8) -> Option<u8> {
+        Some(data)
+    }
+
+    /// Called after `data` has been written to `address`.
+    fn after_write(&mut self, _address: u16, _data: u8) {}
+}
+
+/// Wraps a `Bus` and runs every access through a list of `BusInterceptor`s.
+pub(crate) struct InterceptedBus<'a, B: Bus> {
+    pub bus: B,
+    pub interceptors: &'a mut [Box<dyn BusInterceptor>],
+}
+
+impl <'a, B: Bus> Bus for InterceptedBus<'a, B> {
+    fn read_u8(&mut self, address: u16) -> u8 {
+        let replaced = self.interceptors
+            .iter_mut()
+            .find_map(|interceptor| interceptor.before_read(address));
+
+        let value = match replaced {
+            Some(value) => value,
+            None => self.bus.read_u8(address),
+        };
+
+        self.interceptors
+            .iter_mut()
+            .fold(value, |value, interceptor| interceptor.after_read(address, value))
+    }
+
+    fn write_u8(&mut self, address: u16, data: u8) {
+        let mut data = Some(data);
+        for interceptor in self.interceptors.iter_mut() {
+            data = data.and_then(|data| interceptor.before_write(address, data));
+        }
+
+        if let Some(data) = data {
+            self.bus.write_u8(address, data);
+
+            for interceptor in self.interceptors.iter_mut() {
+                interceptor.after_write(address, data);
+            }
+        }
+    }
+}
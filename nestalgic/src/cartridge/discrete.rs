@@ -0,0 +1,220 @@
+use nestalgic_rom::nesrom::NESROM;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `DiscreteBoard` emulates boards built from discrete logic rather than a mapper chip. A single
+/// latch anywhere in `0x8000`-`0xFFFF` selects a 32kb PRG ROM bank at `0x8000` and an 8kb CHR bank
+/// at `0x0000`. The boards only differ in which bits of the latch select each bank, see
+/// `BankLatch`.
+///
+/// These boards have bus conflicts: the value written is ANDed with the PRG ROM byte at the same
+/// address. Mirroring is fixed by the cartridge.
+pub struct DiscreteBoard {
+    pub prg_rom: Vec<u8>,
+    /// Either the CHR ROM from the cartridge or CHR RAM sized by the header if the cartridge has
+    /// no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    mirroring: Mirroring,
+    latch: BankLatch,
+
+    prg_bank: u8,
+    chr_bank: u8,
+}
+
+/// Which bits of a `DiscreteBoard`'s latch select the PRG and CHR banks.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct BankLatch {
+    pub prg_bits: u8,
+    pub chr_bits: u8,
+}
+
+impl BankLatch {
+    /// The Color Dreams board (mapper 11), used by unlicensed games from Color Dreams and Wisdom
+    /// Tree such as Crystal Mines and Bible Adventures:
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// CCCC LLPP
+    /// |||| ||||
+    /// |||| ||++- 32kb PRG ROM bank at 0x8000
+    /// |||| ++--- Lockout defeat, unused
+    /// ++++------ 8kb CHR ROM bank at 0x0000
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/Color_Dreams
+    pub const COLOR_DREAMS: BankLatch = BankLatch { prg_bits: 0b0000_0011, chr_bits: 0b1111_0000 };
+
+    /// Nintendo's GNROM and MHROM boards (mapper 66), used by the Super Mario Bros. + Duck Hunt
+    /// multicart and Dragon Power:
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// xxPP xxCC
+    ///   ||   ||
+    ///   ||   ++- 8kb CHR ROM bank at 0x0000
+    ///   ++------ 32kb PRG ROM bank at 0x8000
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/GxROM
+    pub const GXROM: BankLatch = BankLatch { prg_bits: 0b0011_0000, chr_bits: 0b0000_0011 };
+
+    fn bank(data: u8, bits: u8) -> u8 {
+        (data & bits) >> bits.trailing_zeros()
+    }
+}
+
+impl DiscreteBoard {
+    const PRG_BANK_SIZE: usize = 32 * 1024;
+    const CHR_BANK_SIZE: usize = 8 * 1024;
+
+    pub fn from_rom(rom: &NESROM, latch: BankLatch) -> Result<DiscreteBoard, CartridgeError> {
+        check_rom_sizes(rom, DiscreteBoard::PRG_BANK_SIZE, 1, DiscreteBoard::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(DiscreteBoard {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            mirroring: Mirroring::from(&rom.header.mirroring_type),
+            latch,
+            prg_bank: 0,
+            chr_bank: 0,
+        })
+    }
+
+    fn prg_rom_address(&self, address: u16) -> usize {
+        let offset = address as usize - 0x8000;
+        (self.prg_bank as usize * DiscreteBoard::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        (self.chr_bank as usize * DiscreteBoard::CHR_BANK_SIZE + address as usize) % self.chr.len()
+    }
+}
+
+impl Mapper for DiscreteBoard {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xFFFF => self.prg_rom[self.prg_rom_address(address)],
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x7FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0xFFFF => {
+                let data = data & self.cpu_read_u8(address);
+                self.prg_bank = BankLatch::bank(data, self.latch.prg_bits);
+                self.chr_bank = BankLatch::bank(data, self.latch.chr_bits);
+            },
+            0x4020..=0x7FFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.prg_bank);
+        state.write_u8(self.chr_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.prg_bank = state.read_u8()?;
+        self.chr_bank = state.read_u8()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn discrete_board(latch: BankLatch, chr_banks: usize) -> DiscreteBoard {
+        let mut rom = test_rom(11, DiscreteBoard::PRG_BANK_SIZE, 4, DiscreteBoard::CHR_BANK_SIZE, chr_banks);
+
+        // Fill every PRG bank with 0xFF so writes aren't masked by bus conflicts, except for the
+        // marker byte at the start of each bank so we can tell which one is mapped
+        for (offset, byte) in rom.prg_rom.iter_mut().enumerate() {
+            if offset % DiscreteBoard::PRG_BANK_SIZE != 0 {
+                *byte = 0xFF;
+            }
+        }
+
+        DiscreteBoard::from_rom(&rom, latch).unwrap()
+    }
+
+    #[test]
+    pub fn color_dreams_latch_selects_prg_and_chr_banks() {
+        let mut board = discrete_board(BankLatch::COLOR_DREAMS, 16);
+        board.cpu_write_u8(0x8001, 0b1010_0010);
+
+        assert_eq!(board.cpu_read_u8(0x8000), 2);
+        assert_eq!(board.ppu_read_u8(0x0000), 10);
+    }
+
+    #[test]
+    pub fn gxrom_latch_selects_prg_and_chr_banks() {
+        let mut board = discrete_board(BankLatch::GXROM, 4);
+        board.cpu_write_u8(0x8001, 0b0010_0011);
+
+        assert_eq!(board.cpu_read_u8(0x8000), 2);
+        assert_eq!(board.ppu_read_u8(0x0000), 3);
+    }
+
+    #[test]
+    pub fn writes_are_anded_with_prg_rom() {
+        let mut board = discrete_board(BankLatch::COLOR_DREAMS, 16);
+        board.cpu_write_u8(0x8000, 0b0011_0011);
+
+        // The byte at 0x8000 in bank 0 is 0, so the whole write is masked out
+        assert_eq!(board.cpu_read_u8(0x8000), 0);
+        assert_eq!(board.ppu_read_u8(0x0000), 0);
+    }
+
+    #[test]
+    pub fn chr_ram_is_writable() {
+        let mut board = discrete_board(BankLatch::GXROM, 0);
+        board.ppu_write_u8(0x1234, 0xAB);
+
+        assert_eq!(board.ppu_read_u8(0x1234), 0xAB);
+    }
+}
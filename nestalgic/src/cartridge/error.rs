@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CartridgeError {
+    #[error("Unsupported mapper {mapper_number}")]
+    UnsupportedMapper {
+        mapper_number: u16,
+    },
+
+    #[error("{bytes} bytes of PRG ROM can't be banked by this mapper")]
+    InvalidPrgRomSize {
+        bytes: usize,
+    },
+
+    #[error("{bytes} bytes of CHR can't be banked by this mapper")]
+    InvalidChrSize {
+        bytes: usize,
+    },
+}
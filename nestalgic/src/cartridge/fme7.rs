@@ -0,0 +1,294 @@
+use nestalgic_rom::nesrom::NESROM;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `FME7` emulates the Sunsoft FME-7 (mapper 69), used by Gimmick! and Batman: Return of the
+/// Joker.
+///
+/// Every internal register is written by first selecting it with a command and then writing its
+/// value as a parameter:
+///
+/// ```text
+/// 0x8000-0x9FFF: Command     0xA000-0xBFFF: Parameter
+///
+/// Command  Register
+/// 0x0-0x7  1kb CHR banks at 0x0000, 0x0400, ..., 0x1C00
+/// 0x8      PRG bank at 0x6000 (ROM or RAM)
+/// 0x9-0xB  8kb PRG ROM banks at 0x8000, 0xA000 and 0xC000
+/// 0xC      Mirroring
+/// 0xD      IRQ control
+/// 0xE-0xF  IRQ counter low and high byte
+/// ```
+///
+/// The last 8kb of PRG ROM is always mapped at `0xE000`. The Sunsoft 5B audio registers at
+/// `0xC000`-`0xFFFF` are ignored.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Sunsoft_FME-7
+pub struct FME7 {
+    pub prg_rom: Vec<u8>,
+
+    /// Either the CHR ROM from the cartridge or 8kb of CHR RAM if the cartridge has no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    /// Mapped to `0x6000`-`0x7FFF` when selected by command `0x8`
+    pub prg_ram: Vec<u8>,
+
+    command: u8,
+    chr_banks: [u8; 8],
+
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// ERbB BBBB
+    /// |||| ||||
+    /// ||++-++++- PRG bank
+    /// |+-------- 0: PRG ROM; 1: PRG RAM
+    /// +--------- PRG RAM enable
+    /// ```
+    prg_bank_6000: u8,
+    prg_banks: [u8; 3],
+
+    /// 0: vertical; 1: horizontal; 2: one-screen, lower; 3: one-screen, upper
+    mirroring_control: u8,
+
+    irq_enabled: bool,
+    irq_counter_enabled: bool,
+    irq_counter: u16,
+    irq_pending: bool,
+}
+
+impl FME7 {
+    const PRG_BANK_SIZE: usize = 8 * 1024;
+    const CHR_BANK_SIZE: usize = 1024;
+
+    pub fn from_rom(rom: &NESROM) -> Result<FME7, CartridgeError> {
+        check_rom_sizes(rom, FME7::PRG_BANK_SIZE, 1, FME7::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(FME7 {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            prg_ram: vec![0; rom.header.prg_ram_bytes as usize],
+            command: 0,
+            chr_banks: [0; 8],
+            prg_bank_6000: 0,
+            prg_banks: [0; 3],
+            mirroring_control: 0,
+            irq_enabled: false,
+            irq_counter_enabled: false,
+            irq_counter: 0,
+            irq_pending: false,
+        })
+    }
+
+    fn write_parameter(&mut self, data: u8) {
+        match self.command {
+            0x0..=0x7 => self.chr_banks[self.command as usize] = data,
+            0x8 => self.prg_bank_6000 = data,
+            0x9..=0xB => self.prg_banks[self.command as usize - 0x9] = data & 0b0011_1111,
+            0xC => self.mirroring_control = data & 0b11,
+            0xD => {
+                self.irq_enabled = data & 0b0000_0001 != 0;
+                self.irq_counter_enabled = data & 0b1000_0000 != 0;
+                self.irq_pending = false;
+            },
+            0xE => self.irq_counter = (self.irq_counter & 0xFF00) | data as u16,
+            _ => self.irq_counter = (self.irq_counter & 0x00FF) | ((data as u16) << 8),
+        }
+    }
+
+    fn prg_rom_address(&self, bank: usize, address: u16) -> usize {
+        let offset = address as usize & (FME7::PRG_BANK_SIZE - 1);
+        (bank * FME7::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn prg_ram_selected(&self) -> bool {
+        self.prg_bank_6000 & 0b0100_0000 != 0
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_bank_6000 & 0b1000_0000 != 0 && !self.prg_ram.is_empty()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        let bank = self.chr_banks[(address as usize >> 10) & 0b111] as usize;
+        let offset = address as usize & (FME7::CHR_BANK_SIZE - 1);
+        (bank * FME7::CHR_BANK_SIZE + offset) % self.chr.len()
+    }
+}
+
+impl Mapper for FME7 {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xDFFF => {
+                let bank = self.prg_banks[(address as usize - 0x8000) / FME7::PRG_BANK_SIZE] as usize;
+                self.prg_rom[self.prg_rom_address(bank, address)]
+            },
+            0xE000..=0xFFFF => {
+                let last_bank = self.prg_rom.len() / FME7::PRG_BANK_SIZE - 1;
+                self.prg_rom[self.prg_rom_address(last_bank, address)]
+            },
+            0x6000..=0x7FFF if !self.prg_ram_selected() => {
+                let bank = (self.prg_bank_6000 & 0b0011_1111) as usize;
+                self.prg_rom[self.prg_rom_address(bank, address)]
+            },
+            0x6000..=0x7FFF if self.prg_ram_enabled() => self.prg_ram[(address as usize - 0x6000) % self.prg_ram.len()],
+            0x6000..=0x7FFF => 0,
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x5FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0x9FFF => self.command = data & 0b0000_1111,
+            0xA000..=0xBFFF => self.write_parameter(data),
+            0xC000..=0xFFFF => {}, // Sunsoft 5B audio
+            0x6000..=0x7FFF => if self.prg_ram_selected() && self.prg_ram_enabled() {
+                let prg_ram_address = (address as usize - 0x6000) % self.prg_ram.len();
+                self.prg_ram[prg_ram_address] = data
+            },
+            0x4020..=0x5FFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    /// The IRQ counter decrements every CPU cycle and raises an IRQ when it wraps from 0 to 0xFFFF.
+    fn cpu_clock(&mut self) {
+        if !self.irq_counter_enabled {
+            return;
+        }
+
+        self.irq_counter = self.irq_counter.wrapping_sub(1);
+        if self.irq_counter == 0xFFFF && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+
+    fn irq_pending(&mut self) -> bool {
+        self.irq_pending
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.mirroring_control {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            2 => Mirroring::SingleScreenLower,
+            _ => Mirroring::SingleScreenUpper,
+        }
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.prg_ram);
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.command);
+        state.write_memory(&self.chr_banks);
+        state.write_u8(self.prg_bank_6000);
+        state.write_memory(&self.prg_banks);
+        state.write_u8(self.mirroring_control);
+
+        state.write_bool(self.irq_enabled);
+        state.write_bool(self.irq_counter_enabled);
+        state.write_u16(self.irq_counter);
+        state.write_bool(self.irq_pending);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        state.read_memory(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.command = state.read_u8()?;
+        state.read_memory(&mut self.chr_banks)?;
+        self.prg_bank_6000 = state.read_u8()?;
+        state.read_memory(&mut self.prg_banks)?;
+        self.mirroring_control = state.read_u8()?;
+
+        self.irq_enabled = state.read_bool()?;
+        self.irq_counter_enabled = state.read_bool()?;
+        self.irq_counter = state.read_u16()?;
+        self.irq_pending = state.read_bool()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn fme7() -> FME7 {
+        FME7::from_rom(&test_rom(69, FME7::PRG_BANK_SIZE, 16, FME7::CHR_BANK_SIZE, 32)).unwrap()
+    }
+
+    #[test]
+    pub fn command_and_parameter_switch_banks() {
+        let mut fme7 = fme7();
+        fme7.cpu_write_u8(0x8000, 0x9);
+        fme7.cpu_write_u8(0xA000, 5);
+        fme7.cpu_write_u8(0x8000, 0x3);
+        fme7.cpu_write_u8(0xA000, 20);
+
+        assert_eq!(fme7.cpu_read_u8(0x8000), 5);
+        assert_eq!(fme7.cpu_read_u8(0xE000), 15);
+        assert_eq!(fme7.ppu_read_u8(0x0C00), 20);
+    }
+
+    #[test]
+    pub fn prg_ram_is_only_writable_when_selected_and_enabled() {
+        let mut fme7 = fme7();
+        fme7.cpu_write_u8(0x8000, 0x8);
+        fme7.cpu_write_u8(0xA000, 2);
+        assert_eq!(fme7.cpu_read_u8(0x6000), 2);
+
+        fme7.cpu_write_u8(0xA000, 0b1100_0000);
+        fme7.cpu_write_u8(0x6000, 0x42);
+        assert_eq!(fme7.cpu_read_u8(0x6000), 0x42);
+    }
+
+    #[test]
+    pub fn irq_fires_when_counter_wraps() {
+        let mut fme7 = fme7();
+        fme7.cpu_write_u8(0x8000, 0xE);
+        fme7.cpu_write_u8(0xA000, 1);
+        fme7.cpu_write_u8(0x8000, 0xD);
+        fme7.cpu_write_u8(0xA000, 0b1000_0001);
+
+        fme7.cpu_clock();
+        assert!(!fme7.irq_pending());
+
+        fme7.cpu_clock();
+        assert!(fme7.irq_pending());
+
+        fme7.cpu_write_u8(0xA000, 0b1000_0001);
+        assert!(!fme7.irq_pending());
+    }
+}
@@ -1,6 +1,7 @@
 use nestalgic_rom::nesrom::NESROM;
 
-use super::NROM;
+use crate::save_state::{StateError, StateReader, StateWriter};
+use super::{CartridgeError, Mirroring, NROM, MMC1, MMC3, VRC6, FME7, DiscreteBoard, BankLatch, Namco108};
 
 /// A mapper is hardware found on the NES cartridge that maps the addresses on the cartridge
 /// to the physical hardware.
@@ -8,7 +9,13 @@ use super::NROM;
 /// All mapper functions accept the entire address space but are only defined
 /// within the address `0x4020` - `0xFFFF`. Attempting to read or write outside
 /// this address range will result in a panic
-pub trait Mapper {
+///
+/// Nametables (`0x2000`-`0x3EFF`) live in the console and palettes (`0x3F00`-`0x3FFF`) in the
+/// PPU, so the PPU functions are only called for the pattern tables. Mappers choose how
+/// nametables are laid out through `mirroring`.
+///
+/// Mappers must be `Send` so that a `Nestalgic` can be moved to another thread.
+pub trait Mapper: Send {
     fn cpu_read_u8(&self, address: u16) -> u8;
 
     fn cpu_write_u8(&mut self, address: u16, data: u8);
@@ -16,17 +23,105 @@ pub trait Mapper {
     fn ppu_read_u8(&self, address: u16) -> u8;
 
     fn ppu_write_u8(&mut self, address: u16, data: u8);
+
+    /// How the nametables at `0x2000`-`0x2FFF` are currently mirrored. Some boards change this at
+    /// runtime.
+    fn mirroring(&self) -> Mirroring;
+
+    /// Called when PPU address line A12 goes from low to high, ignoring rises that happen within
+    /// a few CPU cycles of the previous one. MMC3 uses this to count scanlines.
+    fn ppu_a12_rise(&mut self) {}
+
+    /// Called once per CPU cycle, for mappers with CPU-cycle-driven IRQ counters.
+    fn cpu_clock(&mut self) {}
+
+    /// True while the mapper is holding the CPU's IRQ line low.
+    fn irq_pending(&mut self) -> bool {
+        false
+    }
+
+    /// Write the board's banking registers, IRQ counters and RAM for a save state. ROM isn't saved
+    /// since it's reloaded from the cartridge.
+    fn save_state(&self, state: &mut StateWriter);
+
+    /// Restore state written by `save_state` on a board loaded from the same ROM.
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError>;
 }
 
-impl dyn Mapper {
-    pub fn for_rom(rom: &NESROM) -> Box<dyn Mapper> {
-        match rom.header.mapper_number {
-            0 => Box::new(NROM::from_rom(rom)),
-            _ => panic!("unsupported mapper number: {}", rom.header.mapper_number)
+/// A board that `Mapper::for_rom` knows how to build.
+pub struct MapperInfo {
+    /// The iNES mapper number
+    pub number: u16,
+
+    pub name: &'static str,
+
+    /// The NES 2.0 submappers that this board emulates differently from the base mapper. Empty
+    /// when every submapper is treated as the base mapper, which is all of them until the ROM
+    /// loader reads NES 2.0 headers.
+    pub submappers: &'static [u8],
+
+    build: fn(&NESROM) -> Result<Box<dyn Mapper>, CartridgeError>,
+}
+
+/// Every supported mapper, in mapper number order.
+pub const SUPPORTED_MAPPERS: &[MapperInfo] = &[
+    MapperInfo { number: 0, name: "NROM", submappers: &[], build: |rom| Ok(Box::new(NROM::from_rom(rom)?)) },
+    MapperInfo { number: 1, name: "MMC1", submappers: &[], build: |rom| Ok(Box::new(MMC1::from_rom(rom)?)) },
+    MapperInfo { number: 4, name: "MMC3", submappers: &[], build: |rom| Ok(Box::new(MMC3::from_rom(rom)?)) },
+    MapperInfo { number: 11, name: "Color Dreams", submappers: &[], build: |rom| Ok(Box::new(DiscreteBoard::from_rom(rom, BankLatch::COLOR_DREAMS)?)) },
+    MapperInfo { number: 24, name: "VRC6a", submappers: &[], build: |rom| Ok(Box::new(VRC6::from_rom(rom)?)) },
+    MapperInfo { number: 26, name: "VRC6b", submappers: &[], build: |rom| Ok(Box::new(VRC6::from_rom(rom)?)) },
+    MapperInfo { number: 66, name: "GxROM", submappers: &[], build: |rom| Ok(Box::new(DiscreteBoard::from_rom(rom, BankLatch::GXROM)?)) },
+    MapperInfo { number: 69, name: "Sunsoft FME-7", submappers: &[], build: |rom| Ok(Box::new(FME7::from_rom(rom)?)) },
+    MapperInfo { number: 206, name: "Namco 108", submappers: &[], build: |rom| Ok(Box::new(Namco108::from_rom(rom)?)) },
+];
+
+impl MapperInfo {
+    /// Which submappers this board handles, for listing alongside `name`.
+    pub fn submappers_label(&self) -> String {
+        if self.submappers.is_empty() {
+            return "all submappers".to_string();
         }
+
+        let submappers = self.submappers.iter().map(|submapper| submapper.to_string()).collect::<Vec<_>>();
+        format!("submappers {}", submappers.join(", "))
+    }
+}
+
+impl dyn Mapper {
+    pub fn for_rom(rom: &NESROM) -> Result<Box<dyn Mapper>, CartridgeError> {
+        let mapper_number = rom.header.mapper_number;
+        let info = SUPPORTED_MAPPERS
+            .iter()
+            .find(|info| info.number == mapper_number)
+            .ok_or(CartridgeError::UnsupportedMapper { mapper_number })?;
+
+        (info.build)(rom)
     }
 }
 
+/// Check that `rom` has at least `min_prg_banks` banks of PRG ROM and that PRG and CHR (either
+/// ROM, or the RAM the header asks for) are a whole number of banks. Mappers call this when
+/// they're built so their bank arithmetic can't underflow or divide by zero later on.
+pub(crate) fn check_rom_sizes(
+    rom: &NESROM,
+    prg_bank_size: usize,
+    min_prg_banks: usize,
+    chr_bank_size: usize
+) -> Result<(), CartridgeError> {
+    let prg_bytes = rom.prg_rom.len();
+    if prg_bytes < prg_bank_size * min_prg_banks || !prg_bytes.is_multiple_of(prg_bank_size) {
+        return Err(CartridgeError::InvalidPrgRomSize { bytes: prg_bytes });
+    }
+
+    let chr_bytes = if rom.chr_rom.is_empty() { rom.header.chr_ram_bytes as usize } else { rom.chr_rom.len() };
+    if chr_bytes == 0 || !chr_bytes.is_multiple_of(chr_bank_size) {
+        return Err(CartridgeError::InvalidChrSize { bytes: chr_bytes });
+    }
+
+    Ok(())
+}
+
 pub struct NullMapper {}
 
 impl NullMapper {
@@ -41,4 +136,9 @@ impl Mapper for NullMapper {
 
     fn ppu_read_u8(&self, _address: u16) -> u8 { 0 }
     fn ppu_write_u8(&mut self, _address: u16, _data: u8) {}
+
+    fn mirroring(&self) -> Mirroring { Mirroring::Horizontal }
+
+    fn save_state(&self, _state: &mut StateWriter) {}
+    fn load_state(&mut self, _state: &mut StateReader) -> Result<(), StateError> { Ok(()) }
 }
@@ -0,0 +1,76 @@
+use nestalgic_rom::nesrom::MirroringType;
+
+/// How the four nametables addressed by the PPU (`0x2000`-`0x2FFF`) map onto physical memory.
+///
+/// The console only has 2kb of nametable RAM, enough for two nametables, so each board picks
+/// which pair of nametables share memory:
+///
+/// ```text
+///  Horizontal   Vertical    Single Screen   Four Screen
+/// +---+---+    +---+---+    +---+---+       +---+---+
+/// | A | A |    | A | B |    | A | A |       | A | B |
+/// +---+---+    +---+---+    +---+---+       +---+---+
+/// | B | B |    | A | B |    | A | A |       | C | D |
+/// +---+---+    +---+---+    +---+---+       +---+---+
+/// ```
+///
+/// Four screen boards carry an extra 2kb of RAM on the cartridge for nametables `C` and `D`.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Mirroring#Nametable_Mirroring
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+
+    /// Every nametable maps to the first 1kb of nametable RAM
+    SingleScreenLower,
+
+    /// Every nametable maps to the second 1kb of nametable RAM
+    SingleScreenUpper,
+
+    FourScreen,
+}
+
+impl Mirroring {
+    /// The physical 1kb page (`0`-`3`) that nametable `address` maps to. Pages `0` and `1` are the
+    /// console's nametable RAM, pages `2` and `3` only exist on four screen boards.
+    pub fn nametable_page(&self, address: u16) -> usize {
+        let table = ((address as usize & 0x0FFF) / 0x400) % 4;
+
+        match self {
+            Mirroring::Horizontal => table >> 1,
+            Mirroring::Vertical => table & 1,
+            Mirroring::SingleScreenLower => 0,
+            Mirroring::SingleScreenUpper => 1,
+            Mirroring::FourScreen => table,
+        }
+    }
+}
+
+impl From<&MirroringType> for Mirroring {
+    fn from(mirroring_type: &MirroringType) -> Self {
+        match mirroring_type {
+            MirroringType::Horizontal => Mirroring::Horizontal,
+            MirroringType::Vertical => Mirroring::Vertical,
+            MirroringType::FourScreen => Mirroring::FourScreen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn nametable_page_follows_mirroring() {
+        let pages = |mirroring: Mirroring| {
+            [0x2000, 0x2400, 0x2800, 0x2C00, 0x3000]
+                .map(|address| mirroring.nametable_page(address))
+        };
+
+        assert_eq!(pages(Mirroring::Horizontal), [0, 0, 1, 1, 0]);
+        assert_eq!(pages(Mirroring::Vertical), [0, 1, 0, 1, 0]);
+        assert_eq!(pages(Mirroring::SingleScreenUpper), [1, 1, 1, 1, 1]);
+        assert_eq!(pages(Mirroring::FourScreen), [0, 1, 2, 3, 0]);
+    }
+}
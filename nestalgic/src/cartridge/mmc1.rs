@@ -0,0 +1,310 @@
+use nestalgic_rom::nesrom::NESROM;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `MMC1` emulates the Nintendo MMC1 (mapper 1), used by The Legend of Zelda, Metroid and
+/// hundreds of other games.
+///
+/// The CPU configures the MMC1 one bit at a time: Each write to `0x8000`-`0xFFFF` shifts bit 0
+/// into a 5-bit shift register. On the fifth write the value is copied into the internal register
+/// selected by bits 13 and 14 of the address:
+///
+/// ```text
+/// 7  bit  0
+/// ---- ----
+/// Rxxx xxxD
+/// |       |
+/// |       +- Data bit to be shifted into the shift register, LSB first
+/// +--------- 1: Reset the shift register and set the PRG ROM bank mode to 3
+///
+/// 0x8000-0x9FFF: Control
+/// 0xA000-0xBFFF: CHR bank 0
+/// 0xC000-0xDFFF: CHR bank 1
+/// 0xE000-0xFFFF: PRG bank
+/// ```
+///
+/// The 512kb SUROM variants which use the CHR bank registers to select PRG ROM are not supported.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/MMC1
+pub struct MMC1 {
+    pub prg_rom: Vec<u8>,
+
+    /// Either the CHR ROM from the cartridge or 8kb of CHR RAM if the cartridge has no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    /// Address space: `0x6000`-`0x7FFF`
+    pub prg_ram: Vec<u8>,
+
+    shift_register: u8,
+    shift_count: u8,
+
+    /// ```text
+    /// 4bit0
+    /// -----
+    /// CPPMM
+    /// |||||
+    /// |||++- Mirroring (0: one-screen, lower bank; 1: one-screen, upper bank;
+    /// |||               2: vertical; 3: horizontal)
+    /// |++--- PRG ROM bank mode (0, 1: switch 32kb at 0x8000, ignoring low bit of bank number;
+    /// |                         2: fix first bank at 0x8000 and switch 16kb bank at 0xC000;
+    /// |                         3: fix last bank at 0xC000 and switch 16kb bank at 0x8000)
+    /// +----- CHR ROM bank mode (0: switch 8kb at a time; 1: switch two separate 4kb banks)
+    /// ```
+    control: u8,
+
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+
+    /// ```text
+    /// 4bit0
+    /// -----
+    /// RPPPP
+    /// |||||
+    /// |++++- Select 16kb PRG ROM bank (low bit ignored in 32kb mode)
+    /// +----- PRG RAM chip enable (0: enabled; 1: disabled)
+    /// ```
+    prg_bank: u8,
+}
+
+impl MMC1 {
+    const PRG_BANK_SIZE: usize = 16 * 1024;
+    const CHR_BANK_SIZE: usize = 4 * 1024;
+
+    pub fn from_rom(rom: &NESROM) -> Result<MMC1, CartridgeError> {
+        check_rom_sizes(rom, MMC1::PRG_BANK_SIZE, 1, MMC1::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(MMC1 {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            prg_ram: vec![0; rom.header.prg_ram_bytes as usize],
+            shift_register: 0,
+            shift_count: 0,
+            control: 0b0_11_00,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0,
+        })
+    }
+
+    fn write_register(&mut self, address: u16, data: u8) {
+        if data & 0b1000_0000 != 0 {
+            self.shift_register = 0;
+            self.shift_count = 0;
+            self.control |= 0b0_11_00;
+            return;
+        }
+
+        self.shift_register |= (data & 0b0000_0001) << self.shift_count;
+        self.shift_count += 1;
+
+        if self.shift_count == 5 {
+            let value = self.shift_register;
+            match address {
+                0x8000..=0x9FFF => self.control = value,
+                0xA000..=0xBFFF => self.chr_bank_0 = value,
+                0xC000..=0xDFFF => self.chr_bank_1 = value,
+                _ => self.prg_bank = value,
+            }
+
+            self.shift_register = 0;
+            self.shift_count = 0;
+        }
+    }
+
+    fn prg_rom_address(&self, address: u16) -> usize {
+        let prg_bank = (self.prg_bank & 0b0_1111) as usize;
+        let last_bank = self.prg_rom.len() / MMC1::PRG_BANK_SIZE - 1;
+        let offset = address as usize & (MMC1::PRG_BANK_SIZE - 1);
+        let upper_half = address >= 0xC000;
+
+        let bank = match (self.control & 0b0_11_00) >> 2 {
+            0 | 1 => (prg_bank & !1) + upper_half as usize,
+            2 => if upper_half { prg_bank } else { 0 },
+            _ => if upper_half { last_bank } else { prg_bank },
+        };
+
+        (bank * MMC1::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        let offset = address as usize & (MMC1::CHR_BANK_SIZE - 1);
+        let upper_half = address >= 0x1000;
+
+        let bank = if self.control & 0b1_00_00 == 0 {
+            (self.chr_bank_0 & !1) as usize + upper_half as usize
+        } else if upper_half {
+            self.chr_bank_1 as usize
+        } else {
+            self.chr_bank_0 as usize
+        };
+
+        (bank * MMC1::CHR_BANK_SIZE + offset) % self.chr.len()
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_bank & 0b1_0000 == 0 && !self.prg_ram.is_empty()
+    }
+}
+
+impl Mapper for MMC1 {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xFFFF => self.prg_rom[self.prg_rom_address(address)],
+            0x6000..=0x7FFF if self.prg_ram_enabled() => self.prg_ram[(address as usize - 0x6000) % self.prg_ram.len()],
+            0x6000..=0x7FFF => 0,
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x5FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0xFFFF => self.write_register(address, data),
+            0x6000..=0x7FFF => if self.prg_ram_enabled() {
+                let prg_ram_address = (address as usize - 0x6000) % self.prg_ram.len();
+                self.prg_ram[prg_ram_address] = data
+            },
+            0x4020..=0x5FFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & 0b0_00_11 {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        }
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.prg_ram);
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.shift_register);
+        state.write_u8(self.shift_count);
+        state.write_u8(self.control);
+        state.write_u8(self.chr_bank_0);
+        state.write_u8(self.chr_bank_1);
+        state.write_u8(self.prg_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        state.read_memory(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.shift_register = state.read_u8()?;
+        self.shift_count = state.read_u8()?;
+        self.control = state.read_u8()?;
+        self.chr_bank_0 = state.read_u8()?;
+        self.chr_bank_1 = state.read_u8()?;
+        self.prg_bank = state.read_u8()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn mmc1(prg_banks: usize, chr_banks: usize) -> MMC1 {
+        MMC1::from_rom(&test_rom(1, MMC1::PRG_BANK_SIZE, prg_banks, MMC1::CHR_BANK_SIZE, chr_banks)).unwrap()
+    }
+
+    fn write_serial(mmc1: &mut MMC1, address: u16, value: u8) {
+        for bit in 0..5 {
+            mmc1.cpu_write_u8(address, (value >> bit) & 1);
+        }
+    }
+
+    #[test]
+    pub fn power_on_fixes_last_prg_bank_at_0xc000() {
+        let mmc1 = mmc1(8, 2);
+
+        assert_eq!(mmc1.cpu_read_u8(0x8000), 0);
+        assert_eq!(mmc1.cpu_read_u8(0xFFFC), 7);
+    }
+
+    #[test]
+    pub fn serial_writes_switch_prg_banks() {
+        let mut mmc1 = mmc1(8, 2);
+
+        write_serial(&mut mmc1, 0xE000, 5);
+        assert_eq!(mmc1.cpu_read_u8(0x8000), 5);
+        assert_eq!(mmc1.cpu_read_u8(0xC000), 7);
+
+        // 32kb mode ignores the low bit of the bank
+        write_serial(&mut mmc1, 0x8000, 0b0_00_00);
+        assert_eq!(mmc1.cpu_read_u8(0x8000), 4);
+        assert_eq!(mmc1.cpu_read_u8(0xC000), 5);
+    }
+
+    #[test]
+    pub fn reset_bit_discards_partial_writes() {
+        let mut mmc1 = mmc1(8, 2);
+
+        mmc1.cpu_write_u8(0xE000, 1);
+        mmc1.cpu_write_u8(0xE000, 1);
+        mmc1.cpu_write_u8(0xE000, 0b1000_0000);
+        write_serial(&mut mmc1, 0xE000, 2);
+
+        assert_eq!(mmc1.cpu_read_u8(0x8000), 2);
+    }
+
+    #[test]
+    pub fn chr_4kb_mode_switches_banks_independently() {
+        let mut mmc1 = mmc1(2, 8);
+
+        write_serial(&mut mmc1, 0x8000, 0b1_11_00);
+        write_serial(&mut mmc1, 0xA000, 3);
+        write_serial(&mut mmc1, 0xC000, 6);
+
+        assert_eq!(mmc1.ppu_read_u8(0x0000), 3);
+        assert_eq!(mmc1.ppu_read_u8(0x1000), 6);
+    }
+
+    #[test]
+    pub fn control_selects_mirroring() {
+        let mut mmc1 = mmc1(2, 2);
+
+        write_serial(&mut mmc1, 0x8000, 0b0_11_10);
+        assert_eq!(mmc1.mirroring(), Mirroring::Vertical);
+
+        write_serial(&mut mmc1, 0x8000, 0b0_11_11);
+        assert_eq!(mmc1.mirroring(), Mirroring::Horizontal);
+
+        write_serial(&mut mmc1, 0x8000, 0b0_11_01);
+        assert_eq!(mmc1.mirroring(), Mirroring::SingleScreenUpper);
+    }
+}
@@ -0,0 +1,353 @@
+use nestalgic_rom::nesrom::{MirroringType, NESROM};
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `MMC3` emulates the Nintendo MMC3 (mapper 4), used by Super Mario Bros. 3, Kirby's Adventure
+/// and many other late NES games.
+///
+/// The MMC3 has 8 bank registers (`R0`-`R7`) which are written by first selecting a register
+/// with an even address in `0x8000`-`0x9FFF` and then writing the bank with an odd address:
+///
+/// ```text
+/// 0x8000 (even): Bank select    0x8001 (odd): Bank data
+/// 0xA000 (even): Mirroring      0xA001 (odd): PRG RAM protect
+/// 0xC000 (even): IRQ latch      0xC001 (odd): IRQ reload
+/// 0xE000 (even): IRQ disable    0xE001 (odd): IRQ enable
+/// ```
+///
+/// It also contains a scanline counter which is clocked by rises of PPU address line A12. While
+/// rendering with backgrounds at `0x0000` and sprites at `0x1000` this happens once per scanline.
+///
+/// Four-screen mirroring is not supported yet.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/MMC3
+pub struct MMC3 {
+    pub prg_rom: Vec<u8>,
+
+    /// Either the CHR ROM from the cartridge or 8kb of CHR RAM if the cartridge has no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    /// Address space: `0x6000`-`0x7FFF`
+    pub prg_ram: Vec<u8>,
+
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// CPxx xRRR
+    /// ||    |||
+    /// ||    +++- Bank register to update on the next write to Bank data
+    /// |+-------- PRG ROM bank mode (0: 0x8000 swappable, 0xC000 fixed to second-last bank;
+    /// |                             1: 0xC000 swappable, 0x8000 fixed to second-last bank)
+    /// +--------- CHR A12 inversion (0: two 2kb banks at 0x0000, four 1kb banks at 0x1000;
+    ///                               1: two 2kb banks at 0x1000, four 1kb banks at 0x0000)
+    /// ```
+    bank_select: u8,
+    bank_registers: [u8; 8],
+
+    horizontal_mirroring: bool,
+
+    prg_ram_enabled: bool,
+    prg_ram_write_protected: bool,
+
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+}
+
+impl MMC3 {
+    const PRG_BANK_SIZE: usize = 8 * 1024;
+    const CHR_BANK_SIZE: usize = 1024;
+
+    pub fn from_rom(rom: &NESROM) -> Result<MMC3, CartridgeError> {
+        // The last two banks are fixed so there must be at least two of them
+        check_rom_sizes(rom, MMC3::PRG_BANK_SIZE, 2, MMC3::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(MMC3 {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            prg_ram: vec![0; rom.header.prg_ram_bytes as usize],
+            bank_select: 0,
+            bank_registers: [0; 8],
+            horizontal_mirroring: rom.header.mirroring_type == MirroringType::Horizontal,
+            prg_ram_enabled: true,
+            prg_ram_write_protected: false,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+        })
+    }
+
+    fn write_register(&mut self, address: u16, data: u8) {
+        let even = address & 1 == 0;
+
+        match (address, even) {
+            (0x8000..=0x9FFF, true) => self.bank_select = data,
+            (0x8000..=0x9FFF, false) => self.bank_registers[(self.bank_select & 0b111) as usize] = data,
+            (0xA000..=0xBFFF, true) => self.horizontal_mirroring = data & 1 != 0,
+            (0xA000..=0xBFFF, false) => {
+                self.prg_ram_enabled = data & 0b1000_0000 != 0;
+                self.prg_ram_write_protected = data & 0b0100_0000 != 0;
+            },
+            (0xC000..=0xDFFF, true) => self.irq_latch = data,
+            (0xC000..=0xDFFF, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            },
+            (_, true) => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            },
+            (_, false) => self.irq_enabled = true,
+        }
+    }
+
+    fn prg_rom_address(&self, address: u16) -> usize {
+        let bank_count = self.prg_rom.len() / MMC3::PRG_BANK_SIZE;
+        let second_last = bank_count - 2;
+        let swap_fixed = self.bank_select & 0b0100_0000 != 0;
+        let r6 = self.bank_registers[6] as usize;
+        let r7 = self.bank_registers[7] as usize;
+
+        let bank = match (address, swap_fixed) {
+            (0x8000..=0x9FFF, false) => r6,
+            (0x8000..=0x9FFF, true) => second_last,
+            (0xA000..=0xBFFF, _) => r7,
+            (0xC000..=0xDFFF, false) => second_last,
+            (0xC000..=0xDFFF, true) => r6,
+            _ => bank_count - 1,
+        };
+
+        let offset = address as usize & (MMC3::PRG_BANK_SIZE - 1);
+        (bank * MMC3::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        let inverted = self.bank_select & 0b1000_0000 != 0;
+        let address = if inverted { address ^ 0x1000 } else { address };
+
+        let bank = match address {
+            0x0000..=0x03FF => self.bank_registers[0] & !1,
+            0x0400..=0x07FF => self.bank_registers[0] | 1,
+            0x0800..=0x0BFF => self.bank_registers[1] & !1,
+            0x0C00..=0x0FFF => self.bank_registers[1] | 1,
+            0x1000..=0x13FF => self.bank_registers[2],
+            0x1400..=0x17FF => self.bank_registers[3],
+            0x1800..=0x1BFF => self.bank_registers[4],
+            _ => self.bank_registers[5],
+        } as usize;
+
+        let offset = address as usize & (MMC3::CHR_BANK_SIZE - 1);
+        (bank * MMC3::CHR_BANK_SIZE + offset) % self.chr.len()
+    }
+}
+
+impl Mapper for MMC3 {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xFFFF => self.prg_rom[self.prg_rom_address(address)],
+            0x6000..=0x7FFF if self.prg_ram_enabled && !self.prg_ram.is_empty() => self.prg_ram[(address as usize - 0x6000) % self.prg_ram.len()],
+            0x6000..=0x7FFF => 0,
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x5FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0xFFFF => self.write_register(address, data),
+            0x6000..=0x7FFF => if self.prg_ram_enabled && !self.prg_ram_write_protected && !self.prg_ram.is_empty() {
+                let prg_ram_address = (address as usize - 0x6000) % self.prg_ram.len();
+                self.prg_ram[prg_ram_address] = data
+            },
+            0x4020..=0x5FFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_a12_rise(&mut self) {
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+
+    fn irq_pending(&mut self) -> bool {
+        self.irq_pending
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        if self.horizontal_mirroring {
+            Mirroring::Horizontal
+        } else {
+            Mirroring::Vertical
+        }
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.prg_ram);
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.bank_select);
+        state.write_memory(&self.bank_registers);
+        state.write_bool(self.horizontal_mirroring);
+        state.write_bool(self.prg_ram_enabled);
+        state.write_bool(self.prg_ram_write_protected);
+
+        state.write_u8(self.irq_latch);
+        state.write_u8(self.irq_counter);
+        state.write_bool(self.irq_reload);
+        state.write_bool(self.irq_enabled);
+        state.write_bool(self.irq_pending);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        state.read_memory(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.bank_select = state.read_u8()?;
+        state.read_memory(&mut self.bank_registers)?;
+        self.horizontal_mirroring = state.read_bool()?;
+        self.prg_ram_enabled = state.read_bool()?;
+        self.prg_ram_write_protected = state.read_bool()?;
+
+        self.irq_latch = state.read_u8()?;
+        self.irq_counter = state.read_u8()?;
+        self.irq_reload = state.read_bool()?;
+        self.irq_enabled = state.read_bool()?;
+        self.irq_pending = state.read_bool()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn mmc3(prg_banks: usize, chr_banks: usize) -> MMC3 {
+        MMC3::from_rom(&mmc3_rom(prg_banks, chr_banks)).unwrap()
+    }
+
+    fn mmc3_rom(prg_banks: usize, chr_banks: usize) -> NESROM {
+        test_rom(4, MMC3::PRG_BANK_SIZE, prg_banks, MMC3::CHR_BANK_SIZE, chr_banks)
+    }
+
+    #[test]
+    pub fn from_rom_rejects_prg_rom_without_two_fixed_banks() {
+        assert_eq!(MMC3::from_rom(&mmc3_rom(1, 8)).err(), Some(CartridgeError::InvalidPrgRomSize { bytes: 8192 }));
+        assert_eq!(MMC3::from_rom(&mmc3_rom(0, 8)).err(), Some(CartridgeError::InvalidPrgRomSize { bytes: 0 }));
+    }
+
+    #[test]
+    pub fn prg_bank_mode_swaps_fixed_bank() {
+        let mut mmc3 = mmc3(16, 8);
+        mmc3.cpu_write_u8(0x8000, 6);
+        mmc3.cpu_write_u8(0x8001, 3);
+
+        assert_eq!(mmc3.cpu_read_u8(0x8000), 3);
+        assert_eq!(mmc3.cpu_read_u8(0xC000), 14);
+        assert_eq!(mmc3.cpu_read_u8(0xE000), 15);
+
+        mmc3.cpu_write_u8(0x8000, 0b0100_0110);
+        assert_eq!(mmc3.cpu_read_u8(0x8000), 14);
+        assert_eq!(mmc3.cpu_read_u8(0xC000), 3);
+    }
+
+    #[test]
+    pub fn chr_a12_inversion_swaps_pattern_tables() {
+        let mut mmc3 = mmc3(4, 16);
+        mmc3.cpu_write_u8(0x8000, 0);
+        mmc3.cpu_write_u8(0x8001, 4);
+        mmc3.cpu_write_u8(0x8000, 2);
+        mmc3.cpu_write_u8(0x8001, 9);
+
+        assert_eq!(mmc3.ppu_read_u8(0x0400), 5);
+        assert_eq!(mmc3.ppu_read_u8(0x1000), 9);
+
+        mmc3.cpu_write_u8(0x8000, 0b1000_0000);
+        assert_eq!(mmc3.ppu_read_u8(0x1400), 5);
+        assert_eq!(mmc3.ppu_read_u8(0x0000), 9);
+    }
+
+    #[test]
+    pub fn irq_fires_after_latch_plus_one_rises() {
+        let mut mmc3 = mmc3(4, 8);
+        mmc3.cpu_write_u8(0xC000, 2);
+        mmc3.cpu_write_u8(0xC001, 0);
+        mmc3.cpu_write_u8(0xE001, 0);
+
+        mmc3.ppu_a12_rise(); // Reload to 2
+        mmc3.ppu_a12_rise(); // 1
+        assert!(!mmc3.irq_pending());
+
+        mmc3.ppu_a12_rise(); // 0
+        assert!(mmc3.irq_pending());
+
+        mmc3.cpu_write_u8(0xE000, 0);
+        assert!(!mmc3.irq_pending());
+    }
+
+    #[test]
+    pub fn load_state_restores_banks_ram_and_irq_counter() {
+        let mut saved = mmc3(16, 8);
+        saved.cpu_write_u8(0x8000, 6);
+        saved.cpu_write_u8(0x8001, 3);
+        saved.cpu_write_u8(0x6000, 0x42);
+        saved.cpu_write_u8(0xC000, 1);
+        saved.cpu_write_u8(0xC001, 0);
+        saved.cpu_write_u8(0xE001, 0);
+        saved.ppu_a12_rise(); // Reload to 1
+
+        let mut state = StateWriter::new();
+        saved.save_state(&mut state);
+        let state = state.into_bytes();
+
+        let mut loaded = mmc3(16, 8);
+        loaded.load_state(&mut StateReader::new(&state)).expect("failed to load state");
+
+        assert_eq!(loaded.cpu_read_u8(0x8000), 3);
+        assert_eq!(loaded.cpu_read_u8(0x6000), 0x42);
+
+        loaded.ppu_a12_rise(); // 0
+        assert!(loaded.irq_pending());
+    }
+}
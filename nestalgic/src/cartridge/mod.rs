@@ -1,21 +1,132 @@
 mod nrom;
+mod mmc1;
+mod mmc3;
+mod vrc6;
+mod fme7;
+mod discrete;
+mod namco108;
 mod mapper;
+mod mirroring;
+mod error;
 
 use mapper::Mapper;
+pub use mapper::{MapperInfo, SUPPORTED_MAPPERS};
+pub use mirroring::Mirroring;
+pub use error::CartridgeError;
 pub use nrom::NROM;
+pub use mmc1::MMC1;
+pub use mmc3::MMC3;
+pub use vrc6::VRC6;
+pub use fme7::FME7;
+pub use discrete::{DiscreteBoard, BankLatch};
+pub use namco108::Namco108;
 use nestalgic_rom::nesrom::NESROM;
+use crate::save_state::{StateError, StateReader, StateWriter};
 
 pub struct Cartridge {
     pub rom: NESROM,
-    pub mapper: Box<dyn Mapper>
+    pub mapper: Box<dyn Mapper>,
+
+    /// Nametables `C` and `D` for boards using `Mirroring::FourScreen`. Other boards ignore this.
+    pub four_screen_ram: [u8; 2048],
+
+    /// The last value of PPU address line A12
+    ppu_a12: bool,
+
+    /// How many CPU cycles PPU address line A12 has been low for
+    ppu_a12_low_cycles: u32,
 }
 
 impl Cartridge {
-    pub fn from_rom(rom: NESROM) -> Cartridge {
-        let mapper = <dyn Mapper>::for_rom(&rom);
-        Cartridge {
+    pub fn from_rom(rom: NESROM) -> Result<Cartridge, CartridgeError> {
+        let mapper = <dyn Mapper>::for_rom(&rom)?;
+        Ok(Cartridge {
             rom,
-            mapper
+            mapper,
+            four_screen_ram: [0; 2048],
+            ppu_a12: false,
+            ppu_a12_low_cycles: 0,
+        })
+    }
+
+    /// Track PPU address line A12 so mappers can count scanlines.
+    ///
+    /// A12 drops briefly during every nametable fetch so, like the MMC3, we ignore rises unless A12
+    /// has been low for at least `A12_FILTER_CPU_CYCLES`.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/MMC3#IRQ_Specifics
+    pub fn observe_ppu_address(&mut self, address: u16) {
+        let a12 = address & 0x1000 != 0;
+
+        if a12 && !self.ppu_a12 && self.ppu_a12_low_cycles >= Cartridge::A12_FILTER_CPU_CYCLES {
+            self.mapper.ppu_a12_rise();
+        }
+
+        if !a12 && self.ppu_a12 {
+            self.ppu_a12_low_cycles = 0;
+        }
+
+        self.ppu_a12 = a12;
+    }
+
+    /// Called once per CPU cycle.
+    pub fn cpu_cycle(&mut self) {
+        self.mapper.cpu_clock();
+
+        if !self.ppu_a12 {
+            self.ppu_a12_low_cycles = self.ppu_a12_low_cycles.saturating_add(1);
         }
     }
+
+    /// Write the mapper's registers and RAM along with the cartridge's own state.
+    pub fn save_state(&self, state: &mut StateWriter) {
+        self.mapper.save_state(state);
+        state.write_memory(&self.four_screen_ram);
+        state.write_bool(self.ppu_a12);
+        state.write_u32(self.ppu_a12_low_cycles);
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        self.mapper.load_state(state)?;
+        state.read_memory(&mut self.four_screen_ram)?;
+        self.ppu_a12 = state.read_bool()?;
+        self.ppu_a12_low_cycles = state.read_u32()?;
+
+        Ok(())
+    }
+
+    const A12_FILTER_CPU_CYCLES: u32 = 3;
+}
+
+/// Build a ROM for mapper tests with each bank filled with its own index, so tests can tell which
+/// bank is mapped. Without any CHR banks the cartridge gets 8kb of CHR RAM instead.
+#[cfg(test)]
+pub(crate) fn test_rom(
+    mapper_number: u16,
+    prg_bank_size: usize,
+    prg_banks: usize,
+    chr_bank_size: usize,
+    chr_banks: usize
+) -> NESROM {
+    use nestalgic_rom::nesrom::{FileType, Header, MirroringType};
+
+    let prg_rom = (0..prg_banks).flat_map(|b| vec![b as u8; prg_bank_size]).collect::<Vec<u8>>();
+    let chr_rom = (0..chr_banks).flat_map(|b| vec![b as u8; chr_bank_size]).collect::<Vec<u8>>();
+
+    NESROM {
+        header: Header {
+            file_type: FileType::INES,
+            prg_rom_bytes: prg_rom.len() as u32,
+            chr_rom_bytes: chr_rom.len() as u32,
+            prg_ram_bytes: 8192,
+            chr_ram_bytes: if chr_rom.is_empty() { 8192 } else { 0 },
+            mirroring_type: MirroringType::Vertical,
+            has_persistent_memory: false,
+            has_trainer: false,
+            mapper_number,
+        },
+        trainer: None,
+        prg_rom,
+        chr_rom,
+    }
 }
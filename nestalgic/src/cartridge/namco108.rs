@@ -0,0 +1,193 @@
+use nestalgic_rom::nesrom::NESROM;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `Namco108` emulates the Namco 108 family of boards (mapper 206), used by early Namco and Tengen
+/// releases such as Pac-Mania, Karnov and Gauntlet.
+///
+/// The Namco 108 is the predecessor of the MMC3 and banks memory the same way as an MMC3 with both
+/// mode bits clear, but without mirroring control, PRG RAM or an IRQ counter:
+///
+/// ```text
+/// 0x8000-0x9FFF (even): Bank select
+/// 0x8000-0x9FFF (odd):  Bank data
+///
+/// 0x0000-0x07FF: 2kb CHR bank R0     0x8000-0x9FFF: 8kb PRG bank R6
+/// 0x0800-0x0FFF: 2kb CHR bank R1     0xA000-0xBFFF: 8kb PRG bank R7
+/// 0x1000-0x1FFF: 1kb CHR banks R2-R5 0xC000-0xFFFF: Fixed to the last 16kb
+/// ```
+///
+/// See also: https://wiki.nesdev.com/w/index.php/INES_Mapper_206
+pub struct Namco108 {
+    pub prg_rom: Vec<u8>,
+    /// Either the CHR ROM from the cartridge or CHR RAM sized by the header if the cartridge has
+    /// no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    mirroring: Mirroring,
+
+    bank_select: u8,
+    bank_registers: [u8; 8],
+}
+
+impl Namco108 {
+    const PRG_BANK_SIZE: usize = 8 * 1024;
+    const CHR_BANK_SIZE: usize = 1024;
+
+    pub fn from_rom(rom: &NESROM) -> Result<Namco108, CartridgeError> {
+        check_rom_sizes(rom, Namco108::PRG_BANK_SIZE, 2, Namco108::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(Namco108 {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            mirroring: Mirroring::from(&rom.header.mirroring_type),
+            bank_select: 0,
+            bank_registers: [0; 8],
+        })
+    }
+
+    fn prg_rom_address(&self, address: u16) -> usize {
+        let bank_count = self.prg_rom.len() / Namco108::PRG_BANK_SIZE;
+
+        let bank = match address {
+            0x8000..=0x9FFF => (self.bank_registers[6] & 0b0000_1111) as usize,
+            0xA000..=0xBFFF => (self.bank_registers[7] & 0b0000_1111) as usize,
+            0xC000..=0xDFFF => bank_count - 2,
+            _ => bank_count - 1,
+        };
+
+        let offset = address as usize & (Namco108::PRG_BANK_SIZE - 1);
+        (bank * Namco108::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        let bank = match address {
+            0x0000..=0x03FF => self.bank_registers[0] & !1,
+            0x0400..=0x07FF => self.bank_registers[0] | 1,
+            0x0800..=0x0BFF => self.bank_registers[1] & !1,
+            0x0C00..=0x0FFF => self.bank_registers[1] | 1,
+            0x1000..=0x13FF => self.bank_registers[2],
+            0x1400..=0x17FF => self.bank_registers[3],
+            0x1800..=0x1BFF => self.bank_registers[4],
+            _ => self.bank_registers[5],
+        } & 0b0011_1111;
+
+        let offset = address as usize & (Namco108::CHR_BANK_SIZE - 1);
+        (bank as usize * Namco108::CHR_BANK_SIZE + offset) % self.chr.len()
+    }
+}
+
+impl Mapper for Namco108 {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xFFFF => self.prg_rom[self.prg_rom_address(address)],
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x7FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0x9FFF if address & 1 == 0 => self.bank_select = data & 0b0000_0111,
+            0x8000..=0x9FFF => self.bank_registers[self.bank_select as usize] = data,
+            0x4020..=0x7FFF | 0xA000..=0xFFFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.bank_select);
+        state.write_memory(&self.bank_registers);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.bank_select = state.read_u8()?;
+        state.read_memory(&mut self.bank_registers)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn namco108(chr_banks: usize) -> Namco108 {
+        Namco108::from_rom(&test_rom(206, Namco108::PRG_BANK_SIZE, 8, Namco108::CHR_BANK_SIZE, chr_banks)).unwrap()
+    }
+
+    #[test]
+    pub fn prg_banks_switch_below_0xc000() {
+        let mut namco108 = namco108(64);
+        namco108.cpu_write_u8(0x8000, 6);
+        namco108.cpu_write_u8(0x8001, 3);
+        namco108.cpu_write_u8(0x8000, 7);
+        namco108.cpu_write_u8(0x8001, 5);
+
+        assert_eq!(namco108.cpu_read_u8(0x8000), 3);
+        assert_eq!(namco108.cpu_read_u8(0xA000), 5);
+        assert_eq!(namco108.cpu_read_u8(0xC000), 6);
+        assert_eq!(namco108.cpu_read_u8(0xE000), 7);
+    }
+
+    #[test]
+    pub fn mode_bits_are_ignored() {
+        let mut namco108 = namco108(64);
+        namco108.cpu_write_u8(0x8000, 0b1100_0000);
+        namco108.cpu_write_u8(0x8001, 8);
+        namco108.cpu_write_u8(0x8000, 0b1100_0010);
+        namco108.cpu_write_u8(0x8001, 40);
+
+        assert_eq!(namco108.ppu_read_u8(0x0000), 8);
+        assert_eq!(namco108.ppu_read_u8(0x0400), 9);
+        assert_eq!(namco108.ppu_read_u8(0x1000), 40);
+    }
+
+    #[test]
+    pub fn chr_ram_writes_land_in_the_selected_bank() {
+        let mut namco108 = namco108(0);
+        namco108.cpu_write_u8(0x8000, 2); // Select R2, the 1kb bank at 0x1000
+        namco108.cpu_write_u8(0x8001, 3);
+        namco108.ppu_write_u8(0x1010, 0xAB);
+
+        assert_eq!(namco108.ppu_read_u8(0x1010), 0xAB);
+        assert_eq!(namco108.chr[3 * Namco108::CHR_BANK_SIZE + 0x10], 0xAB);
+    }
+}
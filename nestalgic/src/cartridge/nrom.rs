@@ -1,5 +1,7 @@
 use nestalgic_rom::nesrom::NESROM;
-use super::Mapper;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
 
 pub struct NROM {
     /// In NROM-256 the `prg_rom` is 32kb, for NROM-128 the `prg_rom` is only 16kb and will be
@@ -11,18 +13,20 @@ pub struct NROM {
     /// Address Space: `0xC000`-`0xFFFF` (Last 16kb or mirror of first 16kb)
     pub prg_rom_bank_2: [u8; 16 * 1024],
 
-    /// 2kb mirrored 4 times
+    /// Only present on a few boards (e.g. Family BASIC). Mirrored to fill the address space.
     ///
     /// Address space:
     ///
     /// - `0x6000`-`0x7FFF`
     ///
-    pub prg_ram: [u8; 2048],
+    pub prg_ram: Vec<u8>,
 
-    pub chr_ram: [u8; 8 * 1024],
+    /// 8kb of character rom, or character ram sized by the header for boards without character rom
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
 
-    pub nametable_1: [u8; 1024],
-    pub nametable_2: [u8; 1024],
+    /// Fixed by the cartridge's solder pads
+    pub mirroring: Mirroring,
 }
 
 impl NROM {
@@ -30,15 +34,22 @@ impl NROM {
         NROM {
             prg_rom_bank_1: [0; 16 * 1024],
             prg_rom_bank_2: [0; 16 * 1024],
-            prg_ram: [0; 2048],
-            chr_ram: [0; 8 * 1024],
-            nametable_1: [0; 1024],
-            nametable_2: [0; 1024]
+            prg_ram: Vec::new(),
+            chr: vec![0; 8 * 1024],
+            chr_is_ram: true,
+            mirroring: Mirroring::Vertical,
         }
     }
 
-    pub fn from_rom(rom: &NESROM) -> NROM {
+    pub fn from_rom(rom: &NESROM) -> Result<NROM, CartridgeError> {
+        check_rom_sizes(rom, 16 * 1024, 1, 8 * 1024)?;
+        if rom.prg_rom.len() > 32 * 1024 {
+            return Err(CartridgeError::InvalidPrgRomSize { bytes: rom.prg_rom.len() });
+        }
+
         let mut nrom = NROM::empty();
+        nrom.mirroring = Mirroring::from(&rom.header.mirroring_type);
+        nrom.prg_ram = vec![0; rom.header.prg_ram_bytes as usize];
 
         if rom.prg_rom.len() <= 16 * 1024 {
             nrom.prg_rom_bank_1[0..rom.prg_rom.len()].copy_from_slice(&rom.prg_rom[..]);
@@ -48,10 +59,14 @@ impl NROM {
             nrom.prg_rom_bank_2[0..16 * 1024].copy_from_slice(&rom.prg_rom[16 * 1024..rom.prg_rom.len()]);
         };
 
-        // TODO: Support bigger chr_ram
-        nrom.chr_ram.copy_from_slice(&rom.chr_rom[0..8 * 1024]);
+        nrom.chr_is_ram = rom.chr_rom.is_empty();
+        nrom.chr = if nrom.chr_is_ram {
+            vec![0; rom.header.chr_ram_bytes as usize]
+        } else {
+            rom.chr_rom.clone()
+        };
 
-        nrom
+        Ok(nrom)
     }
 }
 
@@ -60,18 +75,20 @@ impl Mapper for NROM {
         match address {
             0x8000..=0xBFFF => self.prg_rom_bank_1[address as usize - 0x8000],
             0xC000..=0xFFFF => self.prg_rom_bank_2[address as usize - 0xC000],
-            0x6000..=0x7FFF => self.prg_ram[address as usize - 0x6000],
-            _ => {
-                panic!("attempt to cpu_read from unmapped address {:04X}", address);
-                0
-            }
+            0x6000..=0x7FFF if !self.prg_ram.is_empty() => self.prg_ram[(address as usize - 0x6000) % self.prg_ram.len()],
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x7FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
         }
     }
 
     fn cpu_write_u8(&mut self, address: u16, data: u8) {
         match address {
-            0x6000..=0x7FFF => self.prg_ram[address as usize - 0x6000] = data,
-            0x8000..=0xFFFF => {},
+            0x6000..=0x7FFF if !self.prg_ram.is_empty() => {
+                let prg_ram_address = (address as usize - 0x6000) % self.prg_ram.len();
+                self.prg_ram[prg_ram_address] = data
+            },
+            0x4020..=0xFFFF => {},
             _ => {
                 panic!("attempt to cpu_write to unmapped address {:04X}", address)
             }
@@ -80,12 +97,7 @@ impl Mapper for NROM {
 
     fn ppu_read_u8(&self, address: u16) -> u8 {
         match address {
-            0x0000..=0x1FFF => self.chr_ram[address as usize],
-            0x2000..=0x23FF => self.nametable_1[(address - 0x2000) as usize],
-            0x2400..=0x27FF => self.nametable_2[(address - 0x2400)as usize],
-            0x2800..=0x2BFF => self.nametable_1[(address - 0x2800)as usize],
-            0x2C00..=0x2FFF => self.nametable_2[(address - 0x2C00)as usize],
-            0x3000..=0x3EFF => self.ppu_read_u8(address & 0x2FFF),
+            0x0000..=0x1FFF => self.chr[address as usize % self.chr.len()],
             0x3F00..=0x3F1F => 0,
             0x3F20..=0x3FFF => self.ppu_read_u8(address & 0x3F1F),
             _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
@@ -94,15 +106,33 @@ impl Mapper for NROM {
 
     fn ppu_write_u8(&mut self, address: u16, data: u8) {
         match address {
-            0x0000..=0x1FFF => self.chr_ram[address as usize] = data,
-            0x2000..=0x23FF => self.nametable_1[(address - 0x2000) as usize] = data,
-            0x2400..=0x27FF => self.nametable_2[(address - 0x2400)as usize] = data,
-            0x2800..=0x2BFF => self.nametable_1[(address - 0x2800)as usize] = data,
-            0x2C00..=0x2FFF => self.nametable_2[(address - 0x2C00)as usize] = data,
-            0x3000..=0x3EFF => self.ppu_write_u8(address & 0x2FFF, data),
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = address as usize % self.chr.len();
+                self.chr[chr_address] = data
+            },
             0x3F00..=0x3F1F => println!("palette ram write"),
             0x3F20..=0x3FFF => self.ppu_write_u8(address & 0x3F1F, data),
             _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
         }
     }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.prg_ram);
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        state.read_memory(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        Ok(())
+    }
 }
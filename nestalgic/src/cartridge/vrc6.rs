@@ -0,0 +1,372 @@
+use nestalgic_rom::nesrom::NESROM;
+use super::{CartridgeError, Mapper, Mirroring};
+use super::mapper::check_rom_sizes;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// `VRC6` emulates the Konami VRC6 (mappers 24 and 26), used by Akumajou Densetsu, Madara and
+/// Esper Dream 2.
+///
+/// The two mappers are the same chip wired differently: Mapper 26 swaps address lines A0 and A1,
+/// so we swap them back before decoding the registers.
+///
+/// ```text
+/// 0x8000-0x8003: 16kb PRG ROM bank at 0x8000
+/// 0xB003:        PPU banking mode, mirroring and PRG RAM enable
+/// 0xC000-0xC003: 8kb PRG ROM bank at 0xC000
+/// 0xD000-0xD003: 1kb CHR banks R0-R3
+/// 0xE000-0xE003: 1kb CHR banks R4-R7
+/// 0xF000:        IRQ latch
+/// 0xF001:        IRQ control
+/// 0xF002:        IRQ acknowledge
+/// ```
+///
+/// Only PPU banking mode 0 (eight 1kb CHR banks) is supported, which covers every released game.
+/// The expansion audio registers are ignored.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/VRC6
+pub struct VRC6 {
+    pub prg_rom: Vec<u8>,
+    /// Either the CHR ROM from the cartridge or CHR RAM sized by the header if the cartridge has
+    /// no CHR ROM
+    pub chr: Vec<u8>,
+    pub chr_is_ram: bool,
+
+    /// Address space: `0x6000`-`0x7FFF`
+    pub prg_ram: Vec<u8>,
+
+    swap_address_lines: bool,
+
+    prg_bank_16kb: u8,
+    prg_bank_8kb: u8,
+    chr_banks: [u8; 8],
+
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// Wx.. MMxx
+    /// |    ||
+    /// |    ++--- Mirroring (0: vertical; 1: horizontal; 2: one-screen, lower; 3: one-screen, upper)
+    /// +--------- PRG RAM enable
+    /// ```
+    banking_control: u8,
+
+    irq: VRCIrq,
+}
+
+impl VRC6 {
+    const PRG_BANK_SIZE: usize = 8 * 1024;
+    const CHR_BANK_SIZE: usize = 1024;
+
+    /// Create a VRC6 for `rom`. Mapper 26 ROMs will have their address lines swapped.
+    pub fn from_rom(rom: &NESROM) -> Result<VRC6, CartridgeError> {
+        check_rom_sizes(rom, VRC6::PRG_BANK_SIZE, 2, VRC6::CHR_BANK_SIZE)?;
+
+        let chr_is_ram = rom.chr_rom.is_empty();
+        let chr = if chr_is_ram { vec![0; rom.header.chr_ram_bytes as usize] } else { rom.chr_rom.clone() };
+
+        Ok(VRC6 {
+            prg_rom: rom.prg_rom.clone(),
+            chr,
+            chr_is_ram,
+            prg_ram: vec![0; rom.header.prg_ram_bytes as usize],
+            swap_address_lines: rom.header.mapper_number == 26,
+            prg_bank_16kb: 0,
+            prg_bank_8kb: 0,
+            chr_banks: [0; 8],
+            banking_control: 0,
+            irq: VRCIrq::default(),
+        })
+    }
+
+    fn write_register(&mut self, address: u16, data: u8) {
+        let address = if self.swap_address_lines {
+            (address & !0b11) | ((address & 0b01) << 1) | ((address & 0b10) >> 1)
+        } else {
+            address
+        };
+
+        match address & 0xF003 {
+            0x8000..=0x8003 => self.prg_bank_16kb = data & 0b0000_1111,
+            0xB003 => self.banking_control = data,
+            0xC000..=0xC003 => self.prg_bank_8kb = data & 0b0001_1111,
+            0xD000..=0xD003 => self.chr_banks[(address & 0b11) as usize] = data,
+            0xE000..=0xE003 => self.chr_banks[4 + (address & 0b11) as usize] = data,
+            0xF000 => self.irq.latch = data,
+            0xF001 => self.irq.write_control(data),
+            0xF002 => self.irq.acknowledge(),
+            _ => {} // Expansion audio
+        }
+    }
+
+    fn prg_rom_address(&self, address: u16) -> usize {
+        let last_bank = self.prg_rom.len() / VRC6::PRG_BANK_SIZE - 1;
+        let bank = match address {
+            0x8000..=0xBFFF => self.prg_bank_16kb as usize * 2 + ((address as usize >> 13) & 1),
+            0xC000..=0xDFFF => self.prg_bank_8kb as usize,
+            _ => last_bank,
+        };
+
+        let offset = address as usize & (VRC6::PRG_BANK_SIZE - 1);
+        (bank * VRC6::PRG_BANK_SIZE + offset) % self.prg_rom.len()
+    }
+
+    fn chr_address(&self, address: u16) -> usize {
+        let bank = self.chr_banks[(address as usize >> 10) & 0b111] as usize;
+        let offset = address as usize & (VRC6::CHR_BANK_SIZE - 1);
+        (bank * VRC6::CHR_BANK_SIZE + offset) % self.chr.len()
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.banking_control & 0b1000_0000 != 0 && !self.prg_ram.is_empty()
+    }
+}
+
+impl Mapper for VRC6 {
+    fn cpu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0xFFFF => self.prg_rom[self.prg_rom_address(address)],
+            0x6000..=0x7FFF if self.prg_ram_enabled() => self.prg_ram[(address as usize - 0x6000) % self.prg_ram.len()],
+            0x6000..=0x7FFF => 0,
+            // Nothing is connected here so we get open bus. TODO: Return the last value on the data bus
+            0x4020..=0x5FFF => 0,
+            _ => panic!("attempt to cpu_read from unmapped address {:04X}", address)
+        }
+    }
+
+    fn cpu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x8000..=0xFFFF => self.write_register(address, data),
+            0x6000..=0x7FFF => if self.prg_ram_enabled() {
+                let prg_ram_address = (address as usize - 0x6000) % self.prg_ram.len();
+                self.prg_ram[prg_ram_address] = data
+            },
+            0x4020..=0x5FFF => {},
+            _ => panic!("attempt to cpu_write to unmapped address {:04X}", address)
+        }
+    }
+
+    fn ppu_read_u8(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=0x1FFF => self.chr[self.chr_address(address)],
+            _ => panic!("attempt to ppu_read from unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn ppu_write_u8(&mut self, address: u16, data: u8) {
+        match address {
+            0x0000..=0x1FFF => if self.chr_is_ram {
+                let chr_address = self.chr_address(address);
+                self.chr[chr_address] = data
+            },
+            _ => panic!("attempt to ppu_write to unmapped address 0x{:04X}", address)
+        }
+    }
+
+    fn cpu_clock(&mut self) {
+        self.irq.cpu_clock();
+    }
+
+    fn irq_pending(&mut self) -> bool {
+        self.irq.pending
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match (self.banking_control & 0b0000_1100) >> 2 {
+            0 => Mirroring::Vertical,
+            1 => Mirroring::Horizontal,
+            2 => Mirroring::SingleScreenLower,
+            _ => Mirroring::SingleScreenUpper,
+        }
+    }
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.prg_ram);
+        if self.chr_is_ram {
+            state.write_memory(&self.chr);
+        }
+
+        state.write_u8(self.prg_bank_16kb);
+        state.write_u8(self.prg_bank_8kb);
+        state.write_memory(&self.chr_banks);
+        state.write_u8(self.banking_control);
+
+        self.irq.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        state.read_memory(&mut self.prg_ram)?;
+        if self.chr_is_ram {
+            state.read_memory(&mut self.chr)?;
+        }
+
+        self.prg_bank_16kb = state.read_u8()?;
+        self.prg_bank_8kb = state.read_u8()?;
+        state.read_memory(&mut self.chr_banks)?;
+        self.banking_control = state.read_u8()?;
+
+        self.irq.load_state(state)
+    }
+}
+
+/// The IRQ counter shared by the VRC4, VRC6 and VRC7.
+///
+/// The counter is clocked either every CPU cycle or, using a prescaler, once per scanline
+/// (every 113.667 CPU cycles). An IRQ is raised when the counter overflows, at which point it is
+/// reloaded from `latch`.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/VRC_IRQ
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+struct VRCIrq {
+    latch: u8,
+    counter: u8,
+    prescaler: i16,
+
+    enabled: bool,
+    enable_after_acknowledge: bool,
+    cycle_mode: bool,
+
+    pending: bool,
+}
+
+impl VRCIrq {
+    /// The prescaler counts down by 3 every CPU cycle and clocks the counter every 341 PPU cycles.
+    const PRESCALER_PERIOD: i16 = 341;
+
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.latch);
+        state.write_u8(self.counter);
+        state.write_u16(self.prescaler as u16);
+        state.write_bool(self.enabled);
+        state.write_bool(self.enable_after_acknowledge);
+        state.write_bool(self.cycle_mode);
+        state.write_bool(self.pending);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        self.latch = state.read_u8()?;
+        self.counter = state.read_u8()?;
+        self.prescaler = state.read_u16()? as i16;
+        self.enabled = state.read_bool()?;
+        self.enable_after_acknowledge = state.read_bool()?;
+        self.cycle_mode = state.read_bool()?;
+        self.pending = state.read_bool()?;
+
+        Ok(())
+    }
+
+    fn write_control(&mut self, data: u8) {
+        self.enable_after_acknowledge = data & 0b001 != 0;
+        self.enabled = data & 0b010 != 0;
+        self.cycle_mode = data & 0b100 != 0;
+
+        self.pending = false;
+        if self.enabled {
+            self.counter = self.latch;
+            self.prescaler = VRCIrq::PRESCALER_PERIOD;
+        }
+    }
+
+    fn acknowledge(&mut self) {
+        self.pending = false;
+        self.enabled = self.enable_after_acknowledge;
+    }
+
+    fn cpu_clock(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        if self.cycle_mode {
+            self.clock_counter();
+        } else {
+            self.prescaler -= 3;
+            if self.prescaler <= 0 {
+                self.prescaler += VRCIrq::PRESCALER_PERIOD;
+                self.clock_counter();
+            }
+        }
+    }
+
+    fn clock_counter(&mut self) {
+        if self.counter == 0xFF {
+            self.counter = self.latch;
+            self.pending = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::test_rom;
+
+    fn vrc6(mapper_number: u16, chr_banks: usize) -> VRC6 {
+        VRC6::from_rom(&test_rom(mapper_number, VRC6::PRG_BANK_SIZE, 16, VRC6::CHR_BANK_SIZE, chr_banks)).unwrap()
+    }
+
+    #[test]
+    pub fn prg_banks_are_switchable() {
+        let mut vrc6 = vrc6(24, 32);
+        vrc6.cpu_write_u8(0x8000, 3);
+        vrc6.cpu_write_u8(0xC000, 9);
+
+        assert_eq!(vrc6.cpu_read_u8(0x8000), 6);
+        assert_eq!(vrc6.cpu_read_u8(0xA000), 7);
+        assert_eq!(vrc6.cpu_read_u8(0xC000), 9);
+        assert_eq!(vrc6.cpu_read_u8(0xE000), 15);
+    }
+
+    #[test]
+    pub fn mapper_26_swaps_address_lines() {
+        let mut vrc6 = vrc6(26, 32);
+        vrc6.cpu_write_u8(0xD001, 5); // R2 on mapper 26
+        vrc6.cpu_write_u8(0xD002, 7); // R1 on mapper 26
+
+        assert_eq!(vrc6.ppu_read_u8(0x0400), 7);
+        assert_eq!(vrc6.ppu_read_u8(0x0800), 5);
+    }
+
+    #[test]
+    pub fn cycle_mode_irq_fires_on_overflow() {
+        let mut vrc6 = vrc6(24, 32);
+        vrc6.cpu_write_u8(0xF000, 0xFD);
+        vrc6.cpu_write_u8(0xF001, 0b110);
+
+        vrc6.cpu_clock();
+        vrc6.cpu_clock();
+        assert!(!vrc6.irq_pending());
+
+        vrc6.cpu_clock();
+        assert!(vrc6.irq_pending());
+
+        vrc6.cpu_write_u8(0xF002, 0);
+        assert!(!vrc6.irq_pending());
+    }
+
+    #[test]
+    pub fn scanline_mode_irq_uses_prescaler() {
+        let mut vrc6 = vrc6(24, 32);
+        vrc6.cpu_write_u8(0xF000, 0xFF);
+        vrc6.cpu_write_u8(0xF001, 0b010);
+
+        for _ in 0..113 {
+            vrc6.cpu_clock();
+        }
+        assert!(!vrc6.irq_pending());
+
+        vrc6.cpu_clock();
+        assert!(vrc6.irq_pending());
+    }
+
+    #[test]
+    pub fn chr_ram_is_writable() {
+        let mut vrc6 = vrc6(24, 0);
+        vrc6.cpu_write_u8(0xD001, 5); // 1kb bank 5 at 0x0400
+        vrc6.ppu_write_u8(0x0410, 0xAB);
+
+        assert_eq!(vrc6.ppu_read_u8(0x0410), 0xAB);
+        assert_eq!(vrc6.chr[5 * VRC6::CHR_BANK_SIZE + 0x10], 0xAB);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::rp2c02::PpuWatchpoint;
+
+/// Something to wait for with `Nestalgic::run_until`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Condition {
+    /// The CPU is about to execute the instruction at this address.
+    ProgramCounter(u16),
+
+    /// The byte at `address` in CPU memory equals `value`. Reading memory-mapped registers has
+    /// side effects so `0x2000`-`0x401F` always reads as `0`.
+    MemoryEquals { address: u16, value: u8 },
+
+    /// This many vblanks have started since `run_until` was called.
+    Vblanks(u64),
+
+    /// The PPU accessed this address, see `PpuWatchpoint`. Once stopped,
+    /// `Nestalgic::ppu_watch_hit` says which scanline and dot it happened on.
+    PpuAccess(PpuWatchpoint),
+}
+
+/// Why `Nestalgic::run_until` returned.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum StopReason {
+    /// The `Condition` was met.
+    Condition,
+
+    /// `max_cycles` CPU cycles ran without the `Condition` being met.
+    CycleLimit,
+}
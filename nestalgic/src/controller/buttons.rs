@@ -0,0 +1,71 @@
+/// `Buttons` represents the state of the eight buttons on a standard NES controller.
+///
+/// The bit order matches the order the controller shifts buttons out on `0x4016`/`0x4017`:
+///
+/// ```text
+/// +---+---+---+---+---+---+---+---+
+/// | R | L | D | U | T | S | B | A |
+/// +---+---+---+---+---+---+---+---+
+///   |   |   |   |   |   |   |   |
+///   |   |   |   |   |   |   |   \-------- A (read first)
+///   |   |   |   |   |   |   \------------ B
+///   |   |   |   |   |   \---------------- Select
+///   |   |   |   |   \-------------------- Start
+///   |   |   |   \------------------------ Up
+///   |   |   \---------------------------- Down
+///   |   \-------------------------------- Left
+///   \------------------------------------ Right (read last)
+/// ```
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Standard_controller
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct Buttons(pub u8);
+
+impl Buttons {
+    pub fn get(&self, button: Button) -> bool {
+        let mask = button as u8;
+        (self.0 & mask) != 0
+    }
+
+    pub fn set(&mut self, button: Button, value: bool) {
+        let mask = button as u8;
+        if value {
+            self.0 |= mask;
+        } else {
+            self.0 &= !mask;
+        }
+    }
+
+    pub fn with(&self, button: Button, value: bool) -> Self {
+        let mut buttons = *self;
+        buttons.set(button, value);
+        buttons
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Button {
+    A       = 0b0000_0001,
+    B       = 0b0000_0010,
+    Select  = 0b0000_0100,
+    Start   = 0b0000_1000,
+    Up      = 0b0001_0000,
+    Down    = 0b0010_0000,
+    Left    = 0b0100_0000,
+    Right   = 0b1000_0000,
+}
+
+impl Button {
+    pub fn variants() -> impl Iterator<Item = Button> {
+        [
+            Button::A,
+            Button::B,
+            Button::Select,
+            Button::Start,
+            Button::Up,
+            Button::Down,
+            Button::Left,
+            Button::Right,
+        ].iter().copied()
+    }
+}
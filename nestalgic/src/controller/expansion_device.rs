@@ -0,0 +1,45 @@
+use std::any::Any;
+
+/// `ExpansionDevice` is implemented by peripherals plugged into the Famicom expansion port.
+///
+/// The expansion port sees the same `0x4016` writes as the controllers and can drive some of the
+/// data lines when `0x4016` and `0x4017` are read:
+///
+/// ```text
+/// 0x4016 write         0x4016 read          0x4017 read
+/// 7  bit  0            7  bit  0            7  bit  0
+/// ---- ----            ---- ----            ---- ----
+/// xxxx xAAA            xxxx xxEx            xxxE EEEx
+///       |||                   |                |  |||
+///       +++- OUT0-OUT2        +- Expansion     +-+++- Expansion
+/// ```
+///
+/// Devices must be `Send` so that a `Nestalgic` can be moved to another thread.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Expansion_port
+pub trait ExpansionDevice: Any + Send {
+    /// Handle a CPU write to `0x4016`. Only `OUT0`-`OUT2` (the lowest 3 bits) are connected.
+    fn write(&mut self, data: u8);
+
+    /// Handle a CPU read from `0x4016`. Only bit 1 will reach the CPU.
+    fn read_4016(&mut self) -> u8 {
+        0
+    }
+
+    /// Handle a CPU read from `0x4017`. Only bits 1-4 will reach the CPU.
+    fn read_4017(&mut self) -> u8 {
+        0
+    }
+
+    /// True while the device is holding the CPU's IRQ line low. The line is shared with the
+    /// cartridge so either can interrupt the CPU.
+    fn irq_pending(&mut self) -> bool {
+        false
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+pub(crate) const EXPANSION_OUT_MASK: u8 = 0b0000_0111;
+pub(crate) const EXPANSION_4016_MASK: u8 = 0b0000_0010;
+pub(crate) const EXPANSION_4017_MASK: u8 = 0b0001_1110;
@@ -0,0 +1,173 @@
+use std::any::Any;
+
+use super::ExpansionDevice;
+
+/// `FamilyBasicKeyboard` emulates the keyboard bundled with Family BASIC, which plugs into the
+/// Famicom expansion port.
+///
+/// The keyboard is a matrix of 9 rows and 2 columns with 4 keys in each cell. The CPU selects
+/// a row and column by writing to `0x4016` and reads the 4 keys back from `0x4017`:
+///
+/// ```text
+/// 0x4016 write         0x4017 read
+/// 7  bit  0            7  bit  0
+/// ---- ----            ---- ----
+/// xxxx xKCR            xxxK KKKx
+///       |||               | |||
+///       ||+- Reset to row 0  +-+++- Keys in the selected row/column (0: pressed)
+///       |+-- Select column, moves to the next row when going from 1 to 0
+///       +--- Enable the keyboard (0: all keys read as 0)
+/// ```
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Family_BASIC_Keyboard
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct FamilyBasicKeyboard {
+    /// The pressed keys in each row/column, stored in the bit positions they're read from.
+    pressed: [[u8; 2]; FamilyBasicKeyboard::ROWS],
+
+    row: usize,
+    column: usize,
+    enabled: bool,
+}
+
+impl FamilyBasicKeyboard {
+    const ROWS: usize = 9;
+    const KEY_MASK: u8 = 0b0001_1110;
+
+    pub fn new() -> FamilyBasicKeyboard {
+        FamilyBasicKeyboard::default()
+    }
+
+    pub fn is_pressed(&self, key: FamilyBasicKey) -> bool {
+        let (row, column, mask) = key.position();
+        self.pressed[row][column] & mask != 0
+    }
+
+    pub fn set_pressed(&mut self, key: FamilyBasicKey, pressed: bool) {
+        let (row, column, mask) = key.position();
+        if pressed {
+            self.pressed[row][column] |= mask;
+        } else {
+            self.pressed[row][column] &= !mask;
+        }
+    }
+
+    pub fn release_all(&mut self) {
+        self.pressed = [[0; 2]; FamilyBasicKeyboard::ROWS];
+    }
+}
+
+impl ExpansionDevice for FamilyBasicKeyboard {
+    fn write(&mut self, data: u8) {
+        let reset = data & 0b0000_0001 != 0;
+        let column = ((data & 0b0000_0010) >> 1) as usize;
+        self.enabled = data & 0b0000_0100 != 0;
+
+        if self.column == 1 && column == 0 {
+            self.row += 1;
+        }
+
+        if reset {
+            self.row = 0;
+        }
+
+        self.column = column;
+    }
+
+    fn read_4017(&mut self) -> u8 {
+        if !self.enabled {
+            return 0;
+        }
+
+        // Reading past the last row reports every key as released
+        match self.pressed.get(self.row) {
+            Some(row) => !row[self.column] & FamilyBasicKeyboard::KEY_MASK,
+            None => FamilyBasicKeyboard::KEY_MASK,
+        }
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The keys on the Family BASIC keyboard.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum FamilyBasicKey {
+    F1, F2, F3, F4, F5, F6, F7, F8,
+    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Return, Stop, Yen, Kana, Space, Escape, Ctr, Grph,
+    LeftShift, RightShift,
+    Semicolon, Colon, At, Caret, Minus, Slash, Underscore, Comma, Period,
+    LeftBracket, RightBracket,
+    Up, Down, Left, Right, ClrHome, Ins, Del,
+}
+
+impl FamilyBasicKey {
+    /// The row, column and `0x4017` bit mask of this key.
+    fn position(self) -> (usize, usize, u8) {
+        use FamilyBasicKey::*;
+
+        // Each column lists the keys read from bit 4, 3, 2 and 1.
+        const MATRIX: [[[FamilyBasicKey; 4]; 2]; FamilyBasicKeyboard::ROWS] = [
+            [[RightBracket, LeftBracket, Return, F8], [Stop, Yen, RightShift, Kana]],
+            [[Semicolon, Colon, At, F7], [Caret, Minus, Slash, Underscore]],
+            [[K, L, O, F6], [Num0, P, Comma, Period]],
+            [[J, U, I, F5], [Num8, Num9, N, M]],
+            [[H, G, Y, F4], [Num6, Num7, V, B]],
+            [[D, R, T, F3], [Num4, Num5, C, F]],
+            [[A, S, W, F2], [Num3, E, Z, X]],
+            [[Ctr, Q, Escape, F1], [Num2, Num1, Grph, LeftShift]],
+            [[Left, Right, Up, ClrHome], [Ins, Del, Space, Down]],
+        ];
+
+        for (row, columns) in MATRIX.iter().enumerate() {
+            for (column, keys) in columns.iter().enumerate() {
+                if let Some(index) = keys.iter().position(|&k| k == self) {
+                    return (row, column, 0b0001_0000 >> index);
+                }
+            }
+        }
+
+        unreachable!("{:?} is missing from the keyboard matrix", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn scanning_the_matrix_reports_pressed_keys() {
+        let mut keyboard = FamilyBasicKeyboard::new();
+        keyboard.set_pressed(FamilyBasicKey::Return, true);
+        keyboard.set_pressed(FamilyBasicKey::X, true);
+
+        // Reset to row 0, column 0
+        keyboard.write(0b101);
+        keyboard.write(0b100);
+        assert_eq!(keyboard.read_4017(), 0b0001_1010);
+
+        let mut reads = Vec::new();
+        for _ in 0..FamilyBasicKeyboard::ROWS {
+            keyboard.write(0b100);
+            reads.push(keyboard.read_4017());
+            keyboard.write(0b110);
+            reads.push(keyboard.read_4017());
+        }
+
+        assert_eq!(reads[0], 0b0001_1010); // Return in row 0, column 0
+        assert_eq!(reads[13], 0b0001_1100); // X in row 6, column 1
+        assert!(reads.iter().enumerate().all(|(i, &r)| i == 0 || i == 13 || r == 0b0001_1110));
+    }
+
+    #[test]
+    pub fn disabled_keyboard_reads_zero() {
+        let mut keyboard = FamilyBasicKeyboard::new();
+        keyboard.write(0b001);
+
+        assert_eq!(keyboard.read_4017(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use super::{Button, Buttons, Player};
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// Counts how often each button is pressed during a play session, e.g. to see which buttons a
+/// game leans on.
+///
+/// Buttons are sampled once per frame so a press is a button going from released on one frame
+/// to held on the next. Turbo buttons count every time turbo presses them.
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct InputStats {
+    /// The number of frames sampled
+    pub frames: u64,
+
+    presses: [[u64; 8]; 2],
+    frames_held: [[u64; 8]; 2],
+    previous_buttons: [Buttons; 2],
+}
+
+impl InputStats {
+    pub fn new() -> InputStats {
+        InputStats::default()
+    }
+
+    /// Record the buttons both players are holding on this frame.
+    pub fn record_frame(&mut self, buttons: [Buttons; 2]) {
+        self.frames += 1;
+
+        for player in [Player::One, Player::Two] {
+            let held = buttons[player.index()];
+            let previous = self.previous_buttons[player.index()];
+
+            for button in Button::variants() {
+                let index = InputStats::button_index(button);
+                if held.get(button) {
+                    self.frames_held[player.index()][index] += 1;
+
+                    if !previous.get(button) {
+                        self.presses[player.index()][index] += 1;
+                    }
+                }
+            }
+
+            self.previous_buttons[player.index()] = held;
+        }
+    }
+
+    /// How many times `button` has been pressed by `player`
+    pub fn presses(&self, player: Player, button: Button) -> u64 {
+        self.presses[player.index()][InputStats::button_index(button)]
+    }
+
+    /// How many frames `player` has held `button` for
+    pub fn frames_held(&self, player: Player, button: Button) -> u64 {
+        self.frames_held[player.index()][InputStats::button_index(button)]
+    }
+
+    /// The stats as CSV with one row per player and button, for exporting to a spreadsheet.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("player,button,presses,frames_held\n");
+
+        for (label, player) in [("1", Player::One), ("2", Player::Two)] {
+            for button in Button::variants() {
+                csv.push_str(&format!(
+                    "{},{:?},{},{}\n",
+                    label,
+                    button,
+                    self.presses(player, button),
+                    self.frames_held(player, button),
+                ));
+            }
+        }
+
+        csv
+    }
+
+    /// Write the counts so a later session of the same game can carry on from them, see
+    /// `Nestalgic::save_input_stats`.
+    pub(crate) fn save_state(&self, state: &mut StateWriter) {
+        state.write_u64(self.frames);
+        for count in self.presses.iter().chain(&self.frames_held).flatten() {
+            state.write_u64(*count);
+        }
+    }
+
+    /// Restore counts written by `save_state`. Held buttons aren't saved, so a button still held
+    /// when the stats are loaded counts as a new press.
+    pub(crate) fn load_state(state: &mut StateReader) -> Result<InputStats, StateError> {
+        let mut stats = InputStats::new();
+        stats.frames = state.read_u64()?;
+        for count in stats.presses.iter_mut().chain(&mut stats.frames_held).flatten() {
+            *count = state.read_u64()?;
+        }
+
+        Ok(stats)
+    }
+
+    fn button_index(button: Button) -> usize {
+        (button as u8).trailing_zeros() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn holding_a_button_counts_one_press() {
+        let mut stats = InputStats::new();
+        let a = Buttons::default().with(Button::A, true);
+
+        stats.record_frame([a, Buttons::default()]);
+        stats.record_frame([a, Buttons::default()]);
+        stats.record_frame([Buttons::default(), Buttons::default()]);
+        stats.record_frame([a, Buttons::default()]);
+
+        assert_eq!(stats.frames, 4);
+        assert_eq!(stats.presses(Player::One, Button::A), 2);
+        assert_eq!(stats.frames_held(Player::One, Button::A), 3);
+        assert_eq!(stats.presses(Player::Two, Button::A), 0);
+    }
+
+    #[test]
+    pub fn to_csv_has_a_row_per_player_and_button() {
+        let mut stats = InputStats::new();
+        stats.record_frame([Buttons::default(), Buttons::default().with(Button::Start, true)]);
+
+        let csv = stats.to_csv();
+
+        assert_eq!(csv.lines().count(), 1 + 2 * 8);
+        assert!(csv.contains("2,Start,1,1\n"));
+    }
+
+    #[test]
+    pub fn load_state_restores_saved_counts() {
+        let mut stats = InputStats::new();
+        let b = Buttons::default().with(Button::B, true);
+        stats.record_frame([b, Buttons::default()]);
+        stats.record_frame([Buttons::default(), b]);
+
+        let mut state = StateWriter::new();
+        stats.save_state(&mut state);
+        let bytes = state.into_bytes();
+        let loaded = InputStats::load_state(&mut StateReader::new(&bytes)).unwrap();
+
+        assert_eq!(loaded.frames, 2);
+        assert_eq!(loaded.presses(Player::One, Button::B), 1);
+        assert_eq!(loaded.frames_held(Player::Two, Button::B), 1);
+        assert!(InputStats::load_state(&mut StateReader::new(&bytes[..bytes.len() - 1])).is_err());
+    }
+}
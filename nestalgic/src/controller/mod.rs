@@ -0,0 +1,214 @@
+mod buttons;
+mod expansion_device;
+mod family_basic_keyboard;
+mod input_stats;
+
+pub use buttons::{Button, Buttons};
+pub use expansion_device::ExpansionDevice;
+pub(crate) use expansion_device::{EXPANSION_OUT_MASK, EXPANSION_4016_MASK, EXPANSION_4017_MASK};
+pub use family_basic_keyboard::{FamilyBasicKeyboard, FamilyBasicKey};
+pub use input_stats::InputStats;
+
+use crate::save_state::StateWriter;
+
+/// Identifies which controller port a `Controller` is plugged into.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Player {
+    /// Controller port 1, read from `0x4016`
+    One,
+
+    /// Controller port 2, read from `0x4017`
+    Two,
+}
+
+impl Player {
+    pub fn index(&self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+/// How long turbo buttons spend pressed and released, measured in frames.
+///
+/// Turbo is driven by the emulated frame counter rather than host time so that
+/// rapid-fire input is deterministic and can be replayed exactly.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct DutyCycle {
+    pub frames_pressed: u32,
+    pub frames_released: u32,
+}
+
+impl DutyCycle {
+    pub fn is_pressed(&self, frame: u64) -> bool {
+        let period = (self.frames_pressed + self.frames_released) as u64;
+        if period == 0 {
+            return false;
+        }
+
+        frame % period < self.frames_pressed as u64
+    }
+}
+
+impl Default for DutyCycle {
+    /// Two frames on, two frames off. This is 15 presses per second on NTSC.
+    fn default() -> Self {
+        DutyCycle { frames_pressed: 2, frames_released: 2 }
+    }
+}
+
+/// `Controller` emulates a standard NES controller.
+///
+/// The controller contains an 8-bit shift register. While the strobe bit written to `0x4016` is
+/// high the shift register is continuously reloaded with the current button state. Once the strobe
+/// is low each read returns the next button in the order given by `Buttons`.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Standard_controller
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct Controller {
+    /// The buttons currently held down by the player.
+    pub buttons: Buttons,
+
+    /// The buttons currently held down with turbo enabled. These are pressed and released
+    /// automatically according to `turbo_duty_cycle`.
+    pub turbo_buttons: Buttons,
+
+    pub turbo_duty_cycle: DutyCycle,
+
+    strobe: bool,
+
+    shift_register: u8,
+}
+
+impl Controller {
+    pub fn new() -> Controller {
+        Controller::default()
+    }
+
+    /// The buttons the console will see on `frame`, including any turbo buttons that are currently
+    /// in the pressed part of their duty cycle.
+    pub fn effective_buttons(&self, frame: u64) -> Buttons {
+        if self.turbo_duty_cycle.is_pressed(frame) {
+            Buttons(self.buttons.0 | self.turbo_buttons.0)
+        } else {
+            self.buttons
+        }
+    }
+
+    /// Handle a CPU write to `0x4016`. Only the lowest bit (the strobe) is used by a standard controller.
+    pub fn write_strobe(&mut self, data: u8, frame: u64) {
+        self.strobe = data & 0b0000_0001 != 0;
+
+        if self.strobe {
+            self.shift_register = self.effective_buttons(frame).0;
+        }
+    }
+
+    /// Handle a CPU read from this controllers port, returning the next button in bit 0.
+    pub fn read_serial(&mut self, frame: u64) -> u8 {
+        if self.strobe {
+            self.shift_register = self.effective_buttons(frame).0;
+        }
+
+        let bit = self.shift_register & 0b0000_0001;
+
+        // After all 8 buttons have been read an official controller returns 1 for every
+        // subsequent read, so we shift in 1s from the top.
+        self.shift_register = (self.shift_register >> 1) | 0b1000_0000;
+
+        bit
+    }
+
+    /// Handle a CPU read from this controllers port, returning the full byte seen on the data bus.
+    ///
+    /// Only the lower 5 bits of `0x4016` and `0x4017` are driven on the NES. The upper 3 bits
+    /// are open bus and keep whatever value was last on the data bus, which for the usual
+    /// `LDA $4016` is the high byte of the address. Some games (e.g. Paperboy) rely on this
+    /// and expect to read `0x41` rather than `0x01`.
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// OOOx xxxS
+    /// |||| ||||
+    /// |||| |||+- Serial controller data
+    /// |||+-+++-- Unused by a standard controller, always 0
+    /// +++------- Open bus
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/Standard_controller#Output_.28.244016.2F.244017_read.29
+    pub fn read_port(&mut self, frame: u64, open_bus: u8) -> u8 {
+        (open_bus & Controller::OPEN_BUS_MASK) | self.read_serial(frame)
+    }
+
+    /// Write the strobe and shift register, see `Nestalgic::state_hash`. The buttons are input
+    /// from the player rather than console state, so they aren't written.
+    pub(crate) fn save_state(&self, state: &mut StateWriter) {
+        state.write_bool(self.strobe);
+        state.write_u8(self.shift_register);
+    }
+
+    const OPEN_BUS_MASK: u8 = 0b1110_0000;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn read_serial_returns_buttons_in_order() {
+        let mut controller = Controller::new();
+        controller.buttons = Buttons::default()
+            .with(Button::A, true)
+            .with(Button::Start, true)
+            .with(Button::Right, true);
+
+        controller.write_strobe(1, 0);
+        controller.write_strobe(0, 0);
+
+        let bits: Vec<u8> = (0..8).map(|_| controller.read_serial(0)).collect();
+        assert_eq!(bits, vec![1, 0, 0, 1, 0, 0, 0, 1]);
+
+        // Official controllers return 1 once all the buttons have been read
+        assert_eq!(controller.read_serial(0), 1);
+    }
+
+    #[test]
+    pub fn read_serial_while_strobe_is_high_returns_a() {
+        let mut controller = Controller::new();
+        controller.buttons = Buttons::default().with(Button::A, true);
+
+        controller.write_strobe(1, 0);
+
+        assert_eq!(controller.read_serial(0), 1);
+        assert_eq!(controller.read_serial(0), 1);
+        assert_eq!(controller.read_serial(0), 1);
+    }
+
+    #[test]
+    pub fn read_port_keeps_open_bus_upper_bits() {
+        let mut controller = Controller::new();
+        controller.buttons = Buttons::default().with(Button::A, true);
+
+        controller.write_strobe(1, 0);
+        controller.write_strobe(0, 0);
+
+        assert_eq!(controller.read_port(0, 0x40), 0x41);
+        assert_eq!(controller.read_port(0, 0x40), 0x40);
+        assert_eq!(controller.read_port(0, 0xFF), 0xE0);
+    }
+
+    #[test]
+    pub fn turbo_buttons_follow_duty_cycle() {
+        let mut controller = Controller::new();
+        controller.turbo_buttons = Buttons::default().with(Button::B, true);
+        controller.turbo_duty_cycle = DutyCycle { frames_pressed: 1, frames_released: 2 };
+
+        let pressed: Vec<bool> = (0..6)
+            .map(|frame| controller.effective_buttons(frame).get(Button::B))
+            .collect();
+
+        assert_eq!(pressed, vec![true, false, false, true, false, false]);
+    }
+}
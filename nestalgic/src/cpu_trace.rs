@@ -0,0 +1,144 @@
+use std::fmt;
+use std::io::Write;
+
+use nestalgic_mos6502::mos6502::{DisassembledInstruction, Opcode, MOS6502};
+
+/// The state of the console as the CPU starts an instruction, see `CpuTracer`.
+///
+/// `Display` formats the line exactly like Nintendulator's `nestest.log`, so a trace of nestest
+/// can be diffed against the golden log:
+///
+/// ```text
+/// C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+/// ```
+///
+/// See also: https://www.qmtpro.com/~nes/misc/nestest.log
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct TraceLine {
+    /// The address of the opcode
+    pub pc: u16,
+
+    /// The opcode followed by its operand bytes
+    pub bytes: Vec<u8>,
+
+    /// False for unofficial opcodes, which nestest marks with a `*`
+    pub official: bool,
+
+    /// The instruction in assembly, including the address it touches and the value there for
+    /// instructions that access memory, e.g. `STX $00 = 00`.
+    pub disassembly: String,
+
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub p: u8,
+    pub sp: u8,
+
+    pub scanline: u16,
+    pub dot: usize,
+
+    /// CPU cycles since power on, counted up to the start of this instruction
+    pub cycles: u64,
+}
+
+impl TraceLine {
+    /// Describe the instruction `cpu` has just fetched. `peek` must read CPU memory without side
+    /// effects, since the operand and the memory it points at are read before the CPU gets to them.
+    pub(crate) fn capture(cpu: &MOS6502, scanline: u16, dot: usize, peek: impl Fn(u16) -> u8) -> TraceLine {
+        let pc = cpu.instruction_pc();
+        let bytes = (0..3).map(|offset| peek(pc.wrapping_add(offset))).collect::<Vec<u8>>();
+        let instruction = DisassembledInstruction::decode(pc, &bytes);
+
+        let disassembly = match instruction.info {
+            Some(info) => {
+                let operand = instruction.operand_with_memory(cpu.x, cpu.y, &peek);
+                if operand.is_empty() {
+                    mnemonic(info.opcode)
+                } else {
+                    format!("{} {}", mnemonic(info.opcode), operand)
+                }
+            },
+            None => instruction.to_string(),
+        };
+
+        TraceLine {
+            pc,
+            official: instruction.info.is_some_and(|info| info.official),
+            bytes: instruction.bytes,
+            disassembly,
+            a: cpu.a,
+            x: cpu.x,
+            y: cpu.y,
+            p: cpu.p.0,
+            sp: cpu.sp,
+            scanline,
+            dot,
+            // The opcode fetch has already been counted
+            cycles: cpu.elapsed_cycles - 1,
+        }
+    }
+}
+
+impl fmt::Display for TraceLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.bytes
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        write!(
+            f,
+            "{:04X}  {:<8} {}{:<31} A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+            self.pc, bytes, if self.official { ' ' } else { '*' }, self.disassembly,
+            self.a, self.x, self.y, self.p, self.sp, self.scanline, self.dot, self.cycles
+        )
+    }
+}
+
+/// The name nestest uses for `opcode`. Most match our names but a few unofficial opcodes go by
+/// other names in Nintendulator.
+fn mnemonic(opcode: Opcode) -> String {
+    match opcode {
+        Opcode::ISC => "ISB".to_string(),
+        _ => opcode.to_string(),
+    }
+}
+
+/// A `CpuTracer` is given a `TraceLine` as the CPU starts each instruction, see
+/// `Nestalgic::set_cpu_tracer`.
+///
+/// Interrupts aren't instructions so they aren't traced, but the instruction they jump to is.
+/// Closures taking a `&TraceLine` are tracers, and `TraceWriter` writes the lines out in
+/// `nestest.log` format.
+///
+/// Tracers must be `Send` so that a `Nestalgic` can be moved to another thread.
+pub trait CpuTracer: Send {
+    fn trace(&mut self, line: &TraceLine);
+}
+
+impl <F: FnMut(&TraceLine) + Send> CpuTracer for F {
+    fn trace(&mut self, line: &TraceLine) {
+        self(line)
+    }
+}
+
+/// Writes each `TraceLine` to `writer` on its own line. A trace grows by millions of lines a
+/// minute so `writer` should usually be buffered.
+///
+/// Tracing is a debugging aid, so write errors are ignored rather than stopping the console.
+pub struct TraceWriter<W: Write + Send> {
+    writer: W,
+}
+
+impl <W: Write + Send> TraceWriter<W> {
+    pub fn new(writer: W) -> TraceWriter<W> {
+        TraceWriter { writer }
+    }
+}
+
+impl <W: Write + Send> CpuTracer for TraceWriter<W> {
+    fn trace(&mut self, line: &TraceLine) {
+        let _ = writeln!(self.writer, "{}", line);
+    }
+}
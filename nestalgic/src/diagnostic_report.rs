@@ -0,0 +1,125 @@
+use std::fmt;
+
+/// A plain text summary of the emulator and the game it's running, for users to paste into bug
+/// reports. Build one with `Nestalgic::diagnostic_report`.
+///
+/// The report only describes the console: front ends should add what they know about the machine
+/// it's running on (e.g. the GPU) with `with_host_info`. Use `redacted` to get the text to share,
+/// which hides the user's home directory in case it's shown in any of the values.
+#[derive(PartialEq, Debug, Clone)]
+pub struct DiagnosticReport {
+    pub version: &'static str,
+
+    /// `(name, value)` pairs describing the host, e.g. the OS or GPU
+    pub host: Vec<(String, String)>,
+
+    /// The CRC-32 of the PRG and CHR ROM, excluding the iNES header. This matches the checksums
+    /// in No-Intro DATs, see `Dat`.
+    pub rom_crc32: u32,
+
+    pub mapper: u16,
+
+    /// The name of the mapper, or `None` if it isn't supported
+    pub mapper_name: Option<&'static str>,
+
+    /// `(name, value)` pairs for each emulator setting
+    pub settings: Vec<(&'static str, String)>,
+
+    /// The most recent problems found by the VRAM write checker and test register log, oldest
+    /// first
+    pub diagnostics: Vec<String>,
+}
+
+impl DiagnosticReport {
+    /// The most diagnostics to include, so a misbehaving game doesn't bury the rest of the report
+    pub const MAX_DIAGNOSTICS: usize = 20;
+
+    pub fn with_host_info(mut self, name: impl Into<String>, value: impl Into<String>) -> DiagnosticReport {
+        self.host.push((name.into(), value.into()));
+        self
+    }
+
+    /// The report as text with the user's home directory replaced by `~`.
+    pub fn redacted(&self) -> String {
+        let text = self.to_string();
+
+        match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+            Ok(home) if home.len() > 1 => text.replace(&home, "~"),
+            _ => text,
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nestalgic {}", self.version)?;
+        writeln!(f, "OS: {} {}", std::env::consts::OS, std::env::consts::ARCH)?;
+        for (name, value) in &self.host {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "ROM CRC-32: {:08X}", self.rom_crc32)?;
+        writeln!(f, "Mapper: {} ({})", self.mapper, self.mapper_name.unwrap_or("unsupported"))?;
+
+        writeln!(f)?;
+        writeln!(f, "Settings:")?;
+        for (name, value) in &self.settings {
+            writeln!(f, "  {}: {}", name, value)?;
+        }
+
+        writeln!(f)?;
+        write!(f, "Diagnostics:")?;
+        if self.diagnostics.is_empty() {
+            write!(f, " none")?;
+        }
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n  {}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> DiagnosticReport {
+        DiagnosticReport {
+            version: "1.2.3",
+            host: Vec::new(),
+            rom_crc32: 0x158B_0388,
+            mapper: 4,
+            mapper_name: Some("MMC3"),
+            settings: vec![("Region", "Pal".to_string())],
+            diagnostics: Vec::new(),
+        }
+    }
+
+    #[test]
+    pub fn lists_each_section() {
+        let text = report().with_host_info("GPU", "Example GPU").to_string();
+
+        assert!(text.starts_with("Nestalgic 1.2.3\n"));
+        assert!(text.contains("\nGPU: Example GPU\n"));
+        assert!(text.contains("\nROM CRC-32: 158B0388\nMapper: 4 (MMC3)\n"));
+        assert!(text.contains("\nSettings:\n  Region: Pal\n"));
+        assert!(text.ends_with("\nDiagnostics: none"));
+    }
+
+    #[test]
+    pub fn redacts_the_home_directory() {
+        let home = match std::env::var("HOME") {
+            Ok(home) if home.len() > 1 => home,
+            _ => return,
+        };
+
+        let text = report()
+            .with_host_info("Config", format!("{}/.config/nestalgic", home))
+            .redacted();
+
+        assert!(text.contains("\nConfig: ~/.config/nestalgic\n"));
+        assert!(!text.contains(&home));
+    }
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("CPU failed at PC {pc:04X} after {elapsed_cycles} cycles: {source}")]
+    Cpu {
+        pc: u16,
+        elapsed_cycles: u64,
+        source: nestalgic_mos6502::mos6502::Error,
+    },
+
+    #[error("Scanline {scanline} is past the end of a {scanlines_per_frame} scanline frame")]
+    ScanlineOutOfRange {
+        scanline: u16,
+        scanlines_per_frame: u16,
+    },
+}
@@ -0,0 +1,98 @@
+use std::ops::RangeInclusive;
+
+use crate::BusInterceptor;
+
+/// `FaultInjector` randomly corrupts CPU reads to simulate a dirty or badly seated cartridge.
+///
+/// It's intended for robustness testing: Games running with faults injected will misbehave, but
+/// the emulator should keep running or report an error from `Nestalgic::try_cycle` rather than
+/// panic. Faults are driven by a seeded PRNG so any failure can be reproduced exactly.
+///
+/// Each read in `address_range` has a chance of suffering one of these faults:
+///
+/// - A single random bit is flipped (`bit_flip_rate`)
+/// - The value is replaced with open bus (`open_bus_rate`), which we approximate with the high
+///   byte of the address
+/// - The value is replaced with random garbage (`random_value_rate`)
+pub struct FaultInjector {
+    rng: u64,
+
+    pub address_range: RangeInclusive<u16>,
+    pub bit_flip_rate: f64,
+    pub open_bus_rate: f64,
+    pub random_value_rate: f64,
+
+    pub faults_injected: u64,
+}
+
+impl FaultInjector {
+    /// Create an injector with the given seed which only affects the cartridge (`0x4020-0xFFFF`).
+    ///
+    /// All fault rates start at 0.
+    pub fn new(seed: u64) -> FaultInjector {
+        FaultInjector {
+            // xorshift gets stuck on 0 so make sure the state never starts there
+            rng: seed ^ 0x9E37_79B9_7F4A_7C15,
+            address_range: 0x4020..=0xFFFF,
+            bit_flip_rate: 0.0,
+            open_bus_rate: 0.0,
+            random_value_rate: 0.0,
+            faults_injected: 0,
+        }
+    }
+
+    pub fn with_address_range(mut self, address_range: RangeInclusive<u16>) -> FaultInjector {
+        self.address_range = address_range;
+        self
+    }
+
+    pub fn with_bit_flip_rate(mut self, rate: f64) -> FaultInjector {
+        self.bit_flip_rate = rate;
+        self
+    }
+
+    pub fn with_open_bus_rate(mut self, rate: f64) -> FaultInjector {
+        self.open_bus_rate = rate;
+        self
+    }
+
+    pub fn with_random_value_rate(mut self, rate: f64) -> FaultInjector {
+        self.random_value_rate = rate;
+        self
+    }
+
+    /// xorshift64, see https://www.jstatsoft.org/article/view/v008i14
+    fn next_u64(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    /// A uniformly distributed value in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl BusInterceptor for FaultInjector {
+    fn after_read(&mut self, address: u16, value: u8) -> u8 {
+        if !self.address_range.contains(&address) {
+            return value;
+        }
+
+        let roll = self.next_f64();
+        let fault = if roll < self.bit_flip_rate {
+            value ^ (1 << (self.next_u64() % 8))
+        } else if roll < self.bit_flip_rate + self.open_bus_rate {
+            (address >> 8) as u8
+        } else if roll < self.bit_flip_rate + self.open_bus_rate + self.random_value_rate {
+            self.next_u64() as u8
+        } else {
+            return value;
+        };
+
+        self.faults_injected += 1;
+        fault
+    }
+}
@@ -0,0 +1,121 @@
+//! A gym-style environment for driving `Nestalgic` from reinforcement learning agents.
+//!
+//! Each `step` applies an `Action` for a fixed number of frames and returns what the agent
+//! observed along with the reward and whether the episode is over. Because the emulator is
+//! deterministic, the same sequence of actions after `reset` always produces the same results.
+
+use crate::{Buttons, CartridgeError, Nestalgic, NESROM, Player};
+
+/// The buttons held on each controller during a `step`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct Action {
+    pub player_one: Buttons,
+    pub player_two: Buttons,
+}
+
+/// What the agent sees after each `step`.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Observation {
+    /// The palette index (0x00-0x3F) of each pixel on screen in row-major order.
+    pub frame: Vec<u8>,
+
+    /// The value of each address passed to `Environment::with_ram_addresses`, in the same order.
+    pub ram: Vec<u8>,
+}
+
+/// The result of a single `step`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f64,
+    pub done: bool,
+}
+
+pub type RewardHook = Box<dyn FnMut(&Nestalgic) -> f64>;
+pub type DoneHook = Box<dyn FnMut(&Nestalgic) -> bool>;
+
+pub struct Environment {
+    rom: NESROM,
+    nestalgic: Nestalgic,
+
+    ram_addresses: Vec<u16>,
+    frames_per_step: u32,
+
+    reward_hook: RewardHook,
+    done_hook: DoneHook,
+}
+
+impl Environment {
+    pub fn new(rom: NESROM) -> Result<Environment, CartridgeError> {
+        Ok(Environment {
+            nestalgic: Nestalgic::new(rom.clone())?,
+            rom,
+            ram_addresses: Vec::new(),
+            frames_per_step: 1,
+            reward_hook: Box::new(|_| 0.0),
+            done_hook: Box::new(|_| false),
+        })
+    }
+
+    /// Include the values at `addresses` (in the 2KB of CPU RAM) in each `Observation`.
+    pub fn with_ram_addresses(mut self, addresses: Vec<u16>) -> Environment {
+        self.ram_addresses = addresses;
+        self
+    }
+
+    /// Hold each `Action` for `frames` frames. Defaults to 1.
+    pub fn with_frames_per_step(mut self, frames: u32) -> Environment {
+        self.frames_per_step = frames;
+        self
+    }
+
+    /// Compute the reward after each `step`. Defaults to always returning 0.
+    pub fn with_reward_hook(mut self, reward_hook: impl FnMut(&Nestalgic) -> f64 + 'static) -> Environment {
+        self.reward_hook = Box::new(reward_hook);
+        self
+    }
+
+    /// Decide if the episode is over after each `step`. Defaults to never ending.
+    pub fn with_done_hook(mut self, done_hook: impl FnMut(&Nestalgic) -> bool + 'static) -> Environment {
+        self.done_hook = Box::new(done_hook);
+        self
+    }
+
+    /// Power cycle the console and return the initial observation.
+    pub fn reset(&mut self) -> Observation {
+        // `new` has already loaded this ROM successfully so it can't fail here
+        self.nestalgic = Nestalgic::new(self.rom.clone()).expect("failed to reload ROM");
+        self.observe()
+    }
+
+    pub fn step(&mut self, action: Action) -> Step {
+        self.nestalgic.controller_mut(Player::One).buttons = action.player_one;
+        self.nestalgic.controller_mut(Player::Two).buttons = action.player_two;
+
+        for _ in 0..self.frames_per_step {
+            self.nestalgic.run_frame();
+        }
+
+        Step {
+            observation: self.observe(),
+            reward: (self.reward_hook)(&self.nestalgic),
+            done: (self.done_hook)(&self.nestalgic),
+        }
+    }
+
+    pub fn nestalgic(&self) -> &Nestalgic {
+        &self.nestalgic
+    }
+
+    fn observe(&self) -> Observation {
+        let wram = self.nestalgic.wram();
+
+        Observation {
+            frame: self.nestalgic.palette_indices().to_vec(),
+            ram: self.ram_addresses
+                .iter()
+                .map(|&address| wram[(address as usize) % wram.len()])
+                .collect(),
+        }
+    }
+}
@@ -1,29 +1,106 @@
 mod nes_bus;
 mod rp2c02;
 mod cartridge;
+mod controller;
+mod state_hash;
+mod region;
+mod bus_interceptor;
+mod fault_injector;
+mod error;
+mod save_state;
+mod condition;
+mod version;
+mod vram_write_checker;
+mod test_registers;
+mod video_sink;
+mod cpu_trace;
+mod diagnostic_report;
+pub mod movie;
+pub mod batch;
+#[cfg(feature = "gym")]
+pub mod gym;
 
 use cartridge::Cartridge;
+pub use cartridge::{CartridgeError, MapperInfo, SUPPORTED_MAPPERS};
+pub use version::{version_info, VersionInfo};
+pub use vram_write_checker::{VramWriteChecker, VramWriteDiagnostic, VramWriteProblem};
+pub use test_registers::{TestRegisterAccess, TestRegisterLog};
+pub use video_sink::{FrameBuffer, VideoSink};
+pub use cpu_trace::{CpuTracer, TraceLine, TraceWriter};
+pub use diagnostic_report::DiagnosticReport;
 use nes_bus::{CpuBus, PpuBus};
 pub use nestalgic_rom::nesrom::NESROM;
-pub use rp2c02::{Texture, Pixel};
+pub use nestalgic_rom::dat::{Dat, DatEntry, DatError, DumpStatus};
+pub use rp2c02::{PpuAccessKind, PpuWatchpoint, PpuWatchHit, PpuEvent, PpuEventKind, SpriteInfo};
+pub use rp2c02::{Texture, Pixel, FrameBlend, PPUCtrl, PPUCtrlFlag, PPUMask, PPUStatus, PPURevision, DebugRegisters, VramAddress};
+pub use region::{Region, Timing};
+pub use bus_interceptor::BusInterceptor;
+pub use fault_injector::FaultInjector;
+pub use error::Error;
+pub use condition::{Condition, StopReason};
+pub use save_state::StateError;
+use save_state::{StateReader, StateWriter};
+use bus_interceptor::InterceptedBus;
+pub use controller::{
+    Controller, Button, Buttons, DutyCycle, Player,
+    ExpansionDevice, FamilyBasicKeyboard, FamilyBasicKey, InputStats
+};
 use nestalgic_mos6502::mos6502::{MOS6502, DMA};
-use rp2c02::RP2C02;
+pub use rp2c02::RP2C02;
+use rp2c02::FrameBlender;
+pub use state_hash::StateHasher;
 
+use std::hash::Hasher;
 use std::time::Duration;
 
 type WRAM = [u8; 2048];
 
+/// Called with the scanline number and the PPU at dot 0 of every scanline, see
+/// `Nestalgic::set_scanline_hook`.
+pub type ScanlineHook = Box<dyn FnMut(u16, &RP2C02) + Send>;
+
+/// The console's 2kb of nametable RAM. Which nametables share it is chosen by the cartridge's
+/// `Mirroring`.
+type CIRAM = [u8; 2048];
+
 pub struct Nestalgic {
     pub cpu: MOS6502,
     pub ppu: RP2C02,
 
     wram: WRAM,
+    ciram: CIRAM,
     cartridge: Cartridge,
+    controllers: [Controller; 2],
+    input_stats: InputStats,
+    frame_blender: Option<FrameBlender>,
+
+    /// Set whenever the PPU finishes a frame, until `take_frame_complete` is called
+    frame_complete: bool,
+    video_sinks: Vec<Box<dyn VideoSink>>,
+    scanline_hook: Option<ScanlineHook>,
+    cpu_tracer: Option<Box<dyn CpuTracer>>,
+    expansion_device: Option<Box<dyn ExpansionDevice>>,
+    bus_interceptors: Vec<Box<dyn BusInterceptor>>,
+    vram_write_checker: Option<VramWriteChecker>,
+    test_register_log: TestRegisterLog,
+
+    /// Whether a reset starts the PPU's warm up period, see `RP2C02::warming_up`
+    ppu_warm_up: bool,
     // TODO: APU
-    // TODO: Input
 
-    master_clock_speed: Duration,
-    time_since_last_master_cycle: Duration,
+    timing: Timing,
+    cpu_cycle_duration: Duration,
+    time_since_last_cpu_cycle: Duration,
+
+    /// The most CPU cycles a single `tick` will run, see `set_cycle_budget`
+    cycle_budget: Option<u64>,
+
+    /// CPU cycles skipped by `tick` because they didn't fit in `cycle_budget`
+    dropped_cycles: u64,
+
+    /// Master clock cycles the PPU is owed but hasn't run yet. This lets us run a fractional
+    /// number of PPU cycles for each CPU cycle.
+    ppu_clock_remainder: u32,
 }
 
 impl Nestalgic {
@@ -36,18 +113,81 @@ impl Nestalgic {
     pub const PATTERN_TABLE_WIDTH: usize = 128;
     pub const PATTERN_TABLE_HEIGHT: usize = 128;
 
-    pub fn new(rom: NESROM) -> Nestalgic {
+    pub const NAMETABLES_WIDTH: usize = 512;
+    pub const NAMETABLES_HEIGHT: usize = 480;
+
+    /// About 3 NTSC frames of CPU cycles
+    pub const DEFAULT_CYCLE_BUDGET: u64 = 90_000;
+
+    /// Power on a console with `rom` inserted. Fails if the cartridge's mapper isn't supported.
+    pub fn new(rom: NESROM) -> Result<Nestalgic, CartridgeError> {
         let mut nestalgic = Nestalgic {
             cpu: Nestalgic::nes_cpu(),
             wram: [0; 2048],
+            ciram: [0; 2048],
             ppu: RP2C02::new(),
-            cartridge: Cartridge::from_rom(rom),
+            cartridge: Cartridge::from_rom(rom)?,
+            controllers: [Controller::new(), Controller::new()],
+            input_stats: InputStats::new(),
+            frame_blender: None,
+            frame_complete: false,
+            video_sinks: Vec::new(),
+            scanline_hook: None,
+            cpu_tracer: None,
+            expansion_device: None,
+            bus_interceptors: Vec::new(),
+            vram_write_checker: None,
+            test_register_log: TestRegisterLog::new(),
+            ppu_warm_up: true,
 
-            master_clock_speed: Duration::from_nanos(559),
-            time_since_last_master_cycle: Duration::new(0, 0),
+            timing: Region::Ntsc.timing(),
+            cpu_cycle_duration: Region::Ntsc.timing().cpu_cycle_duration(),
+            time_since_last_cpu_cycle: Duration::new(0, 0),
+            cycle_budget: Some(Nestalgic::DEFAULT_CYCLE_BUDGET),
+            dropped_cycles: 0,
+            ppu_clock_remainder: 0,
         };
         nestalgic.reset();
-        nestalgic
+        Ok(nestalgic)
+    }
+
+    pub fn with_region(self, region: Region) -> Nestalgic {
+        self.with_timing(region.timing()).with_ppu_revision(region.ppu_revision())
+    }
+
+    /// Emulate a specific PPU chip, e.g. one of the RGB PPUs used by VS. System arcade boards.
+    pub fn with_ppu_revision(mut self, revision: PPURevision) -> Nestalgic {
+        self.ppu.revision = revision;
+        self
+    }
+
+    /// Turn the PPU's warm up period after reset on or off, see `RP2C02::warming_up`. It's on by
+    /// default; turning it off helps when testing homebrew that doesn't wait for the PPU.
+    pub fn with_ppu_warm_up(mut self, enabled: bool) -> Nestalgic {
+        self.ppu_warm_up = enabled;
+        self.ppu.warming_up &= enabled;
+        self
+    }
+
+    /// Delay PPU register writes by as many dots as they take on hardware, see
+    /// `RP2C02::delay_register_effects`.
+    pub fn with_delayed_register_effects(mut self, enabled: bool) -> Nestalgic {
+        self.ppu.delay_register_effects = enabled;
+        self
+    }
+
+    /// Run with custom CPU/PPU clocks and frame layout, for consoles not covered by `Region`.
+    pub fn with_timing(mut self, timing: Timing) -> Nestalgic {
+        self.timing = timing;
+        self.cpu_cycle_duration = timing.cpu_cycle_duration();
+        self.ppu.scanlines_per_frame = timing.scanlines_per_frame;
+        self.ppu.vblank_scanline = timing.vblank_scanline;
+        self.ppu.skip_odd_frame_dot = timing.skip_odd_frame_dot;
+        self
+    }
+
+    pub fn timing(&self) -> Timing {
+        self.timing
     }
 
     fn nes_cpu() -> MOS6502 {
@@ -61,12 +201,49 @@ impl Nestalgic {
     }
 
     pub fn reset(&mut self) {
+        self.ppu.warming_up = self.ppu_warm_up;
+
         let mut cpu_bus = CpuBus {
             wram: &mut self.wram,
             ppu: &mut self.ppu,
-            cartridge: &mut self.cartridge
+            cartridge: &mut self.cartridge,
+            ciram: &mut self.ciram,
+            controllers: &mut self.controllers,
+            expansion_device: &mut self.expansion_device,
+            vram_write_checker: &mut self.vram_write_checker,
+            test_register_log: &mut self.test_register_log,
+            pc: self.cpu.pc,
         };
-        self.cpu.reset(&mut cpu_bus).expect("Failed to reset CPU");
+
+        if self.bus_interceptors.is_empty() {
+            self.cpu.reset(&mut cpu_bus).expect("Failed to reset CPU");
+        } else {
+            let mut bus = InterceptedBus { bus: cpu_bus, interceptors: &mut self.bus_interceptors };
+            self.cpu.reset(&mut bus).expect("Failed to reset CPU");
+        }
+    }
+
+    /// Run every CPU read and write through `interceptor` from now on.
+    pub fn add_bus_interceptor(&mut self, interceptor: Box<dyn BusInterceptor>) {
+        self.bus_interceptors.push(interceptor);
+    }
+
+    pub fn clear_bus_interceptors(&mut self) {
+        self.bus_interceptors.clear();
+    }
+
+    /// Check every write to `PPUDATA` with `checker`, or stop checking with `None`.
+    pub fn set_vram_write_checker(&mut self, checker: Option<VramWriteChecker>) {
+        self.vram_write_checker = checker;
+    }
+
+    pub fn vram_write_checker(&self) -> Option<&VramWriteChecker> {
+        self.vram_write_checker.as_ref()
+    }
+
+    /// Every access the game has made to the disabled test registers at `0x4018`-`0x401F`.
+    pub fn test_register_log(&self) -> &TestRegisterLog {
+        &self.test_register_log
     }
 
     /// Simulate the NES forward by `delta` time. Depending on how much time has elapsed this may:
@@ -74,48 +251,617 @@ impl Nestalgic {
     /// - Cycle the CPU some number of times
     /// - Cycle the PPU some number of times
     ///
+    /// At most `cycle_budget` CPU cycles are run per call. If the host falls behind, the rest of
+    /// the time is dropped (and counted in `dropped_cycles`) so the game slows down instead of the
+    /// host freezing while it tries to catch up.
     pub fn tick(&mut self, delta: Duration) {
-        self.time_since_last_master_cycle += delta;
+        self.time_since_last_cpu_cycle += delta;
+
+        let mut cycles = 0;
+        while self.time_since_last_cpu_cycle > self.cpu_cycle_duration {
+            if self.cycle_budget.is_some_and(|budget| cycles >= budget) {
+                let cycle_nanos = self.cpu_cycle_duration.as_nanos();
+                let behind_nanos = self.time_since_last_cpu_cycle.as_nanos();
+                self.dropped_cycles += (behind_nanos / cycle_nanos) as u64;
+                self.time_since_last_cpu_cycle = Duration::from_nanos((behind_nanos % cycle_nanos) as u64);
+                break;
+            }
 
-        while self.time_since_last_master_cycle > self.master_clock_speed {
-            self.time_since_last_master_cycle -= self.master_clock_speed;
+            self.time_since_last_cpu_cycle -= self.cpu_cycle_duration;
             self.cycle();
+            cycles += 1;
+        }
+    }
+
+    /// Limit how many CPU cycles each `tick` can run, or `None` to always catch up. Defaults to
+    /// `DEFAULT_CYCLE_BUDGET`.
+    pub fn set_cycle_budget(&mut self, budget: Option<u64>) {
+        self.cycle_budget = budget;
+    }
+
+    pub fn cycle_budget(&self) -> Option<u64> {
+        self.cycle_budget
+    }
+
+    /// The number of CPU cycles `tick` has skipped to stay within `cycle_budget`. If this keeps
+    /// growing the host can't keep up with the emulator.
+    pub fn dropped_cycles(&self) -> u64 {
+        self.dropped_cycles
+    }
+
+    /// Summarise the emulator, the cartridge and any problems it's noticed for a bug report, see
+    /// `DiagnosticReport`.
+    pub fn diagnostic_report(&self) -> DiagnosticReport {
+        let rom = &self.cartridge.rom;
+        let mapper = rom.header.mapper_number;
+
+        let settings = vec![
+            ("Master clock", format!("{} Hz", self.timing.master_clock_hz)),
+            ("Scanlines per frame", self.timing.scanlines_per_frame.to_string()),
+            ("PPU revision", format!("{:?}", self.ppu.revision)),
+            ("PPU warm up", self.ppu_warm_up.to_string()),
+            ("Delayed register effects", self.ppu.delay_register_effects.to_string()),
+            ("Cycle budget", format!("{:?}", self.cycle_budget)),
+            ("Dropped cycles", self.dropped_cycles.to_string()),
+            ("Frame", self.ppu.frame.to_string()),
+        ];
+
+        let vram_writes = self.vram_write_checker
+            .iter()
+            .flat_map(|checker| &checker.diagnostics)
+            .map(|diagnostic| diagnostic.to_string());
+        let test_registers = self.test_register_log
+            .accesses()
+            .iter()
+            .map(|access| format!(
+                "PC {:04X}: {} test register {:04X} ({} times)",
+                access.pc, if access.write { "wrote" } else { "read" }, access.address, access.count
+            ));
+        let mut diagnostics = vram_writes.chain(test_registers).collect::<Vec<String>>();
+        diagnostics.drain(..diagnostics.len().saturating_sub(DiagnosticReport::MAX_DIAGNOSTICS));
+
+        DiagnosticReport {
+            version: version_info().version,
+            host: Vec::new(),
+            rom_crc32: self.rom_crc32(),
+            mapper,
+            mapper_name: SUPPORTED_MAPPERS.iter().find(|info| info.number == mapper).map(|info| info.name),
+            settings,
+            diagnostics,
         }
     }
 
     pub fn cycle(&mut self) {
+        self.try_cycle().expect("failed to cycle")
+    }
+
+    /// Cycle the CPU once and the PPU however many times it runs per CPU cycle, returning an
+    /// error instead of panicking if the CPU can't continue.
+    pub fn try_cycle(&mut self) -> Result<(), Error> {
+        // IRQ is open collector, so the CPU sees it whenever any device pulls it low
+        let expansion_irq = self.expansion_device.as_mut().is_some_and(|device| device.irq_pending());
+        self.cpu.irq = self.cartridge.mapper.irq_pending() || expansion_irq;
+
+        let pc = self.cpu.instruction_pc();
+
         let mut cpu_bus = CpuBus {
             wram: &mut self.wram,
             ppu: &mut self.ppu,
-            cartridge: &mut self.cartridge
+            cartridge: &mut self.cartridge,
+            ciram: &mut self.ciram,
+            controllers: &mut self.controllers,
+            expansion_device: &mut self.expansion_device,
+            vram_write_checker: &mut self.vram_write_checker,
+            test_register_log: &mut self.test_register_log,
+            pc,
         };
-        self.cpu.cycle(&mut cpu_bus).expect("failed to cycle cpu");
+
+        // Skip the wrapper when nothing is registered so instrumentation costs nothing by default
+        let cpu_result = if self.bus_interceptors.is_empty() {
+            self.cpu.cycle(&mut cpu_bus)
+        } else {
+            let mut bus = InterceptedBus { bus: cpu_bus, interceptors: &mut self.bus_interceptors };
+            self.cpu.cycle(&mut bus)
+        };
+
+        cpu_result.map_err(|source| Error::Cpu {
+            pc,
+            elapsed_cycles: self.cpu.elapsed_cycles,
+            source,
+        })?;
+
+        // Trace before the PPU catches up so the dot matches when the opcode was fetched
+        if self.cpu_tracer.is_some() && self.cpu.instruction_cycle() == 1 && !self.cpu.in_interrupt_sequence() {
+            // The APU and I/O registers can't be peeked, so show them as open bus like Nintendulator
+            let peek = |address| match address {
+                0x4000..=0x401F => 0xFF,
+                _ => self.peek_u8(address),
+            };
+            let line = TraceLine::capture(&self.cpu, self.ppu.scanline, self.ppu.cycles, peek);
+            if let Some(cpu_tracer) = &mut self.cpu_tracer {
+                cpu_tracer.trace(&line);
+            }
+        }
 
         let mut ppu_bus = PpuBus {
-            cartridge: &mut self.cartridge
+            cartridge: &mut self.cartridge,
+            ciram: &mut self.ciram,
         };
-        self.ppu.cycle(&mut self.cpu, &mut ppu_bus);
-        self.ppu.cycle(&mut self.cpu, &mut ppu_bus);
-        self.ppu.cycle(&mut self.cpu, &mut ppu_bus);
+
+        let frame = self.ppu.frame;
+        let mut frame_complete = false;
+
+        // Run however many PPU cycles fit into the master clock cycles taken by one CPU cycle.
+        self.ppu_clock_remainder += self.timing.cpu_clock_divider;
+        while self.ppu_clock_remainder >= self.timing.ppu_clock_divider {
+            self.ppu_clock_remainder -= self.timing.ppu_clock_divider;
+            self.ppu.cycle(&mut self.cpu, &mut ppu_bus);
+            frame_complete |= self.ppu.frame_complete();
+
+            if let Some(scanline_hook) = &mut self.scanline_hook {
+                if self.ppu.cycles == 0 {
+                    scanline_hook(self.ppu.scanline, &self.ppu);
+                }
+            }
+        }
+
+        if frame_complete {
+            self.frame_complete = true;
+
+            let buttons = [
+                self.controllers[0].effective_buttons(frame),
+                self.controllers[1].effective_buttons(frame),
+            ];
+            self.input_stats.record_frame(buttons);
+
+            if let Some(frame_blender) = &mut self.frame_blender {
+                frame_blender.accumulate(&self.ppu.pixels);
+            }
+
+            for video_sink in &mut self.video_sinks {
+                video_sink.submit_frame(&self.ppu.pixels);
+            }
+        }
+
+        self.cartridge.cpu_cycle();
+
+        Ok(())
+    }
+
+    /// Cycle the NES until the PPU finishes the current frame.
+    pub fn run_frame(&mut self) {
+        self.try_run_frame().expect("failed to run frame")
+    }
+
+    /// Like `run_frame` but returns an error instead of panicking if the CPU can't continue.
+    pub fn try_run_frame(&mut self) -> Result<(), Error> {
+        let frame = self.ppu.frame;
+        while self.ppu.frame == frame {
+            self.try_cycle()?;
+        }
+
+        Ok(())
+    }
+
+    /// Cycle the NES until the PPU starts frame `frame`, e.g. to inspect a raster bug reported at a
+    /// specific frame. Returns immediately if that frame has already started.
+    ///
+    /// The NES only stops between CPU cycles so we stop on the first CPU cycle of the frame, which
+    /// leaves the PPU within a few dots of the start of scanline 0.
+    pub fn run_to_frame(&mut self, frame: u64) {
+        self.try_run_to_frame(frame).expect("failed to run to frame")
+    }
+
+    /// Like `run_to_frame` but returns an error instead of panicking if the CPU can't continue.
+    pub fn try_run_to_frame(&mut self, frame: u64) -> Result<(), Error> {
+        while self.ppu.frame < frame {
+            self.try_cycle()?;
+        }
+
+        Ok(())
+    }
+
+    /// Cycle the NES until the PPU reaches `scanline` of the next frame.
+    ///
+    /// Like `run_to_frame` this stops on the first CPU cycle of the scanline.
+    pub fn run_to_scanline(&mut self, scanline: u16) {
+        self.try_run_to_scanline(scanline).expect("failed to run to scanline")
+    }
+
+    /// Like `run_to_scanline` but returns an error instead of panicking if the CPU can't continue
+    /// or `scanline` isn't part of a frame.
+    pub fn try_run_to_scanline(&mut self, scanline: u16) -> Result<(), Error> {
+        let scanlines_per_frame = self.ppu.scanlines_per_frame;
+        if scanline >= scanlines_per_frame {
+            return Err(Error::ScanlineOutOfRange { scanline, scanlines_per_frame });
+        }
+
+        self.try_run_to_frame(self.ppu.frame + 1)?;
+        while self.ppu.scanline < scanline {
+            self.try_cycle()?;
+        }
+
+        Ok(())
+    }
+
+    /// Cycle the NES until `condition` is met, or until `max_cycles` CPU cycles have run.
+    ///
+    /// The condition is checked after every CPU cycle so at least one cycle runs, even if the
+    /// condition already holds.
+    pub fn run_until(&mut self, condition: Condition, max_cycles: u64) -> StopReason {
+        self.try_run_until(condition, max_cycles).expect("failed to run until condition")
+    }
+
+    /// Like `run_until` but returns an error instead of panicking if the CPU can't continue.
+    pub fn try_run_until(&mut self, condition: Condition, max_cycles: u64) -> Result<StopReason, Error> {
+        if let Condition::PpuAccess(watchpoint) = condition {
+            self.ppu.watchpoint = Some(watchpoint);
+            self.ppu.watch_hit = None;
+        }
+
+        let reason = self.run_until_met(condition, max_cycles);
+        self.ppu.watchpoint = None;
+        reason
+    }
+
+    fn run_until_met(&mut self, condition: Condition, max_cycles: u64) -> Result<StopReason, Error> {
+        let mut vblanks = 0;
+
+        for _ in 0..max_cycles {
+            let was_in_vblank = self.ppu.ppustatus.in_vblank;
+            self.try_cycle()?;
+            if self.ppu.ppustatus.in_vblank && !was_in_vblank {
+                vblanks += 1;
+            }
+
+            let met = match condition {
+                // `pc` moves past the opcode partway through an instruction, so only stop between
+                // instructions.
+                Condition::ProgramCounter(pc) => self.cpu.at_instruction_boundary() && self.cpu.pc == pc,
+                Condition::MemoryEquals { address, value } => {
+                    CpuBus::peek_u8(&self.wram, &self.cartridge, address) == value
+                },
+                Condition::Vblanks(count) => vblanks >= count,
+                Condition::PpuAccess(_) => self.ppu.watch_hit.is_some(),
+            };
+
+            if met {
+                return Ok(StopReason::Condition);
+            }
+        }
+
+        Ok(StopReason::CycleLimit)
+    }
+
+    /// Where the PPU was when the last `Condition::PpuAccess` was met.
+    pub fn ppu_watch_hit(&self) -> Option<PpuWatchHit> {
+        self.ppu.watch_hit
+    }
+
+    /// A fingerprint of the emulated machine state: the ROM, the CPU's registers and interrupt
+    /// lines, WRAM, the controllers' shift registers and everything in `save_cartridge_state` and
+    /// `save_ppu_state`. Two consoles with the same `state_hash` will behave identically given the
+    /// same inputs.
+    ///
+    /// The CPU is only hashed between instructions in detail. Mid-instruction it's summarised by
+    /// `instruction_pc` and `instruction_cycle`, and an OAM DMA in progress isn't hashed at all.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write(&self.rom_crc32().to_le_bytes());
+
+        let cpu = &self.cpu;
+        hasher.write(&[cpu.a, cpu.x, cpu.y, cpu.p.0, cpu.sp]);
+        hasher.write(&cpu.pc.to_le_bytes());
+        hasher.write(&[cpu.nmi as u8, cpu.irq as u8]);
+        hasher.write(&cpu.elapsed_cycles.to_le_bytes());
+        hasher.write(&cpu.wait_cycles.to_le_bytes());
+        hasher.write(&cpu.instruction_pc().to_le_bytes());
+        hasher.write(&[cpu.instruction_cycle(), cpu.in_interrupt_sequence() as u8, cpu.is_jammed() as u8]);
+
+        hasher.write(&self.wram);
+
+        let mut controllers = StateWriter::new();
+        for controller in &self.controllers {
+            controller.save_state(&mut controllers);
+        }
+        hasher.write(&controllers.into_bytes());
+
+        hasher.write(&self.save_cartridge_state());
+        hasher.write(&self.save_ppu_state());
+
+        hasher.finish()
+    }
+
+    pub fn controller(&self, player: Player) -> &Controller {
+        &self.controllers[player.index()]
+    }
+
+    pub fn controller_mut(&mut self, player: Player) -> &mut Controller {
+        &mut self.controllers[player.index()]
+    }
+
+    /// Plug `device` into the Famicom expansion port, replacing any existing device.
+    pub fn set_expansion_device(&mut self, device: Option<Box<dyn ExpansionDevice>>) {
+        self.expansion_device = device;
+    }
+
+    /// The device in the expansion port if it is a `D`.
+    pub fn expansion_device_mut<D: ExpansionDevice>(&mut self) -> Option<&mut D> {
+        self.expansion_device.as_mut()?.as_any_mut().downcast_mut::<D>()
+    }
+
+    /// Capture the cartridge's banking registers, IRQ counters and RAM. The state can be restored
+    /// with `load_cartridge_state` on a console running the same ROM.
+    pub fn save_cartridge_state(&self) -> Vec<u8> {
+        let mut state = StateWriter::new();
+        self.cartridge.save_state(&mut state);
+        state.into_bytes()
+    }
+
+    pub fn load_cartridge_state(&mut self, state: &[u8]) -> Result<(), StateError> {
+        self.cartridge.load_state(&mut StateReader::new(state))
+    }
+
+    /// Capture the PPU and the nametable RAM it draws from, see `RP2C02::save_state`. Combined with
+    /// `save_cartridge_state` this is everything outside the CPU and WRAM.
+    pub fn save_ppu_state(&self) -> Vec<u8> {
+        let mut state = StateWriter::new();
+        self.ppu.save_state(&mut state);
+        state.write_memory(&self.ciram);
+        state.write_u32(self.ppu_clock_remainder);
+        state.into_bytes()
+    }
+
+    pub fn load_ppu_state(&mut self, state: &[u8]) -> Result<(), StateError> {
+        let mut state = StateReader::new(state);
+        self.ppu.load_state(&mut state)?;
+        state.read_memory(&mut self.ciram)?;
+        self.ppu_clock_remainder = state.read_u32()?;
+
+        Ok(())
+    }
+
+    /// How often each button has been pressed since the console was created, or since the stats
+    /// were restored with `load_input_stats`.
+    pub fn input_stats(&self) -> &InputStats {
+        &self.input_stats
+    }
+
+    pub fn reset_input_stats(&mut self) {
+        self.input_stats = InputStats::new();
+    }
+
+    /// Capture the input stats so the next session of the same game can carry on counting with
+    /// `load_input_stats`. `rom_crc32` is a good key for storing them per game.
+    pub fn save_input_stats(&self) -> Vec<u8> {
+        let mut state = StateWriter::new();
+        self.input_stats.save_state(&mut state);
+        state.into_bytes()
+    }
+
+    pub fn load_input_stats(&mut self, state: &[u8]) -> Result<(), StateError> {
+        self.input_stats = InputStats::load_state(&mut StateReader::new(state))?;
+        Ok(())
+    }
+
+    /// The CRC-32 of the cartridge's PRG and CHR ROM, which identifies the game regardless of
+    /// the file's header. This is the checksum No-Intro DATs use.
+    pub fn rom_crc32(&self) -> u32 {
+        let rom = &self.cartridge.rom;
+        nestalgic_rom::dat::crc32(&[&rom.prg_rom[..], &rom.chr_rom[..]].concat())
+    }
+
+    /// The MD5 of the cartridge's PRG and CHR ROM, which is how FCEUX identifies a game.
+    pub fn rom_md5(&self) -> [u8; 16] {
+        let rom = &self.cartridge.rom;
+        nestalgic_rom::md5::md5(&[&rom.prg_rom[..], &rom.chr_rom[..]].concat())
+    }
+
+    /// The 2KB of RAM inside the console, mirrored through `0x0000-0x1FFF` on the CPU bus.
+    pub fn wram(&self) -> &[u8] {
+        &self.wram
+    }
+
+    /// Read CPU memory without side effects, see `CpuBus::peek_u8`.
+    pub fn peek_u8(&self, address: u16) -> u8 {
+        CpuBus::peek_u8(&self.wram, &self.cartridge, address)
+    }
+
+    /// Change a byte of the console's RAM (`0x0000`-`0x1FFF`, mirrored every 2KB) without the CPU
+    /// noticing. Returns `false` for addresses outside RAM, since writing anywhere else has side
+    /// effects such as switching banks.
+    pub fn poke_u8(&mut self, address: u16, value: u8) -> bool {
+        match address {
+            0x0000..=0x1FFF => {
+                self.wram[(address & 0x07FF) as usize] = value;
+                true
+            },
+            _ => false,
+        }
     }
 
     pub fn pixels(&self) -> &[Pixel; Nestalgic::SCREEN_PIXELS] {
         &self.ppu.pixels
     }
 
+    /// The palette index (0x00-0x3F) of each pixel on screen, for front ends that apply their own
+    /// palette or NTSC filter. Greyscale is already applied but colour emphasis isn't, see
+    /// `PPUMask`.
+    pub fn palette_indices(&self) -> &[u8; Nestalgic::SCREEN_PIXELS] {
+        &self.ppu.palette_indices
+    }
+
+    /// Copy the screen into `frame` as RGBA bytes in row order, e.g. straight into a front end's
+    /// texture. The PPU already stores its pixels as RGBA so this is a single copy.
+    ///
+    /// # Panics
+    ///
+    /// If `frame` isn't exactly `SCREEN_PIXELS * 4` bytes long.
+    pub fn render_into(&self, frame: &mut [u8]) {
+        assert!(
+            frame.len() == Nestalgic::SCREEN_PIXELS * 4,
+            "frame must be {} bytes, got {}",
+            Nestalgic::SCREEN_PIXELS * 4,
+            frame.len()
+        );
+
+        frame.copy_from_slice(Pixel::as_rgba_bytes(&self.ppu.pixels[..]));
+    }
+
+    /// Whether a frame has finished since the last call. Front ends that run the console by time
+    /// (e.g. with `tick`) can check this to present each frame exactly once.
+    pub fn take_frame_complete(&mut self) -> bool {
+        std::mem::take(&mut self.frame_complete)
+    }
+
+    /// Call `hook` at the start of every scanline, before the PPU draws anything on it. This lets
+    /// debuggers and tests inspect mid-frame state like the scroll position without stopping the
+    /// console. `None` removes the hook.
+    pub fn set_scanline_hook(&mut self, hook: Option<ScanlineHook>) {
+        self.scanline_hook = hook;
+    }
+
+    /// Give `cpu_tracer` a `TraceLine` for every instruction the CPU runs, or stop tracing with
+    /// `None`. See `CpuTracer`.
+    pub fn set_cpu_tracer(&mut self, cpu_tracer: Option<Box<dyn CpuTracer>>) {
+        self.cpu_tracer = cpu_tracer;
+    }
+
+    /// Send every frame the console finishes to `video_sink`, see `VideoSink`.
+    pub fn add_video_sink(&mut self, video_sink: Box<dyn VideoSink>) {
+        self.video_sinks.push(video_sink);
+    }
+
+    pub fn clear_video_sinks(&mut self) {
+        self.video_sinks.clear();
+    }
+
+    /// Start blending every frame the console renders, e.g. while fast-forwarding so sprites that
+    /// flicker on alternate frames stay visible. `None` stops blending.
+    pub fn set_frame_blend(&mut self, mode: Option<FrameBlend>) {
+        self.frame_blender = mode.map(FrameBlender::new);
+    }
+
+    /// The blend of every frame completed since the last call, or `None` if blending is off or no
+    /// frames have completed.
+    pub fn take_blended_pixels(&mut self) -> Option<Box<[Pixel; Nestalgic::SCREEN_PIXELS]>> {
+        self.frame_blender.as_mut()?.take()
+    }
+
     pub fn pattern_table_left(&self) -> Texture {
-        let chr_data = (0..=0x0FFF)
-            .map(|a| self.cartridge.mapper.ppu_read_u8(a as u16))
+        self.pattern_table(0x0000, Texture::DEBUG_PALETTE)
+    }
+
+    pub fn pattern_table_right(&self) -> Texture {
+        self.pattern_table(0x1000, Texture::DEBUG_PALETTE)
+    }
+
+    /// The left pattern table drawn with one of the 8 palettes in palette RAM, see
+    /// `RP2C02::palette_colors`.
+    pub fn pattern_table_left_in_palette(&self, palette: u8) -> Texture {
+        self.pattern_table(0x0000, self.ppu.palette_colors(palette))
+    }
+
+    /// The right pattern table drawn with one of the 8 palettes in palette RAM, see
+    /// `RP2C02::palette_colors`.
+    pub fn pattern_table_right_in_palette(&self, palette: u8) -> Texture {
+        self.pattern_table(0x1000, self.ppu.palette_colors(palette))
+    }
+
+    /// The colours of all 8 palettes currently in palette RAM: 4 background palettes followed by 4
+    /// sprite palettes. See `RP2C02::palette_colors`.
+    pub fn palettes(&self) -> [[Pixel; 4]; 8] {
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|palette| self.ppu.palette_colors(palette))
+    }
+
+    /// All 64 sprites in OAM, see `RP2C02::sprites`.
+    pub fn sprites(&self) -> Vec<SpriteInfo> {
+        self.ppu.sprites()
+    }
+
+    /// Draw `sprite` as it would appear on screen: 8 pixels wide, `sprite.height` pixels tall and
+    /// flipped by its attributes. Transparent pixels are drawn with the backdrop colour.
+    pub fn sprite_texture(&self, sprite: &SpriteInfo) -> Texture {
+        let chr_data = (0..sprite.height as u16 * 2)
+            .map(|offset| self.cartridge.mapper.ppu_read_u8(sprite.pattern_address + offset))
             .collect::<Vec<u8>>();
 
-        Texture::from_bitplanes(&chr_data, 16, 128, 128)
+        Texture::from_bitplanes_in_palette(&chr_data, 16, 8, sprite.height as usize, self.ppu.palette_colors(sprite.palette))
+            .flipped(sprite.flip_horizontally, sprite.flip_vertically)
     }
 
-    pub fn pattern_table_right(&self) -> Texture {
-        let chr_data = (0x1000..=0x1FFF)
-            .map(|a| self.cartridge.mapper.ppu_read_u8(a as u16))
+    /// The pattern table at `address` (`0x0000` or `0x1000`) drawn with `palette`, e.g. to draw
+    /// tiles with colours that aren't in palette RAM.
+    pub fn pattern_table(&self, address: u16, palette: [Pixel; 4]) -> Texture {
+        let chr_data = (address..=address + 0x0FFF)
+            .map(|a| self.cartridge.mapper.ppu_read_u8(a))
             .collect::<Vec<u8>>();
 
-        Texture::from_bitplanes(&chr_data, 16, 128, 128)
+        Texture::from_bitplanes_in_palette(&chr_data, 16, 128, 128, palette)
+    }
+
+    /// All four nametables drawn with the current background pattern table, laid out as they are
+    /// addressed by the PPU:
+    ///
+    /// ```text
+    /// +--------+--------+
+    /// | 0x2000 | 0x2400 |
+    /// +--------+--------+
+    /// | 0x2800 | 0x2C00 |
+    /// +--------+--------+
+    /// ```
+    ///
+    /// Attribute tables are ignored, so every tile is drawn with the same colours as
+    /// `pattern_table_left`.
+    pub fn nametables(&self) -> Texture {
+        self.nametables_with_palette(Texture::DEBUG_PALETTE)
+    }
+
+    /// Like `nametables` but every tile is drawn with `palette` instead of
+    /// `Texture::DEBUG_PALETTE`.
+    pub fn nametables_with_palette(&self, palette: [Pixel; 4]) -> Texture {
+        self.render_nametables(|_, _| palette)
+    }
+
+    /// Like `nametables` but each tile is drawn with the background palette chosen by its
+    /// attribute table, giving the colours the PPU would draw.
+    pub fn nametables_in_color(&self) -> Texture {
+        self.render_nametables(|nametable_address, index| {
+            let (tile_x, tile_y) = (index % 32, index / 32);
+            let attribute_address = nametable_address + 0x3C0 + ((tile_y / 4) * 8 + tile_x / 4) as u16;
+            let attribute = PpuBus::peek_u8(&self.cartridge, &self.ciram, attribute_address);
+            let attribute_shift = ((tile_y % 4) / 2) * 4 + ((tile_x % 4) / 2) * 2;
+
+            self.ppu.palette_colors((attribute >> attribute_shift) & 0b11)
+        })
+    }
+
+    /// Draw every nametable tile with the palette returned by `tile_palette`, which is given the
+    /// address of the tile's nametable and the tile's index within it.
+    fn render_nametables(&self, tile_palette: impl Fn(u16, usize) -> [Pixel; 4]) -> Texture {
+        let pattern_table: u16 = if self.ppu.ppuctrl.get(PPUCtrlFlag::BackgroundPatternTable) {
+            0x1000
+        } else {
+            0x0000
+        };
+
+        let columns = Nestalgic::NAMETABLES_WIDTH / 8;
+        let rows = Nestalgic::NAMETABLES_HEIGHT / 8;
+
+        let tiles = (0..rows * columns).map(|i| {
+            let (tile_x, tile_y) = (i % columns, i / columns);
+            let nametable = (tile_y / 30) * 2 + tile_x / 32;
+            let index = (tile_y % 30) * 32 + tile_x % 32;
+            let nametable_address = 0x2000 + (nametable * 0x400) as u16;
+            let tile = PpuBus::peek_u8(&self.cartridge, &self.ciram, nametable_address + index as u16) as u16;
+
+            let chr = (0..16)
+                .map(|b| self.cartridge.mapper.ppu_read_u8(pattern_table + tile * 16 + b))
+                .collect::<Vec<u8>>();
+
+            Texture::from_tile(&chr, tile_palette(nametable_address, index))
+        }).collect::<Vec<Texture>>();
+
+        Texture::compose_grid(&tiles, columns)
     }
 }
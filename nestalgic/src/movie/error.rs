@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(PartialEq, Eq, Debug, Error)]
+pub enum MovieError {
+    #[error("Movie was recorded from state {expected:016X} but the console is in state {actual:016X}")]
+    InitialStateMismatch { expected: u64, actual: u64 },
+
+    #[error("Invalid FM2 movie, line {0} could not be parsed")]
+    InvalidFm2Line(usize),
+
+    #[error("Unsupported FM2 movie feature: {0}")]
+    UnsupportedFm2Feature(String),
+}
@@ -0,0 +1,251 @@
+use super::{Movie, MovieError, Result};
+use crate::{Button, Buttons, Nestalgic};
+use nestalgic_rom::md5::md5;
+
+/// The order FM2 lists buttons in each input column.
+const FM2_BUTTON_ORDER: [(Button, char); 8] = [
+    (Button::Right, 'R'),
+    (Button::Left, 'L'),
+    (Button::Down, 'D'),
+    (Button::Up, 'U'),
+    (Button::Start, 'T'),
+    (Button::Select, 'S'),
+    (Button::B, 'B'),
+    (Button::A, 'A'),
+];
+
+/// Support for FCEUX's FM2 movie format.
+///
+/// An FM2 file is a list of `key value` header lines followed by one input line per frame:
+///
+/// ```text
+/// version 3
+/// port0 1
+/// port1 1
+/// |0|R.......|........||
+/// ```
+///
+/// Each input line contains a command field (resets, coin inserts, etc...) followed by one field
+/// per port. Gamepad fields list the buttons in `RLDUTSBA` order where `.` or a space means the
+/// button is released.
+///
+/// We only support NTSC text movies that begin at power on with standard gamepads and no
+/// commands.
+///
+/// See also: https://fceux.com/web/help/fm2.html
+impl Movie {
+    /// Parse an FM2 movie.
+    ///
+    /// FM2 movies always begin at power on so `power_on` should be a console that has just been
+    /// created from the movies ROM. It's used to determine the movies `initial_state_hash`.
+    pub fn from_fm2(fm2: &str, power_on: &Nestalgic) -> Result<Movie> {
+        let mut movie = Movie::new(power_on.state_hash());
+
+        for (line_index, line) in fm2.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if line.starts_with('|') {
+                let buttons = parse_input_line(line, line_number)?;
+                movie.frames.push(buttons);
+                continue;
+            }
+
+            let (key, value) = match line.split_once(' ') {
+                Some((key, value)) => (key, value.trim()),
+                None => (line.trim(), ""),
+            };
+
+            match (key, value) {
+                ("", _) => {},
+                ("version", "3") => {},
+                ("version", version) => {
+                    return Err(MovieError::UnsupportedFm2Feature(format!("version {}", version)))
+                },
+                ("binary", "1") => {
+                    return Err(MovieError::UnsupportedFm2Feature("binary input log".to_string()))
+                },
+                ("savestate", _) => {
+                    return Err(MovieError::UnsupportedFm2Feature("savestate anchored movie".to_string()))
+                },
+                ("fourscore", "1") => {
+                    return Err(MovieError::UnsupportedFm2Feature("fourscore".to_string()))
+                },
+                ("palFlag", "1") => {
+                    return Err(MovieError::UnsupportedFm2Feature("PAL timing".to_string()))
+                },
+                ("port0", port) | ("port1", port) if port != "0" && port != "1" => {
+                    return Err(MovieError::UnsupportedFm2Feature(format!("{} device {}", key, port)))
+                },
+
+                // Everything else is metadata we don't need
+                _ => {},
+            }
+        }
+
+        Ok(movie)
+    }
+
+    /// Write this movie in the FM2 format.
+    ///
+    /// `nestalgic` should be running the ROM the movie was recorded on so FCEUX can check the
+    /// movie matches its ROM. `rom_filename` is the ROM's file name without the extension, FCEUX
+    /// only uses it for display.
+    pub fn to_fm2(&self, nestalgic: &Nestalgic, rom_filename: &str) -> String {
+        let mut fm2 = String::new();
+
+        fm2.push_str("version 3\n");
+        fm2.push_str("emuVersion 0\n");
+        fm2.push_str("rerecordCount 0\n");
+        fm2.push_str(&format!("romFilename {}\n", rom_filename));
+        fm2.push_str(&format!("romChecksum base64:{}\n", base64(&nestalgic.rom_md5())));
+        fm2.push_str(&format!("guid {}\n", self.guid()));
+        fm2.push_str("palFlag 0\n");
+        fm2.push_str("fourscore 0\n");
+        fm2.push_str("port0 1\n");
+        fm2.push_str("port1 1\n");
+        fm2.push_str("port2 0\n");
+        fm2.push_str("comment author nestalgic\n");
+
+        for [player_one, player_two] in &self.frames {
+            fm2.push_str(&format!(
+                "|0|{}|{}||\n",
+                format_buttons(*player_one),
+                format_buttons(*player_two)
+            ));
+        }
+
+        fm2
+    }
+
+    /// FCEUX expects every movie to have a unique GUID. We derive ours from the movie's contents
+    /// so exporting the same movie twice gives the same file.
+    fn guid(&self) -> String {
+        let mut contents = self.initial_state_hash.to_le_bytes().to_vec();
+        for [player_one, player_two] in &self.frames {
+            contents.push(player_one.0);
+            contents.push(player_two.0);
+        }
+
+        let hex = md5(&contents).iter().map(|byte| format!("{:02X}", byte)).collect::<String>();
+        format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+    }
+}
+
+fn parse_input_line(line: &str, line_number: usize) -> Result<[Buttons; 2]> {
+    // A line looks like `|commands|port0|port1|port2|`, splitting on `|` gives us an empty
+    // field before the first `|` which we skip.
+    let mut fields = line.split('|').skip(1);
+
+    let commands = fields.next().ok_or(MovieError::InvalidFm2Line(line_number))?;
+    match commands.trim().parse::<u8>() {
+        Ok(0) => {},
+        Ok(command) => return Err(MovieError::UnsupportedFm2Feature(format!("command {}", command))),
+        Err(_) => return Err(MovieError::InvalidFm2Line(line_number)),
+    }
+
+    let player_one = fields.next().ok_or(MovieError::InvalidFm2Line(line_number))?;
+    let player_two = fields.next().unwrap_or("");
+
+    Ok([
+        parse_buttons(player_one, line_number)?,
+        parse_buttons(player_two, line_number)?,
+    ])
+}
+
+fn parse_buttons(field: &str, line_number: usize) -> Result<Buttons> {
+    // Ports without a gamepad plugged in have an empty field
+    if field.is_empty() {
+        return Ok(Buttons::default());
+    }
+
+    if field.chars().count() != FM2_BUTTON_ORDER.len() {
+        return Err(MovieError::InvalidFm2Line(line_number));
+    }
+
+    let buttons = field
+        .chars()
+        .zip(FM2_BUTTON_ORDER.iter())
+        .fold(Buttons::default(), |buttons, (c, &(button, _))| {
+            buttons.with(button, c != '.' && c != ' ')
+        });
+
+    Ok(buttons)
+}
+
+/// Standard base64 with padding, used for FM2 binary header values.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+fn format_buttons(buttons: Buttons) -> String {
+    FM2_BUTTON_ORDER
+        .iter()
+        .map(|&(button, c)| if buttons.get(button) { c } else { '.' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn parse_buttons_reads_rldutsba_order() {
+        let buttons = parse_buttons("R..U...A", 1).unwrap();
+
+        let expected = Buttons::default()
+            .with(Button::Right, true)
+            .with(Button::Up, true)
+            .with(Button::A, true);
+
+        assert_eq!(buttons, expected);
+    }
+
+    #[test]
+    pub fn parse_buttons_treats_spaces_as_released() {
+        assert_eq!(parse_buttons("    T   ", 1).unwrap(), Buttons::default().with(Button::Start, true));
+    }
+
+    #[test]
+    pub fn parse_input_line_rejects_commands() {
+        let result = parse_input_line("|1|........|........||", 3);
+        assert_eq!(result, Err(MovieError::UnsupportedFm2Feature("command 1".to_string())));
+    }
+
+    #[test]
+    pub fn parse_input_line_with_unplugged_second_port() {
+        let result = parse_input_line("|0|.......A|||", 3).unwrap();
+        assert_eq!(result, [Buttons::default().with(Button::A, true), Buttons::default()]);
+    }
+
+    #[test]
+    pub fn base64_pads_partial_groups() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+    }
+
+    #[test]
+    pub fn format_buttons_roundtrip() {
+        let buttons = Buttons::default()
+            .with(Button::Left, true)
+            .with(Button::Select, true)
+            .with(Button::B, true);
+
+        assert_eq!(format_buttons(buttons), ".L...SB.");
+        assert_eq!(parse_buttons(&format_buttons(buttons), 1).unwrap(), buttons);
+    }
+}
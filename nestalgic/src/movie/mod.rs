@@ -0,0 +1,116 @@
+mod error;
+mod fm2;
+
+pub use error::MovieError;
+
+use crate::{Buttons, Nestalgic, Player};
+
+pub type Result<A> = std::result::Result<A, MovieError>;
+
+/// A `Movie` is a recording of the controller input for every frame of a play session.
+///
+/// Since the emulator is deterministic, replaying a `Movie` from the same starting state always
+/// produces the same result. This makes movies useful for automated gameplay regression tests.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Movie {
+    /// The `Nestalgic::state_hash` of the console when recording started.
+    pub initial_state_hash: u64,
+
+    /// The buttons pressed by each player, one entry per frame.
+    ///
+    /// These are the buttons the console saw, so turbo buttons are already expanded into
+    /// their pressed and released frames.
+    pub frames: Vec<[Buttons; 2]>,
+}
+
+impl Movie {
+    pub fn new(initial_state_hash: u64) -> Movie {
+        Movie {
+            initial_state_hash,
+            frames: Vec::new(),
+        }
+    }
+}
+
+/// Records controller input into a `Movie` as frames are run.
+pub struct MovieRecorder {
+    movie: Movie,
+}
+
+impl MovieRecorder {
+    /// Start recording from the current state of `nestalgic`.
+    pub fn start(nestalgic: &Nestalgic) -> MovieRecorder {
+        MovieRecorder {
+            movie: Movie::new(nestalgic.state_hash()),
+        }
+    }
+
+    /// Record the current controller state then run a single frame.
+    pub fn run_frame(&mut self, nestalgic: &mut Nestalgic) {
+        let frame = nestalgic.ppu.frame;
+        let buttons = [
+            nestalgic.controller(Player::One).effective_buttons(frame),
+            nestalgic.controller(Player::Two).effective_buttons(frame),
+        ];
+
+        self.movie.frames.push(buttons);
+        nestalgic.run_frame();
+    }
+
+    pub fn finish(self) -> Movie {
+        self.movie
+    }
+}
+
+/// Replays the input from a `Movie`, one frame at a time.
+pub struct MoviePlayer {
+    movie: Movie,
+    frame: usize,
+}
+
+impl MoviePlayer {
+    /// Prepare to play `movie` on `nestalgic`.
+    ///
+    /// Fails if `nestalgic` isn't in the state the movie was recorded from, since the
+    /// playback would desync.
+    pub fn new(movie: Movie, nestalgic: &Nestalgic) -> Result<MoviePlayer> {
+        let actual = nestalgic.state_hash();
+        if actual != movie.initial_state_hash {
+            return Err(MovieError::InitialStateMismatch {
+                expected: movie.initial_state_hash,
+                actual,
+            });
+        }
+
+        Ok(MoviePlayer { movie, frame: 0 })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.movie.frames.len()
+    }
+
+    /// Apply the recorded input for the next frame and run it.
+    ///
+    /// Returns false without running anything if the movie has finished.
+    pub fn run_frame(&mut self, nestalgic: &mut Nestalgic) -> bool {
+        let buttons = match self.movie.frames.get(self.frame) {
+            Some(buttons) => *buttons,
+            None => return false,
+        };
+
+        for (player, buttons) in [Player::One, Player::Two].iter().zip(buttons.iter()) {
+            let controller = nestalgic.controller_mut(*player);
+            controller.buttons = *buttons;
+            controller.turbo_buttons = Buttons::default();
+        }
+
+        nestalgic.run_frame();
+        self.frame += 1;
+
+        true
+    }
+
+    pub fn movie(&self) -> &Movie {
+        &self.movie
+    }
+}
@@ -2,160 +2,305 @@ use nestalgic_mos6502::MOS6502;
 pub(crate) use nestalgic_mos6502::mos6502::Bus;
 
 use crate::cartridge::Cartridge;
+use crate::controller::{Controller, ExpansionDevice, EXPANSION_OUT_MASK, EXPANSION_4016_MASK, EXPANSION_4017_MASK};
 use crate::rp2c02::PPUMask;
+use crate::vram_write_checker::VramWriteChecker;
+use crate::test_registers::TestRegisterLog;
 
-use super::WRAM;
+use super::{WRAM, CIRAM};
 use super::rp2c02::RP2C02;
 
-
-// pub struct NesBus<'a> {
-//     pub wram: &'a mut WRAM,
-//     pub ppu: &'a mut RP2C02,
-//     pub cartridge: &'a mut Cartridge,
-// }
-
-// pub struct PpuBus<'a> {
-//     pub cartridge: &'a mut Cartridge
-// }
-
-// impl <'a> Bus for NesBus<'a> {
-//     fn read_u8(&mut self, address: u16) -> u8 {
-//         match address {
-//             0x4020..=0xFFFF  => self.cartridge.mapper.cpu_read_u8(address),
-//             0x2000 => self.ppu.ppuctrl.0,
-//             0x2007 => {
-//                 self.ppu.read_ppudata(self)
-//             },
-//             // TODO 0x2001..=2007
-//             0x0000..=0x1FFF  => self.wram[(address & 0x0800) as usize],
-//             _ => 0
-//         }
-//     }
-
-//     fn write_u8(&mut self, address: u16, data: u8) {
-//         todo!()
-//     }
-// }
-
-// pub fn new_nes_bus<'a>(
-//     wram: &'a mut WRAM,
-//     ppu: &'a mut RP2C02,
-//     cartridge: &'a mut Cartridge
-// ) -> NesBus<'a> {
-//     NesBus {
-//         wram,
-//         ppu,
-//         cartridge
-//     }
-// }
-
-// impl <'a, T> NesBus<'a, T> {
-//     pub fn as_cpu_bus<'b>(&'b mut self) -> CpuBus<'b> {
-//         CpuBus {
-//             bus_type: PhantomData,
-//             wram: self.wram,
-//             ppu: self.ppu,
-//             cartridge: self.cartridge
-//         }
-//     }
-
-//     pub fn as_ppu_bus<'b>(&'b mut self) -> PpuBus<'b> {
-//         PpuBus {
-//             bus_type: PhantomData,
-//             wram: self.wram,
-//             ppu: self.ppu,
-//             cartridge: self.cartridge
-//         }
-//         // self.bus_type = PhantomData as PhantomData<PpuBusType>;
-//         // let NesBus { bus_type: _ } = self
-//         // self as PpuBus
-//     }
-// }
-
-// struct CpuBusType;
-// pub type CpuBus<'a> = NesBus<'a, CpuBusType>;
-
-// struct PpuBusType;
-// pub type PpuBus<'a> = NesBus<'a, PpuBusType>;
-
-// impl Bus for CpuBus<'_> {
-//     fn read_u8(&self, address: u16) -> u8 {
-//         match address {
-//             0x4020..=0xFFFF  => self.cartridge.mapper.cpu_read_u8(address),
-//             0x2000 => self.ppu.ppuctrl.0,
-//             0x2007 => {
-//                 let ppu_bus = self.as_ppu_bus();
-//                 let ppu = self.ppu;
-//                 ppu.read_ppudata(&mut ppu_bus)
-//             },
-//             // TODO 0x2001..=2007
-//             0x0000..=0x1FFF  => self.wram[(address & 0x0800) as usize],
-//             _ => 0
-//         }
-//     }
-
-//     fn write_u8(&mut self, address: u16, data: u8) {
-//         let mut ppu_bus = self.as_ppu_bus();
-//         let ppu = self.ppu;
-//         let val = ppu.read_ppudata(&mut ppu_bus);
-
-//         todo!()
-//     }
-// }
-
-// impl <'a> Bus for PpuBus<'a> {
-//     fn read_u8(&self, address: u16) -> u8 {
-//         todo!()
-//     }
-
-//     fn write_u8(&mut self, address: u16, data: u8) {
-//         todo!()
-//     }
-// }
-
 pub struct CpuBus<'a> {
     pub wram: &'a mut WRAM,
     pub ppu: &'a mut RP2C02,
     pub cartridge: &'a mut Cartridge,
+    pub ciram: &'a mut CIRAM,
+    pub controllers: &'a mut [Controller; 2],
+    pub expansion_device: &'a mut Option<Box<dyn ExpansionDevice>>,
+    pub vram_write_checker: &'a mut Option<VramWriteChecker>,
+    pub test_register_log: &'a mut TestRegisterLog,
+
+    /// The address of the instruction making this access, for diagnostics
+    pub pc: u16,
+}
+
+impl <'a> CpuBus<'a> {
+    /// Read CPU memory without any side effects, for debuggers. The PPU, APU and controller
+    /// registers (`0x2000`-`0x401F`) change state when read so they always read as `0`.
+    pub fn peek_u8(wram: &WRAM, cartridge: &Cartridge, address: u16) -> u8 {
+        match CpuRegion::for_address(address) {
+            CpuRegion::Wram(index) => wram[index],
+            CpuRegion::Expansion | CpuRegion::Sram | CpuRegion::Prg => cartridge.mapper.cpu_read_u8(address),
+            CpuRegion::PpuRegisters | CpuRegion::ApuIo | CpuRegion::TestMode => 0,
+        }
+    }
 }
 
 impl <'a> Bus for CpuBus<'a> {
     fn read_u8(&mut self, address: u16) -> u8 {
-        match address {
-            0x4020..=0xFFFF => self.cartridge.mapper.cpu_read_u8(address),
-            0x2000..=0x3FFF => {
-                let mut ppu_bus = PpuBus { cartridge: self.cartridge };
-                let value = self.ppu.cpu_mapped_read_u8(&mut ppu_bus, address);
-                value
+        match CpuRegion::for_address(address) {
+            CpuRegion::Wram(index) => self.wram[index],
+            CpuRegion::PpuRegisters => {
+                let mut ppu_bus = PpuBus { cartridge: self.cartridge, ciram: self.ciram };
+                self.ppu.cpu_mapped_read_u8(&mut ppu_bus, address)
             },
-            0x0000..=0x1FFF  => self.wram[(address & 0x07FF) as usize],
-            _ => 0
+            CpuRegion::ApuIo => match address {
+                // We don't track the data bus so we approximate open bus with the high byte of the
+                // address, which is what the CPU last fetched when reading with absolute addressing.
+                0x4016 => {
+                    let expansion = self.expansion_device.as_mut().map_or(0, |d| d.read_4016());
+                    let port = self.controllers[0].read_port(self.ppu.frame, (address >> 8) as u8);
+                    port | (expansion & EXPANSION_4016_MASK)
+                },
+                0x4017 => {
+                    let expansion = self.expansion_device.as_mut().map_or(0, |d| d.read_4017());
+                    let port = self.controllers[1].read_port(self.ppu.frame, (address >> 8) as u8);
+                    port | (expansion & EXPANSION_4017_MASK)
+                },
+                // TODO: APU
+                _ => 0,
+            },
+            CpuRegion::TestMode => {
+                self.test_register_log.record(self.pc, address, false);
+                (address >> 8) as u8
+            },
+            CpuRegion::Expansion | CpuRegion::Sram | CpuRegion::Prg => self.cartridge.mapper.cpu_read_u8(address),
         }
     }
 
     fn write_u8(&mut self, address: u16, data: u8) {
-        match address {
-            0x4020..=0xFFFF => self.cartridge.mapper.cpu_write_u8(address, data),
-            0x2000..=0x3FFF => {
-                let mut ppu_bus = PpuBus { cartridge: self.cartridge };
+        match CpuRegion::for_address(address) {
+            CpuRegion::Wram(index) => self.wram[index] = data,
+            CpuRegion::PpuRegisters => {
+                if let (0x2007, Some(checker)) = (address & 0x2007, self.vram_write_checker.as_mut()) {
+                    checker.check(self.pc, self.ppu, data);
+                }
+
+                let mut ppu_bus = PpuBus { cartridge: self.cartridge, ciram: self.ciram };
                 self.ppu.cpu_mapped_write_u8(&mut ppu_bus, address, data)
             },
-            0x0000..=0x1FFF => self.wram[(address & 0x07FF) as usize] = data,
-            _ => ()
+            CpuRegion::ApuIo => if address == 0x4016 {
+                // Both controller ports share the strobe line
+                for controller in self.controllers.iter_mut() {
+                    controller.write_strobe(data, self.ppu.frame);
+                }
+
+                if let Some(device) = self.expansion_device.as_mut() {
+                    device.write(data & EXPANSION_OUT_MASK);
+                }
+            },
+            CpuRegion::TestMode => self.test_register_log.record(self.pc, address, true),
+            CpuRegion::Expansion | CpuRegion::Sram | CpuRegion::Prg => self.cartridge.mapper.cpu_write_u8(address, data),
         }
     }
 }
 
+/// The parts of the CPU's address space, each handled by a different part of the console.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/CPU_memory_map
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+enum CpuRegion {
+    /// `0x0000`-`0x1FFF`: An index into the 2kb of work RAM, which is mirrored every `0x800` bytes
+    Wram(usize),
+
+    /// `0x2000`-`0x3FFF`: The 8 PPU registers, mirrored every 8 bytes
+    PpuRegisters,
+
+    /// `0x4000`-`0x4017`: APU, OAM DMA and controller registers
+    ApuIo,
+
+    /// `0x4018`-`0x401F`: APU and I/O test registers, which are disabled on retail consoles. See
+    /// `TestRegisterLog`.
+    TestMode,
+
+    /// `0x4020`-`0x5FFF`: Cartridge expansion area. Mappers like MMC5, Namco 163 and the Famicom
+    /// Disk System put registers and extra RAM here.
+    Expansion,
+
+    /// `0x6000`-`0x7FFF`: Cartridge RAM, often battery backed
+    Sram,
+
+    /// `0x8000`-`0xFFFF`: Cartridge PRG ROM, usually banked by the mapper
+    Prg,
+}
+
+impl CpuRegion {
+    fn for_address(address: u16) -> CpuRegion {
+        match address {
+            0x0000..=0x1FFF => CpuRegion::Wram((address & 0x07FF) as usize),
+            0x2000..=0x3FFF => CpuRegion::PpuRegisters,
+            0x4000..=0x4017 => CpuRegion::ApuIo,
+            0x4018..=0x401F => CpuRegion::TestMode,
+            0x4020..=0x5FFF => CpuRegion::Expansion,
+            0x6000..=0x7FFF => CpuRegion::Sram,
+            0x8000..=0xFFFF => CpuRegion::Prg,
+        }
+    }
+}
+
+/// The PPU's 14-bit address space:
+///
+/// - `0x0000`-`0x1FFF`: Pattern tables, from the cartridge's CHR ROM or RAM
+/// - `0x2000`-`0x3EFF`: Nametables, from CIRAM (or the cartridge) depending on mirroring
+/// - `0x3F00`-`0x3FFF`: Palette RAM. This is inside the PPU so it never reaches the bus, see
+///   `RP2C02::read_ppudata`.
+///
+/// Addresses above `0x3FFF` mirror the range below.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_memory_map
 pub struct PpuBus<'a> {
-    pub cartridge: &'a mut Cartridge
+    pub cartridge: &'a mut Cartridge,
+    pub ciram: &'a mut CIRAM,
+}
+
+impl <'a> PpuBus<'a> {
+    /// Read PPU memory without any of the side effects of the PPU reading it, for debuggers.
+    pub fn peek_u8(cartridge: &Cartridge, ciram: &CIRAM, address: u16) -> u8 {
+        let address = address & 0x3FFF;
+        match NametableRam::for_address(cartridge, address) {
+            Some(NametableRam::Ciram(index)) => ciram[index],
+            Some(NametableRam::FourScreen(index)) => cartridge.four_screen_ram[index],
+            None => cartridge.mapper.ppu_read_u8(address),
+        }
+    }
 }
 
 impl <'a> Bus for PpuBus<'a> {
     fn read_u8(&mut self, address: u16) -> u8 {
-        self.cartridge.mapper.ppu_read_u8(address)
+        let address = address & 0x3FFF;
+        self.cartridge.observe_ppu_address(address);
+        PpuBus::peek_u8(self.cartridge, self.ciram, address)
     }
 
     fn write_u8(&mut self, address: u16, data: u8) {
-        self.cartridge.mapper.ppu_write_u8(address, data)
+        let address = address & 0x3FFF;
+        self.cartridge.observe_ppu_address(address);
+        match NametableRam::for_address(self.cartridge, address) {
+            Some(NametableRam::Ciram(index)) => self.ciram[index] = data,
+            Some(NametableRam::FourScreen(index)) => self.cartridge.four_screen_ram[index] = data,
+            None => self.cartridge.mapper.ppu_write_u8(address, data),
+        }
+    }
+}
+
+/// Where a nametable byte is stored.
+enum NametableRam {
+    /// An index into the console's 2kb of nametable RAM
+    Ciram(usize),
+
+    /// An index into the cartridge's extra nametable RAM
+    FourScreen(usize),
+}
+
+impl NametableRam {
+    /// `0x2000`-`0x3EFF` are nametables, with `0x3000`-`0x3EFF` mirroring `0x2000`-`0x2EFF`.
+    /// Everything else is handled by the cartridge.
+    fn for_address(cartridge: &Cartridge, address: u16) -> Option<NametableRam> {
+        if !(0x2000..=0x3EFF).contains(&address) {
+            return None;
+        }
+
+        let page = cartridge.mapper.mirroring().nametable_page(address);
+        let offset = (address & 0x03FF) as usize;
+
+        Some(match page {
+            0 | 1 => NametableRam::Ciram(page * 0x400 + offset),
+            _ => NametableRam::FourScreen((page - 2) * 0x400 + offset),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nestalgic_rom::nesrom::NESROM;
+
+    #[test]
+    pub fn cpu_addresses_decode_to_regions() {
+        let cases = [
+            (0x0000, CpuRegion::Wram(0x0000)),
+            (0x0801, CpuRegion::Wram(0x0001)),
+            (0x1FFF, CpuRegion::Wram(0x07FF)),
+            (0x2000, CpuRegion::PpuRegisters),
+            (0x3FFF, CpuRegion::PpuRegisters),
+            (0x4000, CpuRegion::ApuIo),
+            (0x4017, CpuRegion::ApuIo),
+            (0x4018, CpuRegion::TestMode),
+            (0x401F, CpuRegion::TestMode),
+            (0x4020, CpuRegion::Expansion),
+            (0x5FFF, CpuRegion::Expansion),
+            (0x6000, CpuRegion::Sram),
+            (0x7FFF, CpuRegion::Sram),
+            (0x8000, CpuRegion::Prg),
+            (0xFFFF, CpuRegion::Prg),
+        ];
+
+        for (address, region) in cases {
+            assert_eq!(CpuRegion::for_address(address), region, "address {:04X}", address);
+        }
+    }
+
+    /// Write `data` to PPU memory through `PPUADDR` and `PPUDATA`, as a game would.
+    fn write_ppu_memory(bus: &mut CpuBus, address: u16, data: &[u8]) {
+        let [low, high] = address.to_le_bytes();
+        bus.write_u8(0x2006, high);
+        bus.write_u8(0x2006, low);
+        for &byte in data {
+            bus.write_u8(0x2007, byte);
+        }
+    }
+
+    fn read_ppu_memory(bus: &mut CpuBus, address: u16) -> u8 {
+        let [low, high] = address.to_le_bytes();
+        bus.write_u8(0x2006, high);
+        bus.write_u8(0x2006, low);
+
+        // Outside palette RAM the first read only fills the PPU's read buffer
+        if address < 0x3F00 {
+            bus.read_u8(0x2007);
+            bus.write_u8(0x2006, high);
+            bus.write_u8(0x2006, low);
+        }
+
+        bus.read_u8(0x2007)
+    }
+
+    #[test]
+    pub fn ppudata_reaches_chr_nametables_and_palettes() {
+        // nestest uses horizontal mirroring, so 0x2400 is a mirror of 0x2000
+        let rom = NESROM::from_bytes(include_bytes!("../tests/fixtures/nestest.nes").to_vec())
+            .expect("Failed to load ROM");
+        let mut cartridge = Cartridge::from_rom(rom).expect("Failed to load cartridge");
+        let chr = cartridge.mapper.ppu_read_u8(0x0010);
+
+        let mut wram = [0; 2048];
+        let mut ppu = RP2C02::new();
+        let mut ciram = [0; 2048];
+        let mut controllers = [Controller::new(), Controller::new()];
+        let mut expansion_device = None;
+        let mut vram_write_checker = None;
+        let mut test_register_log = TestRegisterLog::new();
+        let mut bus = CpuBus {
+            wram: &mut wram,
+            ppu: &mut ppu,
+            cartridge: &mut cartridge,
+            ciram: &mut ciram,
+            controllers: &mut controllers,
+            expansion_device: &mut expansion_device,
+            vram_write_checker: &mut vram_write_checker,
+            test_register_log: &mut test_register_log,
+            pc: 0,
+        };
+
+        write_ppu_memory(&mut bus, 0x2400, &[0x12, 0x34]);
+        write_ppu_memory(&mut bus, 0x2C00, &[0x56]);
+        write_ppu_memory(&mut bus, 0x3F10, &[0x21]);
+
+        assert_eq!(read_ppu_memory(&mut bus, 0x0010), chr);
+        assert_eq!(read_ppu_memory(&mut bus, 0x2001), 0x34);
+        assert_eq!(read_ppu_memory(&mut bus, 0x3800), 0x56);
+        assert_eq!(read_ppu_memory(&mut bus, 0x3F00), 0x21);
+        assert_eq!(ciram[0..2], [0x12, 0x34]);
+        assert_eq!(ciram[0x400], 0x56);
     }
 }
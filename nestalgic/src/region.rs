@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crate::PPURevision;
+
+/// The console variants we can emulate. Each one runs its CPU and PPU at different speeds.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/Cycle_reference_chart
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Region {
+    /// North American and Japanese consoles
+    Ntsc,
+
+    /// European and Australian consoles
+    Pal,
+
+    /// Dendy and other famiclones sold in Russia and Eastern Europe. These use the PAL master clock
+    /// and frame length but keep the NTSC 3:1 PPU/CPU ratio and put vblank later in the frame.
+    Dendy,
+}
+
+impl Region {
+    /// The PPU found in consoles from this region.
+    pub fn ppu_revision(&self) -> PPURevision {
+        match self {
+            Region::Ntsc | Region::Dendy => PPURevision::RP2C02,
+            Region::Pal => PPURevision::RP2C07,
+        }
+    }
+
+    pub fn timing(&self) -> Timing {
+        match self {
+            Region::Ntsc => Timing {
+                master_clock_hz: 21_477_272,
+                cpu_clock_divider: 12,
+                ppu_clock_divider: 4,
+                scanlines_per_frame: 262,
+                vblank_scanline: 241,
+                skip_odd_frame_dot: true,
+            },
+            Region::Pal => Timing {
+                master_clock_hz: 26_601_712,
+                cpu_clock_divider: 16,
+                ppu_clock_divider: 5,
+                scanlines_per_frame: 312,
+                vblank_scanline: 241,
+                skip_odd_frame_dot: false,
+            },
+            Region::Dendy => Timing {
+                master_clock_hz: 26_601_712,
+                cpu_clock_divider: 15,
+                ppu_clock_divider: 5,
+                scanlines_per_frame: 312,
+                vblank_scanline: 291,
+                skip_odd_frame_dot: false,
+            },
+        }
+    }
+}
+
+/// How fast the CPU and PPU run and how the PPU lays out a frame.
+///
+/// The CPU and PPU clocks are both derived by dividing a master clock, so the number of PPU cycles
+/// per CPU cycle is `cpu_clock_divider / ppu_clock_divider`. This doesn't need to be a whole
+/// number: PAL consoles run 3.2 PPU cycles for every CPU cycle.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct Timing {
+    pub master_clock_hz: u64,
+    pub cpu_clock_divider: u32,
+    pub ppu_clock_divider: u32,
+
+    /// Including the pre-render line
+    pub scanlines_per_frame: u16,
+
+    /// The scanline where the vblank flag is set and NMI may be triggered.
+    pub vblank_scanline: u16,
+
+    /// Whether odd frames are one PPU cycle shorter when the background is shown. Only NTSC PPUs
+    /// do this.
+    pub skip_odd_frame_dot: bool,
+}
+
+impl Timing {
+    /// The wall-clock time taken by a single CPU cycle.
+    pub fn cpu_cycle_duration(&self) -> Duration {
+        let nanos = (self.cpu_clock_divider as u64 * 1_000_000_000) / self.master_clock_hz;
+        Duration::from_nanos(nanos)
+    }
+}
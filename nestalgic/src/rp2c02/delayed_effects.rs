@@ -0,0 +1,64 @@
+use super::PPUMask;
+use crate::save_state::{StateError, StateReader, StateWriter};
+
+/// A register write whose effect on the PPU is delayed, see `DelayedEffects`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub(crate) enum DelayedEffect {
+    /// A write to `PPUMASK`, already converted to `PPUMask` bit order.
+    PpuMask(PPUMask),
+}
+
+/// Register writes waiting to take effect.
+///
+/// The CPU and PPU run on separate clocks, and on hardware some writes only reach the rendering
+/// logic a few dots after the CPU makes them. Turning rendering on or off mid-scanline is the
+/// main case games notice, since it changes which dots fetch from the address bus.
+///
+/// Each effect counts down one dot per `tick` and is returned by the `tick` where it reaches zero.
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub(crate) struct DelayedEffects {
+    pending: Vec<(u8, DelayedEffect)>,
+}
+
+impl DelayedEffects {
+    /// Apply `effect` once `dots` more dots have been run.
+    pub fn schedule(&mut self, dots: u8, effect: DelayedEffect) {
+        self.pending.push((dots, effect));
+    }
+
+    /// Advance by one dot, returning the effects that are now due in the order they were written.
+    pub fn tick(&mut self) -> Vec<DelayedEffect> {
+        let mut due = Vec::new();
+        self.pending.retain_mut(|(dots, effect)| {
+            *dots = dots.saturating_sub(1);
+            if *dots == 0 {
+                due.push(*effect);
+            }
+            *dots > 0
+        });
+
+        due
+    }
+
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_u32(self.pending.len() as u32);
+        for &(dots, effect) in &self.pending {
+            let DelayedEffect::PpuMask(ppumask) = effect;
+            state.write_u8(dots);
+            state.write_u8(ppumask.into());
+        }
+    }
+
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        let len = state.read_u32()?;
+
+        self.pending.clear();
+        for _ in 0..len {
+            let dots = state.read_u8()?;
+            let effect = DelayedEffect::PpuMask(PPUMask::from(state.read_u8()?));
+            self.pending.push((dots, effect));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use super::{Pixel, RP2C02};
+
+/// How `FrameBlender` combines the frames it has seen into one.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum FrameBlend {
+    /// The darkest value of each channel. Keeps dark sprites on a light background.
+    Min,
+
+    /// The brightest value of each channel. Keeps light sprites on a dark background.
+    Max,
+
+    /// The mean of each channel, so anything flickering shows up semi-transparent.
+    Average,
+}
+
+/// Combines several frames into one so that nothing flickers out of existence when frames are
+/// skipped, e.g. while fast-forwarding.
+///
+/// Games that show more than 8 sprites on a scanline often flicker them on alternate frames. If a
+/// frontend only shows every Nth frame those sprites can disappear entirely. Blending every frame
+/// since the last one shown keeps them visible.
+pub struct FrameBlender {
+    pub mode: FrameBlend,
+
+    /// The number of frames accumulated since the last `take`
+    pub frames: u32,
+
+    /// The running min, max or sum of each channel, depending on `mode`
+    channels: Box<[[u32; 3]; RP2C02::SCREEN_PIXELS]>,
+}
+
+impl FrameBlender {
+    pub fn new(mode: FrameBlend) -> FrameBlender {
+        FrameBlender {
+            mode,
+            frames: 0,
+            channels: Box::new([[0; 3]; RP2C02::SCREEN_PIXELS]),
+        }
+    }
+
+    pub fn accumulate(&mut self, pixels: &[Pixel; RP2C02::SCREEN_PIXELS]) {
+        for (channels, pixel) in self.channels.iter_mut().zip(pixels.iter()) {
+            let rgb = [pixel.red as u32, pixel.green as u32, pixel.blue as u32];
+
+            for (channel, value) in channels.iter_mut().zip(rgb) {
+                *channel = match self.mode {
+                    _ if self.frames == 0 => value,
+                    FrameBlend::Min => (*channel).min(value),
+                    FrameBlend::Max => (*channel).max(value),
+                    FrameBlend::Average => *channel + value,
+                };
+            }
+        }
+
+        self.frames += 1;
+    }
+
+    /// The blend of every frame accumulated since the last `take`, or `None` if there weren't
+    /// any. The blender starts again from empty afterwards.
+    pub fn take(&mut self) -> Option<Box<[Pixel; RP2C02::SCREEN_PIXELS]>> {
+        if self.frames == 0 {
+            return None;
+        }
+
+        let divisor = match self.mode {
+            FrameBlend::Average => self.frames,
+            FrameBlend::Min | FrameBlend::Max => 1,
+        };
+
+        let mut pixels = Box::new([Pixel::empty(); RP2C02::SCREEN_PIXELS]);
+        for (pixel, channels) in pixels.iter_mut().zip(self.channels.iter()) {
+            let [red, green, blue] = channels.map(|channel| (channel / divisor) as u8);
+            *pixel = Pixel::new(red, green, blue, 255);
+        }
+
+        self.frames = 0;
+        Some(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(pixel: Pixel) -> Box<[Pixel; RP2C02::SCREEN_PIXELS]> {
+        Box::new([pixel; RP2C02::SCREEN_PIXELS])
+    }
+
+    #[test]
+    pub fn blends_each_channel_by_mode() {
+        let dark = frame(Pixel::new(0x10, 0x80, 0x20, 255));
+        let light = frame(Pixel::new(0x30, 0x40, 0xF0, 255));
+
+        let blend = |mode| {
+            let mut blender = FrameBlender::new(mode);
+            blender.accumulate(&dark);
+            blender.accumulate(&light);
+            blender.take().unwrap()[0]
+        };
+
+        assert_eq!(blend(FrameBlend::Min), Pixel::new(0x10, 0x40, 0x20, 255));
+        assert_eq!(blend(FrameBlend::Max), Pixel::new(0x30, 0x80, 0xF0, 255));
+        assert_eq!(blend(FrameBlend::Average), Pixel::new(0x20, 0x60, 0x88, 255));
+    }
+
+    #[test]
+    pub fn take_starts_a_new_blend() {
+        let mut blender = FrameBlender::new(FrameBlend::Min);
+        assert_eq!(blender.take(), None);
+
+        blender.accumulate(&frame(Pixel::new(0x10, 0x10, 0x10, 255)));
+        blender.take();
+        blender.accumulate(&frame(Pixel::new(0x20, 0x20, 0x20, 255)));
+
+        assert_eq!(blender.take().unwrap()[0], Pixel::new(0x20, 0x20, 0x20, 255));
+    }
+}
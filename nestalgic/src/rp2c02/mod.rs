@@ -1,22 +1,41 @@
 mod pixel;
+mod frame_blend;
 mod texture;
 mod ppuctrl;
 mod ppumask;
 mod ppustatus;
+mod revision;
+mod vram_address;
+mod delayed_effects;
+mod ppu_watchpoint;
+mod sprite_info;
+mod ppu_event;
 
 use nestalgic_mos6502::{Bus, MOS6502};
-pub use ppuctrl::PPUCtrl;
+pub use ppuctrl::{PPUCtrl, PPUCtrlFlag};
 pub use ppumask::PPUMask;
 pub use ppustatus::PPUStatus;
 pub use pixel::Pixel;
+pub use frame_blend::{FrameBlend, FrameBlender};
+pub use revision::PPURevision;
 pub use texture::Texture;
+pub use vram_address::VramAddress;
+pub use ppu_watchpoint::{PpuAccessKind, PpuWatchpoint, PpuWatchHit};
+pub use sprite_info::SpriteInfo;
+pub use ppu_event::{PpuEvent, PpuEventKind};
+use delayed_effects::{DelayedEffect, DelayedEffects};
+use crate::save_state::{StateError, StateReader, StateWriter};
 
-use self::ppuctrl::PPUCtrlFlag;
+use std::collections::VecDeque;
 
 
 /// `RP2C02` emulates the NES PPU (a.k.a the `RP2C02`)
 pub struct RP2C02 {
-    pub pixels: [Pixel; RP2C02::SCREEN_PIXELS],
+    // The framebuffers are boxed to keep `RP2C02` (and anything holding it) cheap to move.
+    pub pixels: Box<[Pixel; RP2C02::SCREEN_PIXELS]>,
+
+    /// The palette index (0x00-0x3F) of each pixel in `pixels`
+    pub palette_indices: Box<[u8; RP2C02::SCREEN_PIXELS]>,
 
     /// What cycle we are on in our rendering algorithm
     pub cycles: usize,
@@ -24,6 +43,23 @@ pub struct RP2C02 {
     /// The scanline we are currently drawing to
     pub scanline: u16,
 
+    /// The number of frames completed since power on
+    pub frame: u64,
+
+    /// Set by the `cycle` that finished a frame, see `frame_complete`
+    frame_complete: bool,
+
+    /// Including the pre-render line. 262 on NTSC consoles.
+    pub scanlines_per_frame: u16,
+
+    /// The scanline where vblank starts. 241 on NTSC consoles.
+    pub vblank_scanline: u16,
+
+    /// Whether to skip the last dot of the pre-render scanline on odd frames, see `cycle`.
+    pub skip_odd_frame_dot: bool,
+
+    pub revision: PPURevision,
+
     pub ppuctrl: PPUCtrl,
 
     pub ppumask: PPUMask,
@@ -31,23 +67,79 @@ pub struct RP2C02 {
     /// Don't read directly from emulator, use `read_ppustatus` instead.
     pub ppustatus: PPUStatus,
 
+    /// Set when the PPU wants to interrupt the CPU. The NMI is passed on during the next `cycle`.
+    nmi_requested: bool,
+
+    /// Set by a reset. Until the end of the next vblank the PPU ignores writes to `PPUCTRL`,
+    /// `PPUMASK`, `PPUSCROLL` and `PPUADDR`, which is why games wait for two vblanks before
+    /// setting up the PPU.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_power_up_state
+    pub warming_up: bool,
+
+    /// Delay register writes by the number of dots they take to reach the renderer on hardware,
+    /// see `write_ppumask`. This is more accurate but costs a little speed, so it's off by default.
+    pub delay_register_effects: bool,
+
+    delayed_effects: DelayedEffects,
+
+    /// Record the first access matching this watchpoint in `watch_hit`
+    pub watchpoint: Option<PpuWatchpoint>,
+
+    pub watch_hit: Option<PpuWatchHit>,
+
+    /// Record register accesses, NMIs and sprite 0 hits for `take_events`. Off by default.
+    pub log_events: bool,
+
+    /// The most recent `MAX_EVENTS` events, oldest first
+    events: VecDeque<PpuEvent>,
+
     pub oam_addr: u8,
     pub oam_data: [u8; 256],
 
-    pub addr: u16,
+    /// The palettes at `0x3F00`-`0x3F1F`. Palette RAM is inside the PPU rather than on the
+    /// cartridge.
+    pub palette_ram: [u8; 32],
 
-    /// Determines if we are writing to the high 8 bits of `addr` or the low 8 bits.
-    ///
-    /// If false: Write to the high 8 bits
-    /// If true: Write to the low 8 bits
+    /// The current VRAM address (`v`). This is the address used by `PPUDATA`, and while
+    /// rendering it tracks the tile being fetched.
+    pub vram_address: VramAddress,
+
+    /// The temporary VRAM address (`t`). Writes to `PPUCTRL`, `PPUSCROLL` and `PPUADDR` build up
+    /// the address here, and it's copied into `vram_address` at the start of each scanline and
+    /// frame. While rendering it holds the scroll position of the top left of the screen.
+    pub temp_vram_address: VramAddress,
+
+    /// The scroll within a tile, 0-7 pixels (`x`)
+    pub fine_x_scroll: u8,
+
+    /// Whether the next write to `PPUSCROLL` or `PPUADDR` is the second of the pair (`w`).
     ///
-    /// Toggled on each write to `addr` (shared by PPUADDR and PPUSCROLL)
-    /// Set to false when reading `ppustatus`
-    pub addr_latch: bool,
+    /// Toggled on each write to `PPUSCROLL` or `PPUADDR`, and cleared when reading `PPUSTATUS`.
+    pub write_latch: bool,
+
+    /// The value returned by the next `PPUDATA` read outside palette RAM, see `read_ppudata`.
+    pub read_buffer: u8,
 
-    pub horizontal_scroll: u8,
+    /// The PPU's I/O latch, see `cpu_mapped_read_u8`.
+    pub io_latch: u8,
 
-    pub vertical_scroll:u8,
+    /// The frame each bit of `io_latch` was last driven, so it can decay.
+    io_latch_refreshed: [u64; 8],
+
+    /// The most recently fetched background tile, waiting to be loaded into `background_shifters`
+    next_background_tile: BackgroundTile,
+
+    background_shifters: BackgroundShifters,
+
+    /// The sprites found by sprite evaluation on this scanline, to be drawn on the next one
+    next_sprites: Vec<[u8; 4]>,
+
+    /// Whether the first of `next_sprites` is sprite 0
+    next_sprites_include_sprite_0: bool,
+
+    /// The sprites being drawn on this scanline, in OAM order
+    sprite_rows: Vec<SpriteRow>,
 
     // TODO: https://wiki.nesdev.com/w/index.php/PPU_memory_map
     //
@@ -68,6 +160,106 @@ pub struct RP2C02 {
     // palette: [u8; 256],
 }
 
+/// One row of a background tile, fetched while the PPU draws the tile before it.
+#[derive(Default, Clone, Copy)]
+struct BackgroundTile {
+    pattern_low: u8,
+    pattern_high: u8,
+    palette: u8,
+}
+
+/// The background pipeline: Two tiles worth of pattern and palette bits, which shift left once per
+/// pixel. A new tile is loaded into the low 8 bits every 8 pixels and `fine_x_scroll` picks which
+/// of the top 8 bits is drawn, so the screen can be scrolled a pixel at a time.
+///
+/// ```text
+///  15      8 7       0
+/// +---------+---------+
+/// | Drawing | Next    |  <- shifts left
+/// +---------+---------+
+///   ^^^^^^^^
+///   bit 15 - fine_x_scroll is drawn
+/// ```
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_rendering#Preface
+#[derive(Default, Clone, Copy)]
+struct BackgroundShifters {
+    pattern_low: u16,
+    pattern_high: u16,
+    palette_low: u16,
+    palette_high: u16,
+}
+
+impl BackgroundShifters {
+    fn shift(&mut self) {
+        self.pattern_low <<= 1;
+        self.pattern_high <<= 1;
+        self.palette_low <<= 1;
+        self.palette_high <<= 1;
+    }
+
+    fn load(&mut self, tile: BackgroundTile) {
+        // Every pixel in a tile shares a palette, so we spread its bits across the whole byte
+        let spread = |bit: u8| if bit != 0 { 0xFF } else { 0x00 };
+
+        self.pattern_low = (self.pattern_low & 0xFF00) | tile.pattern_low as u16;
+        self.pattern_high = (self.pattern_high & 0xFF00) | tile.pattern_high as u16;
+        self.palette_low = (self.palette_low & 0xFF00) | spread(tile.palette & 0b01);
+        self.palette_high = (self.palette_high & 0xFF00) | spread(tile.palette & 0b10);
+    }
+
+    /// The colour (0-3) and palette (0-3) of the pixel being drawn
+    fn pixel(&self, fine_x_scroll: u8) -> (u8, u8) {
+        let bit = 15 - fine_x_scroll;
+        let select = |shifter: u16| ((shifter >> bit) & 1) as u8;
+
+        let color = select(self.pattern_low) | (select(self.pattern_high) << 1);
+        let palette = select(self.palette_low) | (select(self.palette_high) << 1);
+        (color, palette)
+    }
+}
+
+/// One row of a sprite, fetched on the scanline before the sprite is drawn.
+#[derive(Default, Clone, Copy)]
+struct SpriteRow {
+    x: u8,
+    pattern_low: u8,
+    pattern_high: u8,
+    attributes: u8,
+    sprite_0: bool,
+}
+
+impl SpriteRow {
+    /// The sprite's colour (0-3) at screen column `x`, or 0 if the sprite doesn't cover `x`.
+    fn color(&self, x: usize) -> u8 {
+        let column = x.wrapping_sub(self.x as usize);
+        if column >= 8 {
+            return 0;
+        }
+
+        let bit = 7 - column;
+        ((self.pattern_low >> bit) & 1) | (((self.pattern_high >> bit) & 1) << 1)
+    }
+
+    fn palette(&self) -> u8 {
+        self.attributes & 0b11
+    }
+
+    fn behind_background(&self) -> bool {
+        self.attributes & 0b0010_0000 != 0
+    }
+}
+
+/// The registers returned by `RP2C02::debug_registers_mut`.
+pub struct DebugRegisters<'a> {
+    pub ppuctrl: &'a mut PPUCtrl,
+    pub ppumask: &'a mut PPUMask,
+    pub ppustatus: &'a mut PPUStatus,
+    pub vram_address: &'a mut VramAddress,
+    pub temp_vram_address: &'a mut VramAddress,
+    pub fine_x_scroll: &'a mut u8,
+}
+
 impl RP2C02 {
     pub const SCREEN_WIDTH: usize = 256;
     pub const SCREEN_HEIGHT: usize = 240;
@@ -75,104 +267,204 @@ impl RP2C02 {
 
     pub fn new() -> RP2C02 {
         RP2C02 {
-            pixels: [Pixel::empty(); RP2C02::SCREEN_PIXELS],
+            pixels: Box::new([Pixel::empty(); RP2C02::SCREEN_PIXELS]),
+            palette_indices: Box::new([0; RP2C02::SCREEN_PIXELS]),
             cycles: 0,
             scanline: 0,
+            frame: 0,
+            frame_complete: false,
+            scanlines_per_frame: 262,
+            vblank_scanline: 241,
+            skip_odd_frame_dot: true,
+            revision: PPURevision::default(),
             ppuctrl: PPUCtrl::default(),
             ppumask: PPUMask::default(),
             ppustatus: PPUStatus::default(),
-            addr: 0,
-            addr_latch: false,
+            nmi_requested: false,
+            warming_up: false,
+            delay_register_effects: false,
+            delayed_effects: DelayedEffects::default(),
+            watchpoint: None,
+            watch_hit: None,
+            log_events: false,
+            events: VecDeque::new(),
+            vram_address: VramAddress::default(),
+            temp_vram_address: VramAddress::default(),
+            fine_x_scroll: 0,
+            write_latch: false,
+            read_buffer: 0,
+            io_latch: 0,
+            io_latch_refreshed: [0; 8],
             oam_addr: 0,
             oam_data: [0; 256],
-            horizontal_scroll: 0,
-            vertical_scroll: 0,
+            palette_ram: [0; 32],
+            next_background_tile: BackgroundTile::default(),
+            background_shifters: BackgroundShifters::default(),
+            next_sprites: Vec::with_capacity(8),
+            next_sprites_include_sprite_0: false,
+            sprite_rows: Vec::with_capacity(8),
+        }
+    }
+
+    /// Whether the last `cycle` finished a frame. `pixels` then holds the whole frame until the
+    /// first visible dot of the next one is drawn.
+    pub fn frame_complete(&self) -> bool {
+        self.frame_complete
+    }
+
+    /// Mutable access to the PPU registers for debuggers.
+    ///
+    /// Unlike `cpu_mapped_write_u8` this doesn't trigger any of the side effects of writing a
+    /// register (such as toggling `write_latch`), so it can be used to experiment with the PPU
+    /// state without disturbing it.
+    pub fn debug_registers_mut(&mut self) -> DebugRegisters<'_> {
+        DebugRegisters {
+            ppuctrl: &mut self.ppuctrl,
+            ppumask: &mut self.ppumask,
+            ppustatus: &mut self.ppustatus,
+            vram_address: &mut self.vram_address,
+            temp_vram_address: &mut self.temp_vram_address,
+            fine_x_scroll: &mut self.fine_x_scroll,
         }
     }
 
     pub fn cycle(&mut self, cpu: &mut MOS6502, bus: &mut impl Bus) {
+        for effect in self.delayed_effects.tick() {
+            match effect {
+                DelayedEffect::PpuMask(ppumask) => self.ppumask = ppumask,
+            }
+        }
 
         // Cycle 0: Idle Cycle
         // Cycles 1-256: Tile data fetch
         // Cycles 257-320:
 
+        // With the background showing, NTSC PPUs skip the last dot of the pre-render scanline on
+        // odd frames. This keeps the colour subcarrier phase shifting between frames, which makes
+        // dot crawl less visible.
+        //
+        // See also: https://wiki.nesdev.com/w/index.php/PPU_frame_timing#Even.2FOdd_Frames
+        let skip_dot = self.skip_odd_frame_dot
+            && !self.frame.is_multiple_of(2)
+            && self.ppumask.show_background
+            && self.scanline == self.scanlines_per_frame - 1
+            && self.cycles == 339;
+
+        self.frame_complete = false;
         self.cycles += 1;
-        if self.cycles >= 341 {
-            self.cycles = self.cycles - 341;
+        if self.cycles >= 341 || skip_dot {
+            self.cycles = 0;
             self.scanline += 1;
 
-            if self.scanline == 241 {
-                self.ppustatus.in_vblank = true;
-                if self.ppuctrl.get(PPUCtrlFlag::GenerateNmiOnVblank) {
-                    cpu.nmi = true;
-                }
-            } else if self.scanline >= 262 {
+            if self.scanline >= self.scanlines_per_frame {
                 self.scanline = 0;
-                self.ppustatus.in_vblank = false;
+                self.frame += 1;
+                self.frame_complete = true;
+            }
+        }
+
+        if self.cycles == 1 && self.scanline == self.vblank_scanline {
+            self.ppustatus.in_vblank = true;
+            if self.ppuctrl.get(PPUCtrlFlag::GenerateNmiOnVblank) {
+                self.nmi_requested = true;
             }
         }
 
-        if self.cycles >= 257 && self.cycles <= 320 {
+        if self.cycles == 1 && self.scanline == self.scanlines_per_frame - 1 {
+            self.warming_up = false;
+            self.ppustatus.in_vblank = false;
+            self.ppustatus.sprite_0_hit = false;
+            self.ppustatus.sprite_overflow = false;
+        }
+
+        if self.nmi_requested {
+            cpu.nmi = true;
+            self.nmi_requested = false;
+            self.log_event(PpuEventKind::Nmi);
+        }
+
+        let visible_scanline = (self.scanline as usize) < RP2C02::SCREEN_HEIGHT;
+        if visible_scanline && self.rendering() && self.cycles == 256 {
+            self.evaluate_sprites();
+        }
+
+        // OAMADDR is cleared during each sprite tile fetch
+        if self.rendering() && self.cycles >= 257 && self.cycles <= 320 {
             self.oam_addr = 0;
         }
 
-        // Render first tile in pattern table 0 (0x0000-0x0FFF)
-        //
-        // Each tile is 8x8
-        //
-        // TODO: Render the last line of the pattern table without crashing
-        // let chr_data = (0..7 * 1024)
-        //     .map(|a| bus.read_u8(a as u16))
-        //     .collect::<Vec<u8>>();
-
-        // for (i, chr) in chr_data.chunks(16).enumerate() {
-        //     for y in 0..8 {
-        //         let line_byte_1 = chr[y];
-        //         let line_byte_2 = chr[8 + y];
-
-        //         for x in 0..8 {
-        //             let pixel_bit_1 = (line_byte_1 >> 7 - x) & 1;
-        //             let pixel_bit_2 = (line_byte_2 >> 7 - x) & 1;
-        //             let pixel_value = pixel_bit_1 + (pixel_bit_2 << 1);
-
-        //             let offset_x = (i * 8) % RP2C02::SCREEN_WIDTH;
-        //             let offset_y = (i / 16) * 8;
-        //             let pixel_x = offset_x + x;
-        //             let pixel_y = offset_y + y;
-
-        //             self.pixels[(pixel_y * RP2C02::SCREEN_WIDTH) + pixel_x] = match pixel_value {
-        //                 0 => Pixel::empty(),
-        //                 1 => Pixel::new(255, 0, 0, 255),
-        //                 2 => Pixel::new(0, 255, 0, 255),
-        //                 3 => Pixel::new(0, 0, 255, 255),
-        //                 _ => Pixel::new(255, 0, 255, 255)
-        //             };
-        //         }
-        //     }
-        // }
+        if self.rendering() && self.cycles == 257 {
+            // Nothing is evaluated on the pre-render scanline so no sprites are drawn on scanline 0
+            if !visible_scanline {
+                self.next_sprites.clear();
+            }
+            self.fetch_sprite_rows(bus);
+        }
+
+        if self.rendering() {
+            self.update_background_shifters();
+        }
+
+        if visible_scanline && (1..=RP2C02::SCREEN_WIDTH).contains(&self.cycles) {
+            self.render_pixel(self.cycles - 1, self.scanline as usize);
+        }
+
+        if self.rendering() {
+            self.update_vram_address(bus);
+        }
     }
 
 
     /// This function is only defined for addresses `0x2000-0x3FFF`, attempting to
     /// read outside this range will result in a panic.
+    ///
+    /// The PPU's data bus to the CPU holds on to the last value driven onto it (the I/O latch, or
+    /// "open bus"). Every write refreshes the whole latch, but reads only drive some bits:
+    ///
+    /// | Register   | Bits driven | Other bits       |
+    /// |------------|-------------|------------------|
+    /// | `PPUSTATUS`| 7-5         | Latch            |
+    /// | `OAMDATA`  | 7-0         |                  |
+    /// | `PPUDATA`  | 7-0         |                  |
+    /// | Palette    | 5-0         | Latch            |
+    /// | Write-only | None        | Latch            |
+    ///
+    /// Bits that aren't refreshed decay to 0 after about 600ms (`IO_LATCH_DECAY_FRAMES`).
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/Open_bus_behavior#PPU_open_bus
     pub fn cpu_mapped_read_u8(&mut self, ppu_bus: &mut impl Bus, address: u16) -> u8 {
-        let data = match address {
-            0x2000 => panic!("0x2000 is not readable"),
-            0x2001 => panic!("0x2001 is not readable"),
-            0x2002 => self.read_ppustatus().into(), // PPU Status
-            0x2003 => panic!("0x2003 is not readable"),
-            0x2004 => self.oam_data[self.oam_addr as usize],
-            0x2005 => panic!("0x2005 is not readable"),
-            0x2006 => panic!("0x2006 is not readable"),
-            0x2007 => self.read_ppudata(ppu_bus),
+        // Registers are mirrored every 8 bytes up to 0x3FFF. We fold the address first so a mirrored
+        // read drives the latch exactly like a read of the register itself.
+        let register = match address {
+            0x2000..=0x3FFF => address & 0x2007,
+            _ => panic!("cpu_mapped_read_u8 expects address in range 0x2000-0x3FFF, was {}", address)
+        };
 
-            // Memory is mirrored everey 8 bytes up to 0x3FFF
-            0x2008..=0x3FFF => self.cpu_mapped_read_u8(ppu_bus, address & 0x2007),
+        self.decay_io_latch();
 
-            _ => panic!("cpu_mapped_read_u8 expects address in range 0x2000-0x3FFF, was {}", address)
+        let (data, driven) = match register {
+            0x2002 => { // PPU Status
+                let status: u8 = self.read_ppustatus().into();
+                match self.revision.status_id() {
+                    Some(id) => ((status & 0b1110_0000) | id, 0b1111_1111),
+                    None => (status, 0b1110_0000),
+                }
+            },
+            0x2004 => (self.read_oamdata(), 0b1111_1111),
+            0x2007 => {
+                let palette = self.vram_address.address() >= 0x3F00;
+                let data = self.read_ppudata(ppu_bus);
+                (data, if palette { 0b0011_1111 } else { 0b1111_1111 })
+            },
+
+            // Write-only registers: 0x2000, 0x2001, 0x2003, 0x2005 and 0x2006
+            _ => (0, 0),
         };
 
-        println!("ppu_read {:X} -> {:08b}", address, data);
+        self.refresh_io_latch(data, driven);
+        let data = self.io_latch;
+
+        self.log_event(PpuEventKind::RegisterRead { address: register, data });
 
         data
     }
@@ -180,11 +472,16 @@ impl RP2C02 {
     /// This function is only defined for addresses `0x2000-0x3FFF`, attempting to
     /// write outside this range will result in a panic.
     pub fn cpu_mapped_write_u8(&mut self, ppu_bus: &mut impl Bus, address: u16, data: u8) {
-        println!("ppu_write {:X} = {:08b}", address, data);
-        match address {
-            0x2000 => self.ppuctrl.0 = data,
-            0x2001 => self.ppumask = PPUMask::from(data),
-            0x2002 => panic!("0x2002 is not writable"),
+        self.refresh_io_latch(data, 0b1111_1111);
+        if address <= 0x2007 {
+            self.log_event(PpuEventKind::RegisterWrite { address, data });
+        }
+
+        match self.revision.register_address(address) {
+            0x2000 | 0x2001 | 0x2005 | 0x2006 if self.warming_up => {},
+            0x2000 => self.write_ppuctrl(data),
+            0x2001 => self.write_ppumask(data),
+            0x2002 => {}, // Read-only
             0x2003 => self.oam_addr = data,
             0x2004 => self.write_oamdata(data),
             0x2005 => self.write_ppuscroll(data),
@@ -198,30 +495,105 @@ impl RP2C02 {
         }
     }
 
+    /// Set the `driven` bits of `io_latch` to `data`.
+    fn refresh_io_latch(&mut self, data: u8, driven: u8) {
+        self.io_latch = (self.io_latch & !driven) | (data & driven);
+        for (bit, refreshed) in self.io_latch_refreshed.iter_mut().enumerate() {
+            if driven & (1 << bit) != 0 {
+                *refreshed = self.frame;
+            }
+        }
+    }
+
+    /// Clear the bits of `io_latch` that haven't been driven for `IO_LATCH_DECAY_FRAMES`.
+    fn decay_io_latch(&mut self) {
+        for (bit, refreshed) in self.io_latch_refreshed.iter().enumerate() {
+            if self.frame.saturating_sub(*refreshed) >= RP2C02::IO_LATCH_DECAY_FRAMES {
+                self.io_latch &= !(1 << bit);
+            }
+        }
+    }
+
+    /// About 600ms on NTSC. The real decay time varies between consoles.
+    const IO_LATCH_DECAY_FRAMES: u64 = 36;
+
+    /// Write `PPUCTRL`. The nametable select bits also go into `temp_vram_address`:
+    ///
+    /// ```text
+    /// t: ...GH.. ........ <- data: ......GH
+    /// ```
+    ///
+    /// Turning on `GenerateNmiOnVblank` while `in_vblank` is set causes an NMI straight away, so a
+    /// game that enables NMIs partway through vblank still gets one.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/NMI
+    pub fn write_ppuctrl(&mut self, data: u8) {
+        let nmi_was_enabled = self.ppuctrl.get(PPUCtrlFlag::GenerateNmiOnVblank);
+        self.ppuctrl.0 = data;
+
+        if !nmi_was_enabled && self.ppuctrl.get(PPUCtrlFlag::GenerateNmiOnVblank) && self.ppustatus.in_vblank {
+            self.nmi_requested = true;
+        }
+
+        self.temp_vram_address.copy_bits(VramAddress((data as u16 & 0b11) << 10), VramAddress::NAMETABLE);
+    }
+
+    /// Write `PPUMASK`. With `delay_register_effects` on the new value only reaches the renderer
+    /// `PPUMASK_DELAY_DOTS` dots after the write, so turning rendering on or off partway through
+    /// a scanline changes the same pixels and fetches it does on hardware.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_registers#PPUMASK
+    pub fn write_ppumask(&mut self, data: u8) {
+        let ppumask = PPUMask::from(self.revision.ppumask_bits(data));
+        if self.delay_register_effects {
+            self.delayed_effects.schedule(RP2C02::PPUMASK_DELAY_DOTS, DelayedEffect::PpuMask(ppumask));
+        } else {
+            self.ppumask = ppumask;
+        }
+    }
+
+    const PPUMASK_DELAY_DOTS: u8 = 2;
+
+    /// Write one half of `PPUADDR`. The high byte is written first, and the whole address is
+    /// copied into `vram_address` once the low byte is written:
+    ///
+    /// ```text
+    /// First write:  t: .CDEFGH ........ <- data: ..CDEFGH (bit 14 is cleared)
+    /// Second write: t: ....... ABCDEFGH <- data: ABCDEFGH, then v = t
+    /// ```
     pub fn write_ppuaddr(&mut self, data: u8) {
-        let [addr_lo, addr_hi] = self.addr.to_le_bytes();
-        let [addr_lo, addr_hi] = if self.addr_latch {
-            [addr_lo, data]
+        if !self.write_latch {
+            self.temp_vram_address.copy_bits(VramAddress((data as u16 & 0b0011_1111) << 8), 0x7F00);
         } else {
-            [data, addr_hi]
-        };
+            self.temp_vram_address.copy_bits(VramAddress(data as u16), 0x00FF);
+            self.vram_address = self.temp_vram_address;
+        }
 
-        self.addr = u16::from_le_bytes([addr_lo, addr_hi]);
-        self.addr_latch = !self.addr_latch;
+        self.write_latch = !self.write_latch;
     }
 
+    /// Write one half of `PPUSCROLL`. The X scroll is written first, then the Y scroll:
+    ///
+    /// ```text
+    /// First write:  t: ....... ...ABCDE <- data: ABCDE..., x <- data: .....FGH
+    /// Second write: t: FGH..AB CDE..... <- data: ABCDEFGH
+    /// ```
     pub fn write_ppuscroll(&mut self, data: u8) {
-        if !self.addr_latch {
-            self.horizontal_scroll = data;
+        let data = data as u16;
+        if !self.write_latch {
+            self.temp_vram_address.copy_bits(VramAddress(data >> 3), VramAddress::COARSE_X);
+            self.fine_x_scroll = data as u8 & 0b111;
         } else {
-            self.vertical_scroll = data;
+            let coarse_y = (data >> 3) << 5;
+            let fine_y = (data & 0b111) << 12;
+            self.temp_vram_address.copy_bits(VramAddress(coarse_y | fine_y), VramAddress::COARSE_Y | VramAddress::FINE_Y);
         }
 
-        self.addr_latch = !self.addr_latch;
+        self.write_latch = !self.write_latch;
     }
 
     pub fn read_ppustatus(&mut self) -> PPUStatus {
-        self.addr_latch = false;
+        self.write_latch = false;
 
         let old_ppustatus = self.ppustatus;
 
@@ -231,20 +603,1194 @@ impl RP2C02 {
         old_ppustatus
     }
 
+    /// Reads outside palette RAM are delayed by one: the PPU returns the contents of its internal
+    /// read buffer and then refills the buffer from `vram_address`. This is why games do a dummy
+    /// read after setting `PPUADDR`.
+    ///
+    /// Palette RAM is inside the PPU so it's returned straight away. The buffer is still refilled,
+    /// but with the nametable byte "underneath" the palette (`address - 0x1000`). Like the picture,
+    /// palette reads are forced into the grey column while greyscale is on.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_registers#The_PPUDATA_read_buffer_.28post-fetch.29
     pub fn read_ppudata(&mut self, bus: &mut impl Bus) -> u8 {
-        // TODO: Mirror values above 0x3FFF
-        let value = bus.read_u8(self.addr & 0x3FFF);
-        self.addr += self.ppuctrl.vram_address_increment() as u16;
+        let address = self.vram_address.address();
+        let value = match address {
+            0x3F00..=0x3FFF => {
+                self.read_buffer = bus.read_u8(address - 0x1000);
+                self.greyscale(self.palette_ram[RP2C02::palette_ram_index(address)])
+            },
+            _ => {
+                let buffered = self.read_buffer;
+                self.read_buffer = bus.read_u8(address);
+                buffered
+            },
+        };
+        self.increment_vram_address();
         value
     }
 
     pub fn write_ppudata(&mut self, bus: &mut impl Bus, data: u8) {
-        bus.write_u8(self.addr & 0x3FFF, data);
-        self.addr += self.ppuctrl.vram_address_increment() as u16;
+        let address = self.vram_address.address();
+        self.watch(address, PpuAccessKind::Write);
+        match address {
+            0x3F00..=0x3FFF => self.palette_ram[RP2C02::palette_ram_index(address)] = data,
+            _ => bus.write_u8(address, data),
+        }
+        self.increment_vram_address();
+    }
+
+    /// Move `vram_address` on after a `PPUDATA` access. While rendering the PPU is using
+    /// `vram_address` to fetch tiles, so instead of the usual increment it moves to the next tile
+    /// and the next row at the same time.
+    fn increment_vram_address(&mut self) {
+        if self.rendering() {
+            self.vram_address.increment_x();
+            self.vram_address.increment_y();
+        } else {
+            let increment = self.ppuctrl.vram_address_increment() as u16;
+            self.vram_address.0 = self.vram_address.0.wrapping_add(increment) & 0x7FFF;
+        }
+    }
+
+    /// Palette RAM is mirrored every 32 bytes up to `0x3FFF`. The first colour of each sprite
+    /// palette (`0x3F10`, `0x3F14`, `0x3F18` and `0x3F1C`) is also a mirror of the matching
+    /// background palette entry.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_palettes#Memory_Map
+    fn palette_ram_index(address: u16) -> usize {
+        let index = (address & 0x1F) as usize;
+        if index & 0b1_0011 == 0b1_0000 {
+            index & 0x0F
+        } else {
+            index
+        }
+    }
+
+    /// The colours of one of the 8 palettes in palette RAM. Palettes 0-3 are used by the
+    /// background and 4-7 by sprites. Colour 0 of every palette is the backdrop colour at `0x3F00`.
+    pub fn palette_colors(&self, palette: u8) -> [Pixel; 4] {
+        [0, 1, 2, 3].map(|color| {
+            let address = if color == 0 { 0 } else { (palette & 0b111) * 4 + color };
+            self.revision.color(self.palette_ram[address as usize] & 0x3F)
+        })
+    }
+
+    /// All 64 sprites in OAM, in OAM order. Sprites are decoded using the current sprite size and
+    /// pattern table in `PPUCTRL`.
+    pub fn sprites(&self) -> Vec<SpriteInfo> {
+        let tall_sprites = self.ppuctrl.get(PPUCtrlFlag::SpriteSize);
+        let pattern_table_address = self.ppuctrl.sprite_pattern_table_address();
+
+        self.oam_data
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(index, oam)| {
+                let oam = [oam[0], oam[1], oam[2], oam[3]];
+                SpriteInfo::decode(index as u8, oam, tall_sprites, pattern_table_address)
+            })
+            .collect()
+    }
+
+    /// Draw the pixel at (`x`, `y`) on screen.
+    ///
+    /// The background pixel comes from `background_shifters`, see `BackgroundShifters`.
+    ///
+    /// Colour 0 of every background and sprite palette is transparent. Where both the background
+    /// and a sprite are opaque the sprite's priority bit decides which is drawn:
+    ///
+    /// | Background | Sprite | Priority | Output     |
+    /// |------------|--------|----------|------------|
+    /// | 0          | 0      | Any      | Backdrop   |
+    /// | 0          | 1-3    | Any      | Sprite     |
+    /// | 1-3        | 0      | Any      | Background |
+    /// | 1-3        | 1-3    | Front    | Sprite     |
+    /// | 1-3        | 1-3    | Behind   | Background |
+    ///
+    /// Only the first opaque sprite in OAM order takes part, even if it is behind the background.
+    /// Games use this to hide other sprites behind scenery, e.g. Mario going down a pipe.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_sprite_priority
+    fn render_pixel(&mut self, x: usize, y: usize) {
+        let (color, background_palette) = self.background_shifters.pixel(self.fine_x_scroll);
+
+        // Hidden pixels are transparent
+        let background_visible = self.ppumask.show_background
+            && (x >= 8 || self.ppumask.show_background_on_left_8_pixels);
+        let background_color = if background_visible { color } else { 0 };
+
+        let sprites_visible = self.ppumask.show_sprites
+            && (x >= 8 || self.ppumask.show_sprites_on_left_8_pixels);
+
+        // Sprite 0 hits are checked against every opaque pixel of sprite 0, even one hidden by
+        // another sprite, but never on the last column of the screen
+        let sprite_0_hit = sprites_visible
+            && background_color != 0
+            && x != 255
+            && self.sprite_rows.first().is_some_and(|sprite| sprite.sprite_0 && sprite.color(x) != 0);
+        if sprite_0_hit && !self.ppustatus.sprite_0_hit {
+            self.ppustatus.sprite_0_hit = true;
+            self.log_event(PpuEventKind::Sprite0Hit);
+        }
+
+        let sprite = self.sprite_rows
+            .iter()
+            .map(|sprite| (sprite, sprite.color(x)))
+            .find(|&(_, color)| sprites_visible && color != 0);
+
+        // Transparent pixels show the backdrop colour at 0x3F00
+        let palette_address = match sprite {
+            Some((sprite, color)) if background_color == 0 || !sprite.behind_background() => {
+                0x10 + sprite.palette() * 4 + color
+            },
+            _ if background_color != 0 => background_palette * 4 + background_color,
+            _ => 0,
+        };
+
+        self.watch(0x3F00 | palette_address as u16, PpuAccessKind::Fetch);
+        let palette_index = self.greyscale(self.palette_ram[palette_address as usize] & 0x3F);
+
+        let pixel = y * RP2C02::SCREEN_WIDTH + x;
+        self.palette_indices[pixel] = palette_index;
+        self.pixels[pixel] = self.revision.emphasised_color(palette_index, &self.ppumask);
+    }
+
+    /// Record `kind` in the event log if `log_events` is on, dropping the oldest event once the log
+    /// is full.
+    fn log_event(&mut self, kind: PpuEventKind) {
+        if !self.log_events {
+            return;
+        }
+
+        if self.events.len() >= RP2C02::MAX_EVENTS {
+            self.events.pop_front();
+        }
+
+        self.events.push_back(PpuEvent {
+            kind,
+            frame: self.frame,
+            scanline: self.scanline,
+            dot: self.cycles,
+        });
+    }
+
+    /// About 10 frames of a game that polls `PPUSTATUS` in a tight loop
+    const MAX_EVENTS: usize = 4096;
+
+    /// Remove and return every event logged since the last call, oldest first. See `PpuEvent`.
+    pub fn take_events(&mut self) -> Vec<PpuEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// Greyscale mode clears the hue of each colour, leaving only the grey column (`0x00`, `0x10`,
+    /// `0x20` and `0x30`).
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_registers#Color_control
+    fn greyscale(&self, palette_index: u8) -> u8 {
+        if self.ppumask.greyscale {
+            palette_index & 0x30
+        } else {
+            palette_index
+        }
+    }
+
+    /// Shift the background pipeline along by a pixel, loading the latest tile every 8 pixels. The
+    /// pipeline runs for dots 2-257 (the visible pixels) and 322-337 (the first two tiles of the
+    /// next scanline).
+    fn update_background_shifters(&mut self) {
+        let dot = self.cycles;
+        if !((2..=257).contains(&dot) || (322..=337).contains(&dot)) {
+            return;
+        }
+
+        self.background_shifters.shift();
+        if dot % 8 == 1 {
+            self.background_shifters.load(self.next_background_tile);
+        }
+    }
+
+    /// Fetch background tiles and move `vram_address` through the nametables while rendering:
+    ///
+    /// - Every 8 dots from dot 8 to 256: Fetch the next tile for `background_shifters` and move to
+    ///   the next tile column
+    /// - Dot 256: Move to the next row of pixels
+    /// - Dot 257: Go back to the first column, copying the horizontal scroll from `t`
+    /// - Dots 280-304 of the pre-render scanline: Go back to the first row, copying the vertical
+    ///   scroll from `t`
+    /// - Dots 328 and 336: Fetch the first two tiles of the next scanline
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_scrolling#During_dots_280_to_304_of_the_pre-render_scanline_.28end_of_vblank.29
+    fn update_vram_address(&mut self, bus: &mut impl Bus) {
+        let dot = self.cycles;
+        let pre_render_scanline = self.scanline == self.scanlines_per_frame - 1;
+
+        if (dot.is_multiple_of(8) && (8..=256).contains(&dot)) || dot == 328 || dot == 336 {
+            self.next_background_tile = self.fetch_background_tile(bus);
+            self.vram_address.increment_x();
+        }
+
+        if dot == 256 {
+            self.vram_address.increment_y();
+        }
+
+        if dot == 257 {
+            self.vram_address.copy_bits(self.temp_vram_address, VramAddress::HORIZONTAL);
+        }
+
+        if pre_render_scanline && (280..=304).contains(&dot) {
+            self.vram_address.copy_bits(self.temp_vram_address, VramAddress::VERTICAL);
+        }
+    }
+
+    /// Fetch the background tile at `vram_address`. Each 8x8 tile gets its palette from the
+    /// attribute table at the end of its nametable, where each byte covers a 32x32 area and picks
+    /// one palette for each 16x16 quarter:
+    ///
+    /// ```text
+    /// 7654 3210
+    /// |||| ||++- Top left
+    /// |||| ++--- Top right
+    /// ||++------ Bottom left
+    /// ++-------- Bottom right
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_attribute_tables
+    fn fetch_background_tile(&mut self, bus: &mut impl Bus) -> BackgroundTile {
+        let address = self.vram_address;
+        let tile_index = self.fetch(bus, address.tile_address());
+        let attribute = self.fetch(bus, address.attribute_address());
+        let attribute_shift = (address.coarse_y() & 0b10) * 2 + (address.coarse_x() & 0b10);
+
+        let pattern_address = self.ppuctrl.background_pattern_table_address()
+            + tile_index as u16 * 16
+            + address.fine_y();
+
+        BackgroundTile {
+            pattern_low: self.fetch(bus, pattern_address),
+            pattern_high: self.fetch(bus, pattern_address + 8),
+            palette: (attribute >> attribute_shift) & 0b11,
+        }
+    }
+
+    /// Fetch the pattern data for the sprites found by `evaluate_sprites`, ready to be drawn on the
+    /// next scanline.
+    ///
+    /// Each sprite is four bytes of OAM:
+    ///
+    /// ```text
+    /// Byte 0: Y position of the top of the sprite, minus 1
+    /// Byte 1: Tile index. In 8x16 mode bit 0 picks the pattern table instead
+    /// Byte 2: Attributes
+    ///
+    ///         7654 3210
+    ///         |||| ||++- Palette (4-7)
+    ///         |||+-++--- Unused
+    ///         ||+------- Priority (0: in front of background, 1: behind background)
+    ///         |+-------- Flip horizontally
+    ///         +--------- Flip vertically
+    ///
+    /// Byte 3: X position of the left of the sprite
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_OAM
+    fn fetch_sprite_rows(&mut self, bus: &mut impl Bus) {
+        let tall_sprites = self.ppuctrl.get(PPUCtrlFlag::SpriteSize);
+        let height = if tall_sprites { 16 } else { 8 };
+
+        self.sprite_rows.clear();
+        let next_sprites = std::mem::take(&mut self.next_sprites);
+        for (i, &[y, tile, attributes, x]) in next_sprites.iter().enumerate() {
+            let row = self.scanline.wrapping_sub(y as u16);
+            let row = if attributes & 0b1000_0000 != 0 { height - 1 - row } else { row };
+
+            let pattern_address = if tall_sprites {
+                let pattern_table = (tile & 1) as u16 * 0x1000;
+                let tile = (tile & 0b1111_1110) as u16 + row / 8;
+                pattern_table + tile * 16 + row % 8
+            } else {
+                self.ppuctrl.sprite_pattern_table_address() + tile as u16 * 16 + row
+            };
+
+            let mut pattern_low = self.fetch(bus, pattern_address);
+            let mut pattern_high = self.fetch(bus, pattern_address + 8);
+            if attributes & 0b0100_0000 != 0 {
+                pattern_low = pattern_low.reverse_bits();
+                pattern_high = pattern_high.reverse_bits();
+            }
+
+            let sprite_0 = i == 0 && self.next_sprites_include_sprite_0;
+            self.sprite_rows.push(SpriteRow { x, pattern_low, pattern_high, attributes, sprite_0 });
+        }
+        self.next_sprites = next_sprites;
+    }
+
+    /// Read `address` for the rendering pipeline.
+    fn fetch(&mut self, bus: &mut impl Bus, address: u16) -> u8 {
+        self.watch(address, PpuAccessKind::Fetch);
+        bus.read_u8(address)
+    }
+
+    /// Record the first access matching `watchpoint` along with where the PPU is in the frame.
+    fn watch(&mut self, address: u16, kind: PpuAccessKind) {
+        let matches = self.watchpoint
+            .is_some_and(|watchpoint| watchpoint.kind == kind && watchpoint.address & 0x3FFF == address & 0x3FFF);
+
+        if matches && self.watch_hit.is_none() {
+            self.watch_hit = Some(PpuWatchHit {
+                address,
+                kind,
+                frame: self.frame,
+                scanline: self.scanline,
+                dot: self.cycles,
+            });
+        }
+    }
+
+    /// Read OAM at `oam_addr`.
+    ///
+    /// While rendering the CPU sees whatever the PPU is reading for sprite evaluation instead. We
+    /// don't emulate secondary OAM so this is approximated as:
+    ///
+    /// - Dots 1-64: `0xFF`, as secondary OAM is being cleared
+    /// - Dots 65-256: The primary OAM byte being evaluated
+    /// - Other dots: `0xFF`, the contents of unused secondary OAM slots
+    ///
+    /// The unused bits 2-4 of each sprite's attribute byte always read back as 0.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_registers#OAMDATA
+    pub fn read_oamdata(&self) -> u8 {
+        if self.rendering() && !(65..=256).contains(&self.cycles) {
+            return 0xFF;
+        }
+
+        let data = self.oam_data[self.oam_addr as usize];
+        if self.oam_addr & 0b11 == 2 {
+            data & 0b1110_0011
+        } else {
+            data
+        }
     }
 
+    /// Write OAM at `oam_addr` and move to the next byte.
+    ///
+    /// Writes while rendering are ignored, but they bump the high 6 bits of `oam_addr` (moving to
+    /// the next sprite) as a side effect.
     pub fn write_oamdata(&mut self, data: u8) {
+        if self.rendering() {
+            self.oam_addr = self.oam_addr.wrapping_add(4);
+            return;
+        }
+
         self.oam_data[self.oam_addr as usize] = data;
-        self.oam_addr += 1; // TODO: Does this wrap?
+        self.oam_addr = self.oam_addr.wrapping_add(1);
+    }
+
+    /// Find the sprites on the next scanline, setting `sprite_overflow` if there are more than 8.
+    ///
+    /// Once 8 sprites are found the PPU keeps checking for overflow, but a hardware bug means it
+    /// increments both the sprite index `n` and the byte index `m` after each miss. From then on it
+    /// compares tile numbers, attributes and X positions against the scanline as if they were Y
+    /// positions, giving both false positives and false negatives.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_sprite_evaluation
+    fn evaluate_sprites(&mut self) {
+        let height = if self.ppuctrl.get(PPUCtrlFlag::SpriteSize) { 16 } else { 8 };
+        let scanline = self.scanline;
+        let in_range = |y: u8| scanline >= y as u16 && scanline - (y as u16) < height;
+
+        self.next_sprites.clear();
+        self.next_sprites_include_sprite_0 = in_range(self.oam_data[0]);
+        let mut n = 0;
+        while n < 64 && self.next_sprites.len() < 8 {
+            if in_range(self.oam_data[n * 4]) {
+                let sprite = [
+                    self.oam_data[n * 4],
+                    self.oam_data[n * 4 + 1],
+                    self.oam_data[n * 4 + 2],
+                    self.oam_data[n * 4 + 3],
+                ];
+                self.next_sprites.push(sprite);
+            }
+            n += 1;
+        }
+
+        let mut m = 0;
+        while n < 64 {
+            if in_range(self.oam_data[n * 4 + m]) {
+                self.ppustatus.sprite_overflow = true;
+                return;
+            }
+
+            n += 1;
+            m = (m + 1) & 0b11;
+        }
+    }
+
+    /// True while the PPU is drawing (or preparing to draw) the screen with rendering enabled.
+    /// This covers the visible scanlines and the pre-render scanline.
+    pub(crate) fn rendering(&self) -> bool {
+        let enabled = self.ppumask.show_background || self.ppumask.show_sprites;
+        let scanline = self.scanline as usize;
+        let pre_render_scanline = self.scanlines_per_frame as usize - 1;
+
+        enabled && (scanline < RP2C02::SCREEN_HEIGHT || scanline == pre_render_scanline)
+    }
+}
+
+impl RP2C02 {
+    /// Capture everything the PPU needs to carry on exactly where it left off: its registers and
+    /// latches, OAM, palette RAM, the rendering pipeline, the current dot and the screen drawn so
+    /// far.
+    ///
+    /// Settings like `revision` and debugging state like `watchpoint` aren't included.
+    pub fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&Pixel::into_texture(&self.pixels[..]));
+        state.write_memory(&self.palette_indices[..]);
+        state.write_u16(self.cycles as u16);
+        state.write_u16(self.scanline);
+        state.write_u64(self.frame);
+        state.write_bool(self.frame_complete);
+
+        state.write_u8(self.ppuctrl.0);
+        state.write_u8(self.ppumask.into());
+        state.write_u8(self.ppustatus.lsb_of_previous_ppu_register);
+        state.write_bool(self.ppustatus.sprite_overflow);
+        state.write_bool(self.ppustatus.sprite_0_hit);
+        state.write_bool(self.ppustatus.in_vblank);
+        state.write_bool(self.nmi_requested);
+        state.write_bool(self.warming_up);
+        self.delayed_effects.save_state(state);
+
+        state.write_u8(self.oam_addr);
+        state.write_memory(&self.oam_data);
+        state.write_memory(&self.palette_ram);
+        state.write_u16(self.vram_address.0);
+        state.write_u16(self.temp_vram_address.0);
+        state.write_u8(self.fine_x_scroll);
+        state.write_bool(self.write_latch);
+        state.write_u8(self.read_buffer);
+        state.write_u8(self.io_latch);
+        for refreshed in self.io_latch_refreshed {
+            state.write_u64(refreshed);
+        }
+
+        let tile = self.next_background_tile;
+        state.write_memory(&[tile.pattern_low, tile.pattern_high, tile.palette]);
+        let shifters = self.background_shifters;
+        for shifter in [shifters.pattern_low, shifters.pattern_high, shifters.palette_low, shifters.palette_high] {
+            state.write_u16(shifter);
+        }
+
+        state.write_u8(self.next_sprites.len() as u8);
+        for sprite in &self.next_sprites {
+            state.write_memory(sprite);
+        }
+        state.write_bool(self.next_sprites_include_sprite_0);
+        state.write_u8(self.sprite_rows.len() as u8);
+        for row in &self.sprite_rows {
+            state.write_memory(&[row.x, row.pattern_low, row.pattern_high, row.attributes]);
+            state.write_bool(row.sprite_0);
+        }
+    }
+
+    /// Restore a state from `save_state`.
+    pub fn load_state(&mut self, state: &mut StateReader) -> Result<(), StateError> {
+        let mut rgba = vec![0; RP2C02::SCREEN_PIXELS * 4];
+        state.read_memory(&mut rgba)?;
+        for (pixel, rgba) in self.pixels.iter_mut().zip(rgba.chunks_exact(4)) {
+            *pixel = Pixel::new(rgba[0], rgba[1], rgba[2], rgba[3]);
+        }
+        state.read_memory(&mut self.palette_indices[..])?;
+        self.cycles = state.read_u16()? as usize;
+        self.scanline = state.read_u16()?;
+        self.frame = state.read_u64()?;
+        self.frame_complete = state.read_bool()?;
+
+        self.ppuctrl = PPUCtrl(state.read_u8()?);
+        self.ppumask = PPUMask::from(state.read_u8()?);
+        self.ppustatus.lsb_of_previous_ppu_register = state.read_u8()?;
+        self.ppustatus.sprite_overflow = state.read_bool()?;
+        self.ppustatus.sprite_0_hit = state.read_bool()?;
+        self.ppustatus.in_vblank = state.read_bool()?;
+        self.nmi_requested = state.read_bool()?;
+        self.warming_up = state.read_bool()?;
+        self.delayed_effects.load_state(state)?;
+
+        self.oam_addr = state.read_u8()?;
+        state.read_memory(&mut self.oam_data)?;
+        state.read_memory(&mut self.palette_ram)?;
+        self.vram_address = VramAddress(state.read_u16()?);
+        self.temp_vram_address = VramAddress(state.read_u16()?);
+        self.fine_x_scroll = state.read_u8()?;
+        self.write_latch = state.read_bool()?;
+        self.read_buffer = state.read_u8()?;
+        self.io_latch = state.read_u8()?;
+        for refreshed in self.io_latch_refreshed.iter_mut() {
+            *refreshed = state.read_u64()?;
+        }
+
+        let mut tile = [0; 3];
+        state.read_memory(&mut tile)?;
+        self.next_background_tile = BackgroundTile { pattern_low: tile[0], pattern_high: tile[1], palette: tile[2] };
+        self.background_shifters = BackgroundShifters {
+            pattern_low: state.read_u16()?,
+            pattern_high: state.read_u16()?,
+            palette_low: state.read_u16()?,
+            palette_high: state.read_u16()?,
+        };
+
+        self.next_sprites.clear();
+        for _ in 0..state.read_u8()? {
+            let mut sprite = [0; 4];
+            state.read_memory(&mut sprite)?;
+            self.next_sprites.push(sprite);
+        }
+        self.next_sprites_include_sprite_0 = state.read_bool()?;
+
+        self.sprite_rows.clear();
+        for _ in 0..state.read_u8()? {
+            let mut row = [0; 4];
+            state.read_memory(&mut row)?;
+            self.sprite_rows.push(SpriteRow {
+                x: row[0],
+                pattern_low: row[1],
+                pattern_high: row[2],
+                attributes: row[3],
+                sprite_0: state.read_bool()?,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RP2C02 {
+    fn default() -> Self {
+        RP2C02::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nestalgic_mos6502::mos6502::RamBus16kb;
+
+    fn run_scanline(ppu: &mut RP2C02, bus: &mut RamBus16kb) {
+        let mut cpu = MOS6502::new();
+        for _ in 0..341 {
+            ppu.cycle(&mut cpu, bus);
+        }
+    }
+
+    /// Run the pre-render scanline, which fetches the first tiles and scroll, then scanline 0.
+    fn run_first_scanline(ppu: &mut RP2C02, bus: &mut RamBus16kb) {
+        ppu.scanline = ppu.scanlines_per_frame - 1;
+        run_scanline(ppu, bus);
+        run_scanline(ppu, bus);
+    }
+
+    #[test]
+    pub fn background_uses_nametable_attributes_and_palette() {
+        let mut bus = RamBus16kb::new();
+        bus.memory[0x1010..0x1018].copy_from_slice(&[0b1111_0000; 8]); // Tile 1: colour 1 then 0
+        bus.memory[0x1018..0x1020].copy_from_slice(&[0b1100_0000; 8]); // Upgrade the first 2 to colour 3
+        bus.memory[0x2000] = 1; // Top left tile
+        bus.memory[0x23C0] = 0b0000_0010; // Top left 16x16 uses palette 2
+
+        let mut ppu = RP2C02::new();
+        ppu.ppuctrl.set(PPUCtrlFlag::BackgroundPatternTable, true);
+        ppu.ppumask.show_background = true;
+        ppu.ppumask.show_background_on_left_8_pixels = true;
+        ppu.palette_ram[0x00] = 0x0F;
+        ppu.palette_ram[0x09] = 0x16;
+        ppu.palette_ram[0x0B] = 0x2A;
+
+        run_first_scanline(&mut ppu, &mut bus);
+
+        assert_eq!(ppu.palette_indices[0..9], [0x2A, 0x2A, 0x16, 0x16, 0x0F, 0x0F, 0x0F, 0x0F, 0x0F]);
+        assert_eq!(ppu.pixels[0], ppu.revision.color(0x2A));
+    }
+
+    #[test]
+    pub fn horizontal_scroll_moves_the_background_left() {
+        let mut bus = RamBus16kb::new();
+        bus.memory[0x0010..0x0018].copy_from_slice(&[0b1000_0000; 8]);
+        bus.memory[0x2001] = 1; // Second tile from the left
+
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_background = true;
+        ppu.ppumask.show_background_on_left_8_pixels = true;
+        ppu.palette_ram[0x01] = 0x30;
+        ppu.write_ppuscroll(3);
+        ppu.write_ppuscroll(0);
+
+        run_first_scanline(&mut ppu, &mut bus);
+
+        assert_eq!(ppu.palette_indices[4], 0x00);
+        assert_eq!(ppu.palette_indices[5], 0x30);
+    }
+
+    #[test]
+    pub fn fine_x_scroll_picks_the_palette_of_each_pixel() {
+        let mut bus = RamBus16kb::new();
+        bus.memory[0x0010..0x0018].copy_from_slice(&[0xFF; 8]);
+        bus.memory[0x2000..0x2003].copy_from_slice(&[1, 1, 1]);
+        bus.memory[0x23C0] = 0b0000_1000; // Tiles 0-1 use palette 0, tiles 2-3 use palette 2
+
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_background = true;
+        ppu.ppumask.show_background_on_left_8_pixels = true;
+        ppu.palette_ram[0x01] = 0x11;
+        ppu.palette_ram[0x09] = 0x19;
+        ppu.write_ppuscroll(15); // Coarse X 1, fine X 7
+        ppu.write_ppuscroll(0);
+
+        run_first_scanline(&mut ppu, &mut bus);
+
+        assert_eq!(ppu.palette_indices[0..3], [0x11, 0x19, 0x19]);
+    }
+
+    #[test]
+    pub fn scroll_crosses_into_the_next_nametable() {
+        let mut bus = RamBus16kb::new();
+        bus.memory[0x0010..0x0018].copy_from_slice(&[0xFF; 8]);
+        bus.memory[0x2400 + 32 * 2] = 1; // Nametable 1, first tile of row 2
+
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_background = true;
+        ppu.ppumask.show_background_on_left_8_pixels = true;
+        ppu.palette_ram[0x01] = 0x30;
+        ppu.write_ppuscroll(252);
+        ppu.write_ppuscroll(16);
+
+        run_first_scanline(&mut ppu, &mut bus);
+
+        assert_eq!(ppu.palette_indices[0..13], [0, 0, 0, 0, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0]);
+    }
+
+    #[test]
+    pub fn scroll_and_address_writes_share_the_temporary_address() {
+        let mut ppu = RP2C02::new();
+
+        ppu.write_ppuctrl(0b0000_0000);
+        ppu.read_ppustatus();
+        ppu.write_ppuscroll(0b0111_1101);
+        assert_eq!(ppu.temp_vram_address, VramAddress::new(0, 0, 0, 0b01111));
+        assert_eq!(ppu.fine_x_scroll, 0b101);
+
+        ppu.write_ppuscroll(0b0101_1110);
+        assert_eq!(ppu.temp_vram_address, VramAddress::new(0b110, 0, 0b01011, 0b01111));
+
+        ppu.write_ppuaddr(0b0011_1101);
+        assert_eq!(ppu.temp_vram_address, VramAddress::new(0b011, 0b11, 0b01011, 0b01111));
+        assert_eq!(ppu.vram_address, VramAddress(0));
+
+        ppu.write_ppuaddr(0b1111_0000);
+        assert_eq!(ppu.temp_vram_address, VramAddress::new(0b011, 0b11, 0b01111, 0b10000));
+        assert_eq!(ppu.vram_address, ppu.temp_vram_address);
+    }
+
+    #[test]
+    pub fn sprite_backdrop_entries_mirror_the_background() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+
+        ppu.write_ppuaddr(0x3F);
+        ppu.write_ppuaddr(0x10);
+        ppu.write_ppudata(&mut bus, 0x21);
+
+        assert_eq!(ppu.palette_ram[0x00], 0x21);
+        assert_eq!(bus.memory[0x3F10], 0x00);
+    }
+
+    #[test]
+    pub fn palette_colors_share_the_backdrop() {
+        let mut ppu = RP2C02::new();
+        ppu.palette_ram[0x00] = 0x0F;
+        ppu.palette_ram[0x14] = 0x30;
+        ppu.palette_ram[0x15] = 0x16;
+        ppu.palette_ram[0x16] = 0x2A;
+        ppu.palette_ram[0x17] = 0x12;
+
+        let colors = [0x0F, 0x16, 0x2A, 0x12].map(|index| ppu.revision.color(index));
+        assert_eq!(ppu.palette_colors(5), colors);
+    }
+
+    #[test]
+    pub fn sprites_decode_oam() {
+        let mut ppu = RP2C02::new();
+        ppu.oam_data[4..8].copy_from_slice(&[0x20, 0x43, 0b1110_0010, 0x80]);
+
+        let sprites = ppu.sprites();
+        assert_eq!(sprites.len(), 64);
+        assert_eq!(sprites[1], SpriteInfo {
+            index: 1,
+            x: 0x80,
+            y: 0x20,
+            tile: 0x43,
+            palette: 6,
+            behind_background: true,
+            flip_horizontally: true,
+            flip_vertically: true,
+            height: 8,
+            pattern_address: 0x0430,
+        });
+
+        ppu.ppuctrl.set(PPUCtrlFlag::SpriteSize, true);
+        assert_eq!(ppu.sprites()[1].height, 16);
+        assert_eq!(ppu.sprites()[1].pattern_address, 0x1420);
+    }
+
+    #[test]
+    pub fn oamdata_writes_while_rendering_only_bump_oam_addr() {
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_background = true;
+        ppu.cycles = 100;
+        ppu.oam_addr = 0x05;
+
+        ppu.write_oamdata(0x42);
+
+        assert_eq!(ppu.oam_data[0x05], 0x00);
+        assert_eq!(ppu.oam_addr, 0x09);
+    }
+
+    #[test]
+    pub fn oamdata_reads_while_clearing_secondary_oam_return_ff() {
+        let mut ppu = RP2C02::new();
+        ppu.oam_data[0] = 0x12;
+        ppu.ppumask.show_sprites = true;
+
+        ppu.cycles = 10;
+        assert_eq!(ppu.read_oamdata(), 0xFF);
+
+        ppu.cycles = 100;
+        assert_eq!(ppu.read_oamdata(), 0x12);
+
+        ppu.scanline = 241;
+        ppu.cycles = 10;
+        assert_eq!(ppu.read_oamdata(), 0x12);
+    }
+
+    #[test]
+    pub fn oamdata_attribute_reads_drop_unused_bits() {
+        let mut ppu = RP2C02::new();
+        ppu.oam_data[2] = 0xFF;
+        ppu.oam_data[3] = 0xFF;
+
+        ppu.oam_addr = 2;
+        assert_eq!(ppu.read_oamdata(), 0b1110_0011);
+
+        ppu.oam_addr = 3;
+        assert_eq!(ppu.read_oamdata(), 0xFF);
+    }
+
+    #[test]
+    pub fn oam_addr_is_only_reset_while_rendering() {
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        let mut ppu = RP2C02::new();
+        ppu.scanline = 241;
+        ppu.cycles = 256;
+        ppu.oam_addr = 0x20;
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert_eq!(ppu.oam_addr, 0x20);
+
+        ppu.scanline = 10;
+        ppu.cycles = 256;
+        ppu.ppumask.show_sprites = true;
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert_eq!(ppu.oam_addr, 0x00);
+    }
+
+    fn ppu_with_sprites(sprites: &[[u8; 4]]) -> RP2C02 {
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_sprites = true;
+        ppu.oam_data = [0xFF; 256];
+        for (index, sprite) in sprites.iter().enumerate() {
+            ppu.oam_data[index * 4..index * 4 + 4].copy_from_slice(sprite);
+        }
+
+        ppu
+    }
+
+    /// A PPU drawing `sprites` over a background where the left 4 pixels of every tile use colour 1
+    /// of palette 0 and the rest are transparent. Every sprite uses tile 1, which is solid colour 3.
+    fn ppu_with_sprites_over_background(sprites: &[[u8; 4]]) -> (RP2C02, RamBus16kb) {
+        let mut bus = RamBus16kb::new();
+        bus.memory[0x0000..0x0008].copy_from_slice(&[0b1111_0000; 8]);
+        bus.memory[0x0010..0x0020].copy_from_slice(&[0xFF; 16]);
+
+        let mut ppu = ppu_with_sprites(sprites);
+        ppu.ppumask.show_background = true;
+        ppu.ppumask.show_background_on_left_8_pixels = true;
+        ppu.ppumask.show_sprites_on_left_8_pixels = true;
+        ppu.palette_ram[0x00] = 0x0F;
+        ppu.palette_ram[0x01] = 0x01;
+        ppu.palette_ram[0x13] = 0x13;
+        ppu.palette_ram[0x17] = 0x17;
+        ppu.scanline = 9;
+
+        (ppu, bus)
+    }
+
+    /// The palette indices drawn on scanline 10
+    fn sprite_scanline(ppu: &mut RP2C02, bus: &mut RamBus16kb) -> Vec<u8> {
+        run_scanline(ppu, bus);
+        run_scanline(ppu, bus);
+
+        let start = 10 * RP2C02::SCREEN_WIDTH;
+        ppu.palette_indices[start..start + 16].to_vec()
+    }
+
+    #[test]
+    pub fn sprites_in_front_cover_the_background() {
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[[9, 1, 0b0000_0000, 2]]);
+
+        assert_eq!(
+            sprite_scanline(&mut ppu, &mut bus),
+            [0x01, 0x01, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x13, 0x01, 0x01, 0x0F, 0x0F, 0x0F, 0x0F],
+        );
+    }
+
+    #[test]
+    pub fn sprites_behind_only_show_through_transparent_background() {
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[[9, 1, 0b0010_0000, 2]]);
+
+        assert_eq!(
+            sprite_scanline(&mut ppu, &mut bus),
+            [0x01, 0x01, 0x01, 0x01, 0x13, 0x13, 0x13, 0x13, 0x01, 0x01, 0x01, 0x01, 0x0F, 0x0F, 0x0F, 0x0F],
+        );
+    }
+
+    #[test]
+    pub fn first_opaque_sprite_wins_even_when_behind_the_background() {
+        // Sprite 0 is behind the background but still hides sprite 1 where the background is opaque
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[
+            [9, 1, 0b0010_0000, 0],
+            [9, 1, 0b0000_0001, 0],
+        ]);
+
+        assert_eq!(
+            sprite_scanline(&mut ppu, &mut bus)[0..8],
+            [0x01, 0x01, 0x01, 0x01, 0x13, 0x13, 0x13, 0x13],
+        );
+    }
+
+    #[test]
+    pub fn sprite_0_hits_are_logged_where_they_happen() {
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[[9, 1, 0b0000_0000, 2]]);
+        ppu.log_events = true;
+
+        sprite_scanline(&mut ppu, &mut bus);
+
+        assert!(ppu.ppustatus.sprite_0_hit);
+        assert_eq!(ppu.take_events(), [PpuEvent { kind: PpuEventKind::Sprite0Hit, frame: 0, scanline: 10, dot: 3 }]);
+        assert!(ppu.take_events().is_empty());
+    }
+
+    #[test]
+    pub fn event_log_keeps_the_most_recent_register_accesses() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+        ppu.log_events = true;
+
+        for i in 0..=RP2C02::MAX_EVENTS {
+            ppu.cpu_mapped_write_u8(&mut bus, 0x2003, i as u8);
+        }
+        ppu.cpu_mapped_read_u8(&mut bus, 0x200A);
+
+        let events = ppu.take_events();
+        assert_eq!(events.len(), RP2C02::MAX_EVENTS);
+        assert_eq!(events[0].kind, PpuEventKind::RegisterWrite { address: 0x2003, data: 2 });
+        assert_eq!(events.last().unwrap().kind, PpuEventKind::RegisterRead { address: 0x2002, data: 0 });
+    }
+
+    #[test]
+    pub fn save_state_resumes_mid_scanline() {
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[[9, 1, 0b0000_0000, 2]]);
+        let mut cpu = MOS6502::new();
+        run_scanline(&mut ppu, &mut bus);
+        for _ in 0..100 {
+            ppu.cycle(&mut cpu, &mut bus);
+        }
+
+        let mut state = StateWriter::new();
+        ppu.save_state(&mut state);
+        let state = state.into_bytes();
+
+        let mut restored = RP2C02::new();
+        restored.load_state(&mut StateReader::new(&state)).expect("Failed to load state");
+        run_scanline(&mut ppu, &mut bus);
+        run_scanline(&mut restored, &mut bus);
+
+        let (mut expected, mut actual) = (StateWriter::new(), StateWriter::new());
+        ppu.save_state(&mut expected);
+        restored.save_state(&mut actual);
+        assert_eq!(actual.into_bytes(), expected.into_bytes());
+        assert!(restored.ppustatus.sprite_0_hit);
+    }
+
+    #[test]
+    pub fn sprites_can_be_flipped_horizontally() {
+        let (mut ppu, mut bus) = ppu_with_sprites_over_background(&[[9, 2, 0b0100_0001, 8]]);
+        bus.memory[0x0020..0x0030].copy_from_slice(&[0b1000_0000; 16]); // Tile 2: left column only
+
+        assert_eq!(sprite_scanline(&mut ppu, &mut bus)[8..16], [0x01, 0x01, 0x01, 0x01, 0x0F, 0x0F, 0x0F, 0x17]);
+    }
+
+    #[test]
+    pub fn vblank_starts_at_dot_1_of_scanline_241() {
+        let mut ppu = RP2C02::new();
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        ppu.write_ppuctrl(PPUCtrlFlag::GenerateNmiOnVblank as u8);
+        ppu.scanline = 240;
+        ppu.cycles = 340;
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert_eq!((ppu.scanline, ppu.cycles), (241, 0));
+        assert!(!ppu.ppustatus.in_vblank);
+        assert!(!cpu.nmi);
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(ppu.ppustatus.in_vblank);
+        assert!(cpu.nmi);
+
+        // Cleared at dot 1 of the pre-render scanline
+        ppu.scanline = 261;
+        ppu.cycles = 0;
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(!ppu.ppustatus.in_vblank);
+    }
+
+    #[test]
+    pub fn frame_complete_is_set_for_one_cycle() {
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        let mut ppu = RP2C02::new();
+        ppu.scanline = 261;
+        ppu.cycles = 340;
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(ppu.frame_complete());
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(!ppu.frame_complete());
+    }
+
+    #[test]
+    pub fn odd_frames_skip_the_last_pre_render_dot() {
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        let pre_render_dot_339 = |frame| {
+            let mut ppu = RP2C02::new();
+            ppu.ppumask.show_background = true;
+            ppu.frame = frame;
+            ppu.scanline = 261;
+            ppu.cycles = 339;
+            ppu
+        };
+
+        let mut even = pre_render_dot_339(2);
+        even.cycle(&mut cpu, &mut bus);
+        assert_eq!((even.scanline, even.cycles), (261, 340));
+
+        let mut odd = pre_render_dot_339(3);
+        odd.cycle(&mut cpu, &mut bus);
+        assert_eq!((odd.frame, odd.scanline, odd.cycles), (4, 0, 0));
+
+        let mut odd_without_background = pre_render_dot_339(3);
+        odd_without_background.ppumask.show_background = false;
+        odd_without_background.cycle(&mut cpu, &mut bus);
+        assert_eq!((odd_without_background.scanline, odd_without_background.cycles), (261, 340));
+    }
+
+    #[test]
+    pub fn ppudata_reads_are_buffered_below_palette_ram() {
+        let mut bus = RamBus16kb::new();
+        bus.write_u8(0x2000, 0x11);
+        bus.write_u8(0x2001, 0x22);
+        bus.write_u8(0x2F00, 0x33);
+
+        let mut ppu = RP2C02::new();
+        ppu.palette_ram[0x00] = 0x0F;
+        ppu.vram_address = VramAddress(0x2000);
+
+        assert_eq!(ppu.read_ppudata(&mut bus), 0x00);
+        assert_eq!(ppu.read_ppudata(&mut bus), 0x11);
+        assert_eq!(ppu.read_ppudata(&mut bus), 0x22);
+
+        // Palette reads skip the buffer but refill it from the nametable mirrored underneath
+        ppu.vram_address = VramAddress(0x3F00);
+        assert_eq!(ppu.read_ppudata(&mut bus), 0x0F);
+        assert_eq!(ppu.read_buffer, 0x33);
+    }
+
+    #[test]
+    pub fn greyscale_uses_the_grey_column() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+        ppu.palette_ram[0x00] = 0x2A;
+        ppu.ppumask.greyscale = true;
+
+        run_first_scanline(&mut ppu, &mut bus);
+        assert_eq!(ppu.palette_indices[0], 0x20);
+
+        ppu.vram_address = VramAddress(0x3F00);
+        assert_eq!(ppu.read_ppudata(&mut bus), 0x20);
+        assert_eq!(ppu.palette_ram[0x00], 0x2A);
+    }
+
+    #[test]
+    pub fn delayed_ppumask_writes_take_effect_after_two_dots() {
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        let mut ppu = RP2C02::new();
+        ppu.delay_register_effects = true;
+
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2001, 0b0000_1000);
+        assert!(!ppu.ppumask.show_background);
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(!ppu.ppumask.show_background);
+
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(ppu.ppumask.show_background);
+
+        // Without delays the write lands straight away
+        ppu.delay_register_effects = false;
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2001, 0);
+        assert!(!ppu.ppumask.show_background);
+    }
+
+    #[test]
+    pub fn watchpoints_record_where_the_renderer_fetched() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+        ppu.ppumask.show_background = true;
+        ppu.watchpoint = Some(PpuWatchpoint { address: 0x2002, kind: PpuAccessKind::Fetch });
+
+        run_first_scanline(&mut ppu, &mut bus);
+
+        // The pre-render scanline fetches tiles like any other, reaching the third tile at dot 24
+        let hit = ppu.watch_hit.expect("the renderer should fetch the third tile");
+        assert_eq!((hit.scanline, hit.dot), (261, 24));
+    }
+
+    #[test]
+    pub fn write_only_registers_read_the_decaying_io_latch() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+        ppu.ppustatus.in_vblank = true;
+
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2003, 0b0101_1010);
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x2005), 0b0101_1010);
+
+        // PPUSTATUS only drives the top 3 bits
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x2002), 0b1001_1010);
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x2000), 0b1001_1010);
+
+        ppu.frame += RP2C02::IO_LATCH_DECAY_FRAMES;
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x2006), 0);
+    }
+
+    #[test]
+    pub fn mirrored_reads_drive_the_io_latch_like_the_register() {
+        let mut bus = RamBus16kb::new();
+        let mut ppu = RP2C02::new();
+
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2000, 0b0101_1010);
+
+        // A mirror of PPUSTATUS only drives the top 3 bits, like PPUSTATUS itself
+        ppu.frame += RP2C02::IO_LATCH_DECAY_FRAMES / 2;
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x3FFA), 0b0001_1010);
+
+        // Reading a mirror of a write-only register doesn't stop the rest of the latch decaying
+        ppu.frame += RP2C02::IO_LATCH_DECAY_FRAMES / 2;
+        assert_eq!(ppu.cpu_mapped_read_u8(&mut bus, 0x2008), 0);
+    }
+
+    #[test]
+    pub fn writes_are_ignored_until_warmed_up() {
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        let mut ppu = RP2C02::new();
+        ppu.warming_up = true;
+
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2000, 0b1000_0000);
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2003, 0x12);
+        assert_eq!(ppu.ppuctrl.0, 0);
+        assert_eq!(ppu.oam_addr, 0x12);
+
+        // Warm up finishes at the end of vblank
+        ppu.scanline = 261;
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(!ppu.warming_up);
+
+        ppu.cpu_mapped_write_u8(&mut bus, 0x2000, 0b1000_0000);
+        assert_eq!(ppu.ppuctrl.0, 0b1000_0000);
+    }
+
+    #[test]
+    pub fn enabling_nmi_during_vblank_triggers_it() {
+        let mut ppu = RP2C02::new();
+        let mut bus = RamBus16kb::new();
+        let mut cpu = MOS6502::new();
+        ppu.scanline = 241;
+        run_scanline(&mut ppu, &mut bus);
+        assert!(ppu.ppustatus.in_vblank);
+        assert!(!cpu.nmi);
+
+        ppu.write_ppuctrl(PPUCtrlFlag::GenerateNmiOnVblank as u8);
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(cpu.nmi);
+
+        // Only turning NMIs on triggers one, not writing PPUCTRL while they're already on
+        cpu.nmi = false;
+        ppu.write_ppuctrl(PPUCtrlFlag::GenerateNmiOnVblank as u8);
+        ppu.cycle(&mut cpu, &mut bus);
+        assert!(!cpu.nmi);
+    }
+
+    #[test]
+    pub fn nine_sprites_on_a_scanline_overflow() {
+        let mut ppu = ppu_with_sprites(&[[10, 0, 0, 0]; 9]);
+        let mut bus = RamBus16kb::new();
+        ppu.scanline = 12;
+
+        run_scanline(&mut ppu, &mut bus);
+        assert!(ppu.ppustatus.sprite_overflow);
+
+        let mut ppu = ppu_with_sprites(&[[10, 0, 0, 0]; 8]);
+        ppu.scanline = 12;
+
+        run_scanline(&mut ppu, &mut bus);
+        assert!(!ppu.ppustatus.sprite_overflow);
+    }
+
+    #[test]
+    pub fn overflow_check_reads_the_wrong_byte_after_a_miss() {
+        // After 8 sprites the PPU misses sprite 8 then reads sprite 9's tile number (12) as a Y
+        // position, which is in range.
+        let mut sprites = vec![[10, 0, 0, 0]; 8];
+        sprites.push([200, 0, 0, 0]);
+        sprites.push([200, 12, 0, 0]);
+        let mut ppu = ppu_with_sprites(&sprites);
+        let mut bus = RamBus16kb::new();
+        ppu.scanline = 12;
+
+        run_scanline(&mut ppu, &mut bus);
+        assert!(ppu.ppustatus.sprite_overflow);
+
+        // Here sprite 9 really is on the scanline but the PPU reads its tile number instead
+        let mut sprites = vec![[10, 0, 0, 0]; 8];
+        sprites.push([200, 0, 0, 0]);
+        sprites.push([10, 200, 0, 0]);
+        let mut ppu = ppu_with_sprites(&sprites);
+        ppu.scanline = 12;
+
+        run_scanline(&mut ppu, &mut bus);
+        assert!(!ppu.ppustatus.sprite_overflow);
     }
 }
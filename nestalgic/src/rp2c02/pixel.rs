@@ -1,4 +1,6 @@
+/// Pixels are laid out as RGBA bytes so a frame can be handed to other languages as-is.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Pixel {
     pub red: u8,
     pub green: u8,
@@ -6,12 +8,14 @@ pub struct Pixel {
     pub alpha: u8,
 }
 
+const _: () = assert!(std::mem::size_of::<Pixel>() == 4 && std::mem::align_of::<Pixel>() == 1);
+
 impl Pixel {
-    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Pixel {
+    pub const fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Pixel {
         Pixel { red, green, blue, alpha }
     }
 
-    pub fn empty() -> Pixel {
+    pub const fn empty() -> Pixel {
         Pixel::new(0, 0, 0, 0)
     }
 
@@ -19,6 +23,13 @@ impl Pixel {
         [self.red, self.green, self.blue, self.alpha]
     }
 
+    /// View `pixels` as RGBA bytes in the same order, without copying them.
+    pub fn as_rgba_bytes(pixels: &[Pixel]) -> &[u8] {
+        // SAFETY: `Pixel` is `repr(C)` with four `u8` fields, so it's four bytes with no padding
+        // and the same alignment as `u8`. See the assertion above.
+        unsafe { std::slice::from_raw_parts(pixels.as_ptr() as *const u8, pixels.len() * 4) }
+    }
+
     pub fn into_texture(pixels: &[Pixel]) -> Vec<u8> {
         pixels
             .into_iter()
@@ -0,0 +1,30 @@
+/// Something the PPU did that affects the CPU's view of frame timing, see `PpuEvent`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum PpuEventKind {
+    /// The CPU read a PPU register (`0x2000`-`0x2007`). `data` is the value the CPU saw.
+    RegisterRead { address: u16, data: u8 },
+
+    /// The CPU wrote a PPU register (`0x2000`-`0x2007`).
+    RegisterWrite { address: u16, data: u8 },
+
+    /// The PPU asserted NMI.
+    Nmi,
+
+    /// An opaque pixel of sprite 0 was drawn over an opaque background pixel.
+    Sprite0Hit,
+}
+
+/// An entry in the PPU event log, with where in the frame it happened.
+///
+/// Raster effects depend on the CPU touching the PPU at the right point in the frame, so when a
+/// split screen jitters or a status bar tears, comparing the dots of these events against the
+/// expected timing usually shows which write is late.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_rendering
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct PpuEvent {
+    pub kind: PpuEventKind,
+    pub frame: u64,
+    pub scanline: u16,
+    pub dot: usize,
+}
@@ -0,0 +1,30 @@
+/// The kinds of PPU memory access a `PpuWatchpoint` can stop on.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum PpuAccessKind {
+    /// The CPU wrote the address through `PPUDATA`.
+    Write,
+
+    /// The rendering pipeline read the address to draw a tile, sprite or pixel.
+    Fetch,
+}
+
+/// Watch for an access to `address` in the PPU's address space: pattern tables, nametables or
+/// palette RAM.
+///
+/// Addresses are compared after masking to 14 bits but mirrors aren't resolved, so a watchpoint
+/// on `0x2400` won't see writes to `0x2000` even if the cartridge mirrors them.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct PpuWatchpoint {
+    pub address: u16,
+    pub kind: PpuAccessKind,
+}
+
+/// Where the PPU was when a `PpuWatchpoint` was hit.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct PpuWatchHit {
+    pub address: u16,
+    pub kind: PpuAccessKind,
+    pub frame: u64,
+    pub scanline: u16,
+    pub dot: usize,
+}
@@ -87,6 +87,7 @@ impl Default for PPUCtrl {
 
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum PPUCtrlFlag {
     NametableLo             = 0b0000_0001,
     NametableHi             = 0b0000_0010,
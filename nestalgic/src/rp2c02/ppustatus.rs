@@ -21,6 +21,10 @@
 pub struct PPUStatus {
     pub lsb_of_previous_ppu_register: u8,
 
+    /// `sprite_overflow` is set when sprite evaluation finds more than 8 sprites on a scanline.
+    /// Due to a hardware bug it is sometimes set with 8 or fewer sprites, or missed with more.
+    ///
+    /// `sprite_overflow` is reset to false at dot 1 of the pre-render line.
     pub sprite_overflow: bool,
 
     /// `sprite_0_hit` is set to true if a non-zero pixel of sprite 0 overlaps with a non-zero background pixel.
@@ -0,0 +1,201 @@
+use super::{Pixel, PPUMask};
+
+/// The PPU chips we can emulate. Each one has its own colour palette and a few behavioural quirks.
+///
+/// The RGB PPUs (`RP2C03` and `RP2C05`) were used in the VS. System, PlayChoice-10 and Famicom
+/// Titler. They output RGB directly rather than composite video, so they have much more saturated
+/// colours than a console.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_variants
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub enum PPURevision {
+    /// The NTSC PPU found in North American and Japanese consoles.
+    #[default]
+    RP2C02,
+
+    /// The PAL PPU. PAL timing is handled by `Region::Pal`; on top of that the 2C07 swaps the
+    /// red and green emphasis bits of `PPUMask`.
+    RP2C07,
+
+    /// The RGB PPU used by the PlayChoice-10 and some VS. System boards.
+    RP2C03,
+
+    /// The RGB PPU used by later VS. System boards. The 2C05 swaps `PPUCtrl` and `PPUMask`
+    /// (`0x2000` and `0x2001`) and returns a chip ID in the low 5 bits of `PPUStatus` that some
+    /// games use for copy protection.
+    RP2C05 { status_id: u8 },
+}
+
+impl PPURevision {
+    /// The colour this PPU outputs for `palette_index` (`0x00`-`0x3F`).
+    ///
+    /// Emphasis and greyscale are not applied.
+    pub fn color(&self, palette_index: u8) -> Pixel {
+        let [red, green, blue] = match self {
+            PPURevision::RP2C02 | PPURevision::RP2C07 => {
+                COMPOSITE_PALETTE[(palette_index & 0x3F) as usize]
+            },
+            PPURevision::RP2C03 | PPURevision::RP2C05 { .. } => {
+                // Each RGB PPU colour channel is 3 bits, stored here as an octal digit
+                let rgb = RGB_PALETTE[(palette_index & 0x3F) as usize];
+                let channel = |shift: u16| (((rgb >> shift) & 0b111) * 255 / 7) as u8;
+                [channel(6), channel(3), channel(0)]
+            },
+        };
+
+        Pixel::new(red, green, blue, 255)
+    }
+
+    /// The colour this PPU outputs for `palette_index` with the emphasis bits of `ppumask` applied.
+    ///
+    /// On composite PPUs each emphasis bit darkens the other two colour channels, so emphasising
+    /// red makes the picture look redder by dimming green and blue. The black columns (`0x0E`,
+    /// `0x0F`, `0x1E`...) aren't affected. The RGB PPUs instead drive the emphasised channel at
+    /// full intensity.
+    ///
+    /// `ppumask` should already be in `PPUMask` bit order, see `ppumask_bits`.
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/Colour_emphasis
+    pub fn emphasised_color(&self, palette_index: u8, ppumask: &PPUMask) -> Pixel {
+        let color = self.color(palette_index);
+        let emphasis = [ppumask.emphasise_red, ppumask.emphasise_green, ppumask.emphasise_blue];
+        if !emphasis.contains(&true) {
+            return color;
+        }
+
+        let mut channels = [color.red, color.green, color.blue];
+        match self {
+            PPURevision::RP2C02 | PPURevision::RP2C07 => {
+                if palette_index & 0x0E == 0x0E {
+                    return color;
+                }
+
+                for (index, channel) in channels.iter_mut().enumerate() {
+                    let attenuated = emphasis
+                        .iter()
+                        .enumerate()
+                        .any(|(other, &emphasise)| emphasise && other != index);
+                    if attenuated {
+                        *channel = (*channel as u32 * EMPHASIS_ATTENUATION / 1000) as u8;
+                    }
+                }
+            },
+            PPURevision::RP2C03 | PPURevision::RP2C05 { .. } => {
+                for (channel, &emphasised) in channels.iter_mut().zip(emphasis.iter()) {
+                    if emphasised {
+                        *channel = 255;
+                    }
+                }
+            },
+        }
+
+        Pixel::new(channels[0], channels[1], channels[2], 255)
+    }
+
+    /// Translate a CPU write address into the PPU register it actually writes.
+    pub(crate) fn register_address(&self, address: u16) -> u16 {
+        match (self, address) {
+            (PPURevision::RP2C05 { .. }, 0x2000) => 0x2001,
+            (PPURevision::RP2C05 { .. }, 0x2001) => 0x2000,
+            _ => address,
+        }
+    }
+
+    /// Rearrange a write to `0x2001` into the bit layout used by `PPUMask`.
+    pub(crate) fn ppumask_bits(&self, data: u8) -> u8 {
+        match self {
+            PPURevision::RP2C07 => {
+                let red = (data & 0b0100_0000) >> 1;
+                let green = (data & 0b0010_0000) << 1;
+                (data & 0b1001_1111) | red | green
+            },
+            _ => data,
+        }
+    }
+
+    /// Bits this PPU drives into the low 5 bits of `PPUStatus`, if any.
+    pub(crate) fn status_id(&self) -> Option<u8> {
+        match self {
+            PPURevision::RP2C05 { status_id } => Some(status_id & 0b0001_1111),
+            _ => None,
+        }
+    }
+}
+
+/// An approximation of the colours produced by a composite PPU, since the exact colours depend on
+/// the TV.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_palettes#2C02
+const COMPOSITE_PALETTE: [[u8; 3]; 64] = [
+    [0x54, 0x54, 0x54], [0x00, 0x1E, 0x74], [0x08, 0x10, 0x90], [0x30, 0x00, 0x88],
+    [0x44, 0x00, 0x64], [0x5C, 0x00, 0x30], [0x54, 0x04, 0x00], [0x3C, 0x18, 0x00],
+    [0x20, 0x2A, 0x00], [0x08, 0x3A, 0x00], [0x00, 0x40, 0x00], [0x00, 0x3C, 0x00],
+    [0x00, 0x32, 0x3C], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+
+    [0x98, 0x96, 0x98], [0x08, 0x4C, 0xC4], [0x30, 0x32, 0xEC], [0x5C, 0x1E, 0xE4],
+    [0x88, 0x14, 0xB0], [0xA0, 0x14, 0x64], [0x98, 0x22, 0x20], [0x78, 0x3C, 0x00],
+    [0x54, 0x5A, 0x00], [0x28, 0x72, 0x00], [0x08, 0x7C, 0x00], [0x00, 0x76, 0x28],
+    [0x00, 0x66, 0x78], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+
+    [0xEC, 0xEE, 0xEC], [0x4C, 0x9A, 0xEC], [0x78, 0x7C, 0xEC], [0xB0, 0x62, 0xEC],
+    [0xE4, 0x54, 0xEC], [0xEC, 0x58, 0xB4], [0xEC, 0x6A, 0x64], [0xD4, 0x88, 0x20],
+    [0xA0, 0xAA, 0x00], [0x74, 0xC4, 0x00], [0x4C, 0xD0, 0x20], [0x38, 0xCC, 0x6C],
+    [0x38, 0xB4, 0xCC], [0x3C, 0x3C, 0x3C], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+
+    [0xEC, 0xEE, 0xEC], [0xA8, 0xCC, 0xEC], [0xBC, 0xBC, 0xEC], [0xD4, 0xB2, 0xEC],
+    [0xEC, 0xAE, 0xEC], [0xEC, 0xAE, 0xD4], [0xEC, 0xB4, 0xB0], [0xE4, 0xC4, 0x90],
+    [0xCC, 0xD2, 0x78], [0xB4, 0xDE, 0x78], [0xA8, 0xE2, 0x90], [0x98, 0xE2, 0xB4],
+    [0xA0, 0xD6, 0xE4], [0xA0, 0xA2, 0xA0], [0x00, 0x00, 0x00], [0x00, 0x00, 0x00],
+];
+
+/// How much each emphasis bit dims the other channels on a composite PPU, in thousandths. Each
+/// additional bit dims the channel no further.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/NTSC_video#Color_Tint_Bits
+const EMPHASIS_ATTENUATION: u32 = 746;
+
+/// The colours produced by the RGB PPUs, as one octal digit per channel (`0oRGB`).
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_palettes#2C03_and_2C05
+const RGB_PALETTE: [u16; 64] = [
+    0o333, 0o014, 0o006, 0o326, 0o403, 0o503, 0o510, 0o420,
+    0o320, 0o120, 0o031, 0o040, 0o022, 0o000, 0o000, 0o000,
+    0o555, 0o036, 0o027, 0o407, 0o507, 0o704, 0o700, 0o630,
+    0o430, 0o140, 0o040, 0o053, 0o044, 0o000, 0o000, 0o000,
+    0o777, 0o357, 0o447, 0o637, 0o707, 0o737, 0o740, 0o750,
+    0o660, 0o360, 0o070, 0o276, 0o077, 0o000, 0o000, 0o000,
+    0o777, 0o567, 0o657, 0o757, 0o747, 0o755, 0o764, 0o772,
+    0o773, 0o572, 0o473, 0o276, 0o467, 0o000, 0o000, 0o000,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn rgb_ppus_use_full_intensity_channels() {
+        assert_eq!(PPURevision::RP2C03.color(0x30), Pixel::new(255, 255, 255, 255));
+        assert_eq!(PPURevision::RP2C03.color(0x16), Pixel::new(255, 0, 0, 255));
+        assert_eq!(PPURevision::RP2C02.color(0x0F), Pixel::new(0, 0, 0, 255));
+    }
+
+    #[test]
+    pub fn emphasis_dims_the_other_channels() {
+        let mut ppumask = PPUMask { emphasise_red: true, ..PPUMask::default() };
+
+        // 0x30 is [0xEC, 0xEE, 0xEC]
+        assert_eq!(PPURevision::RP2C02.emphasised_color(0x30, &ppumask), Pixel::new(0xEC, 0xB1, 0xB0, 255));
+        assert_eq!(PPURevision::RP2C02.emphasised_color(0x0F, &ppumask), PPURevision::RP2C02.color(0x0F));
+        assert_eq!(PPURevision::RP2C03.emphasised_color(0x0F, &ppumask), Pixel::new(255, 0, 0, 255));
+
+        ppumask.emphasise_blue = true;
+        assert_eq!(PPURevision::RP2C02.emphasised_color(0x30, &ppumask), Pixel::new(0xB0, 0xB1, 0xB0, 255));
+    }
+
+    #[test]
+    pub fn rp2c07_swaps_red_and_green_emphasis() {
+        assert_eq!(PPURevision::RP2C07.ppumask_bits(0b0010_0001), 0b0100_0001);
+        assert_eq!(PPURevision::RP2C07.ppumask_bits(0b1100_0000), 0b1010_0000);
+        assert_eq!(PPURevision::RP2C02.ppumask_bits(0b0010_0001), 0b0010_0001);
+    }
+}
@@ -0,0 +1,56 @@
+/// One of the 64 sprites in OAM, decoded for debugging. See `RP2C02::sprites`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct SpriteInfo {
+    /// The sprite's position in OAM (0-63). Lower indexes are drawn in front of higher ones.
+    pub index: u8,
+
+    /// The X position of the left of the sprite
+    pub x: u8,
+
+    /// The Y position of the top of the sprite, minus 1. Sprites are hidden by moving them below
+    /// the screen with a `y` of `0xEF` or more.
+    pub y: u8,
+
+    /// The tile index as stored in OAM. In 8x16 mode bit 0 picks the pattern table instead
+    pub tile: u8,
+
+    /// The sprite palette (4-7), see `RP2C02::palette_colors`
+    pub palette: u8,
+
+    pub behind_background: bool,
+    pub flip_horizontally: bool,
+    pub flip_vertically: bool,
+
+    /// 8 or 16, depending on `PPUCtrlFlag::SpriteSize` when the sprite was decoded
+    pub height: u8,
+
+    /// The address of the sprite's first tile. 8x16 sprites continue into the next tile.
+    pub pattern_address: u16,
+}
+
+impl SpriteInfo {
+    /// Decode the 4 bytes of OAM for sprite `index`. See `RP2C02::fetch_sprite_rows` for the
+    /// layout.
+    pub(crate) fn decode(index: u8, oam: [u8; 4], tall_sprites: bool, pattern_table_address: u16) -> SpriteInfo {
+        let [y, tile, attributes, x] = oam;
+
+        let pattern_address = if tall_sprites {
+            (tile & 1) as u16 * 0x1000 + (tile & 0b1111_1110) as u16 * 16
+        } else {
+            pattern_table_address + tile as u16 * 16
+        };
+
+        SpriteInfo {
+            index,
+            x,
+            y,
+            tile,
+            palette: 4 + (attributes & 0b11),
+            behind_background: attributes & 0b0010_0000 != 0,
+            flip_horizontally: attributes & 0b0100_0000 != 0,
+            flip_vertically: attributes & 0b1000_0000 != 0,
+            height: if tall_sprites { 16 } else { 8 },
+            pattern_address,
+        }
+    }
+}
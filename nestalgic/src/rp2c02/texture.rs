@@ -25,11 +25,22 @@ impl Texture {
         }
     }
 
+    /// Placeholder colours for drawing tiles before we know their real palette. `render_ascii`
+    /// draws these as `.`, `1`, `2` and `3`.
+    pub const DEBUG_PALETTE: [Pixel; 4] = [
+        Pixel::empty(),
+        Pixel::new(255, 0, 0, 255),
+        Pixel::new(0, 255, 0, 255),
+        Pixel::new(0, 0, 255, 255),
+    ];
+
     /// The NES stores pattern tables as bitplanes, which is a packed format that
     /// represents larger bytes as sequences of bits, which must be combined to form
     /// the true "byte".
     ///
-    /// This function assumes we want to merge a bitplane with a bit depth of 2.
+    /// This function assumes we want to merge a bitplane with a bit depth of 2. Tiles are laid out
+    /// left to right, top to bottom and drawn with `DEBUG_PALETTE`. Use `from_bitplanes_in_palette`
+    /// to draw them with real colours.
     ///
     /// # Arguments
     ///
@@ -41,6 +52,13 @@ impl Texture {
     /// - https://wiki.nesdev.com/w/index.php/PPU_pattern_tables
     pub fn from_bitplanes(
         bytes: &[u8], tile_length: usize, width: usize, height: usize
+    ) -> Texture {
+        Texture::from_bitplanes_in_palette(bytes, tile_length, width, height, Texture::DEBUG_PALETTE)
+    }
+
+    /// Like `from_bitplanes` but draws each tile with `palette`.
+    pub fn from_bitplanes_in_palette(
+        bytes: &[u8], tile_length: usize, width: usize, height: usize, palette: [Pixel; 4]
     ) -> Texture {
         assert!(
             bytes.len() % tile_length == 0,
@@ -55,37 +73,93 @@ impl Texture {
             tile_length
         );
 
-        // Each 16 bytes defines a 8x8 sprite within the pattern table, unfortunately there isn't a linear
-        // relationship between bytes and pixels which means we need to translate from our byte indexes to
-        // our target pixel coordinates.
-        let mut pixels = vec![Pixel::empty(); width * height];
-        for (i, chr) in bytes.chunks(16).enumerate() {
-            for y in 0..8 {
-                let line_byte_1 = chr[y];
-                let line_byte_2 = chr[8 + y];
-
-                for x in 0..8 {
-                    let pixel_bit_1 = (line_byte_1 >> 7 - x) & 1;
-                    let pixel_bit_2 = (line_byte_2 >> 7 - x) & 1;
-                    let pixel_value = pixel_bit_1 + (pixel_bit_2 << 1);
-
-                    let offset_x = (i * 8) % width;
-                    let offset_y = (i / 16) * 8;
-                    let pixel_x = offset_x + x;
-                    let pixel_y = offset_y + y;
-
-                    pixels[(pixel_y * width) + pixel_x] = match pixel_value {
-                        0 => Pixel::empty(),
-                        1 => Pixel::new(255, 0, 0, 255),
-                        2 => Pixel::new(0, 255, 0, 255),
-                        3 => Pixel::new(0, 0, 255, 255),
-                        _ => Pixel::new(255, 0, 255, 255)
-                    };
-                }
-            }
+        let mut texture = Texture::new(&vec![Pixel::empty(); width * height], width, height);
+        for (i, chr) in bytes.chunks(tile_length).enumerate() {
+            let tile = Texture::from_tile(chr, palette);
+            texture.blit(&tile, (i * 8) % width, (i * 8 / width) * 8);
         }
 
-        Texture::new(&pixels, width, height)
+        texture
+    }
+
+    /// Draw a single 8x8 tile from its 16 bytes of CHR data, using `palette` for each of the 4
+    /// colours a tile can use.
+    ///
+    /// Each row of the tile is stored in two bytes, 8 bytes apart. The first byte holds the low
+    /// bit of each pixel and the second byte holds the high bit:
+    ///
+    /// ```text
+    /// Byte 0: 0100_0001 -+
+    /// Byte 8: 0000_0001 -+-> .1.....3
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/PPU_pattern_tables
+    pub fn from_tile(chr: &[u8], palette: [Pixel; 4]) -> Texture {
+        assert!(chr.len() >= 16, "a tile needs 16 bytes of CHR data, got {}", chr.len());
+
+        let pixels = (0..64).map(|i| {
+            let (x, y) = (i % 8, i / 8);
+            let low_bit = (chr[y] >> (7 - x)) & 1;
+            let high_bit = (chr[8 + y] >> (7 - x)) & 1;
+
+            palette[(low_bit | (high_bit << 1)) as usize]
+        }).collect::<Vec<Pixel>>();
+
+        Texture::new(&pixels, 8, 8)
+    }
+
+    /// Arrange equally sized `tiles` into a grid `columns` tiles wide, filling left to right and
+    /// top to bottom. Any unfilled space in the last row is left empty.
+    pub fn compose_grid(tiles: &[Texture], columns: usize) -> Texture {
+        let (tile_width, tile_height) = tiles.first()
+            .map(|tile| (tile.width, tile.height))
+            .unwrap_or((0, 0));
+
+        assert!(
+            tiles.iter().all(|tile| tile.width == tile_width && tile.height == tile_height),
+            "every tile in a grid must be {}x{}",
+            tile_width,
+            tile_height
+        );
+
+        let rows = tiles.len().div_ceil(columns);
+        let width = columns * tile_width;
+        let height = rows * tile_height;
+
+        let mut texture = Texture::new(&vec![Pixel::empty(); width * height], width, height);
+        for (i, tile) in tiles.iter().enumerate() {
+            texture.blit(tile, (i % columns) * tile_width, (i / columns) * tile_height);
+        }
+
+        texture
+    }
+
+    /// Copy `src` onto this texture with its top-left corner at (`x`, `y`). Anything that falls
+    /// outside this texture is clipped.
+    pub fn blit(&mut self, src: &Texture, x: usize, y: usize) {
+        let visible_width = src.width.min(self.width.saturating_sub(x));
+        let visible_height = src.height.min(self.height.saturating_sub(y));
+
+        for row in 0..visible_height {
+            let src_start = row * src.width;
+            let dest_start = (y + row) * self.width + x;
+
+            self.pixels[dest_start..dest_start + visible_width]
+                .copy_from_slice(&src.pixels[src_start..src_start + visible_width]);
+        }
+    }
+
+    /// A copy of this texture mirrored left to right and/or top to bottom.
+    pub fn flipped(&self, horizontally: bool, vertically: bool) -> Texture {
+        let pixels = (0..self.width * self.height).map(|i| {
+            let (x, y) = (i % self.width, i / self.width);
+            let x = if horizontally { self.width - 1 - x } else { x };
+            let y = if vertically { self.height - 1 - y } else { y };
+
+            self.pixels[y * self.width + x]
+        }).collect::<Vec<Pixel>>();
+
+        Texture::new(&pixels, self.width, self.height)
     }
 
     pub fn to_rgba(&self) -> Vec<u8> {
@@ -97,7 +171,7 @@ impl Texture {
 
     pub fn render_ascii(&self) -> String {
         self.pixels
-            .chunks(self.height)
+            .chunks(self.width)
             .map(|pixel_row| {
                 let row_text = pixel_row.iter().map(|pixel| {
                     match pixel {
@@ -123,84 +197,128 @@ impl Texture {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Pixel;
+
+    /// Two tiles, each 16 bytes of CHR data
+    const TILES: [u8; 32] = [
+        // Plane 1
+        0b01000001,
+        0b11000010,
+        0b01000100,
+        0b01001000,
+        0b00010000,
+        0b00100000,
+        0b01000000,
+        0b10000000,
+
+        // Plane 2
+        0b00000001,
+        0b00000010,
+        0b00000100,
+        0b00001000,
+        0b00010110,
+        0b00100001,
+        0b01000010,
+        0b10000111,
+
+        // Plane 1
+        0b01000011,
+        0b11000010,
+        0b01000100,
+        0b01001000,
+        0b00010000,
+        0b00100000,
+        0b01000000,
+        0b10000000,
+
+        // Plane 2
+        0b00000011,
+        0b00000010,
+        0b00000100,
+        0b00001000,
+        0b00010110,
+        0b00100001,
+        0b01000010,
+        0b10000111,
+    ];
 
     #[test]
     pub fn texture_from_bitplanes() {
-        let bytes = vec![
-            // Plane 1
-            0b01000001,
-            0b11000010,
-            0b01000100,
-            0b01001000,
-            0b00010000,
-            0b00100000,
-            0b01000000,
-            0b10000000,
-
-            // Plane 2
-            0b00000001,
-            0b00000010,
-            0b00000100,
-            0b00001000,
-            0b00010110,
-            0b00100001,
-            0b01000010,
-            0b10000111,
-
-            // Plane 1
-            0b01000011,
-            0b11000010,
-            0b01000100,
-            0b01001000,
-            0b00010000,
-            0b00100000,
-            0b01000000,
-            0b10000000,
-
-            // Plane 2
-            0b00000011,
-            0b00000010,
-            0b00000100,
-            0b00001000,
-            0b00010110,
-            0b00100001,
-            0b01000010,
-            0b10000111,
+        let result = Texture::from_bitplanes(&TILES, 16, 16, 8);
+
+        assert_eq!(result.render_ascii(), [
+            ".1.....3 .1....33",
+            "11....3. 11....3.",
+            ".1...3.. .1...3..",
+            ".1..3... .1..3...",
+            "...3.22. ...3.22.",
+            "..3....2 ..3....2",
+            ".3....2. .3....2.",
+            "3....222 3....222",
+        ].join("\n"));
+    }
+
+    #[test]
+    pub fn from_bitplanes_wraps_tiles_onto_the_next_row() {
+        let result = Texture::from_bitplanes(&TILES, 16, 8, 16);
+
+        assert_eq!(result.render_ascii().lines().nth(8), Some(".1....33"));
+    }
+
+    #[test]
+    pub fn from_tile_uses_palette() {
+        let palette = [
+            Pixel::empty(),
+            Pixel::new(0, 0, 255, 255),
+            Pixel::new(0, 255, 0, 255),
+            Pixel::new(255, 0, 0, 255),
         ];
 
-        let expected = vec![
-            0,1,0,0,0,0,0,3,
-            1,1,0,0,0,0,3,0,
-            0,1,0,0,0,3,0,0,
-            0,1,0,0,3,0,0,0,
-            0,0,0,3,0,2,2,0,
-            0,0,3,0,0,0,0,2,
-            0,3,0,0,0,0,2,0,
-            3,0,0,0,0,2,2,2,
-
-            0,1,0,0,0,0,3,3,
-            1,1,0,0,0,0,3,0,
-            0,1,0,0,0,3,0,0,
-            0,1,0,0,3,0,0,0,
-            0,0,0,3,0,2,2,0,
-            0,0,3,0,0,0,0,2,
-            0,3,0,0,0,0,2,0,
-            3,0,0,0,0,2,2,2,
+        let tile = Texture::from_tile(&TILES[0..16], palette);
+
+        assert_eq!(tile.render_ascii().lines().next(), Some(".3.....1"));
+    }
+
+    #[test]
+    pub fn compose_grid_leaves_unfilled_tiles_empty() {
+        let tiles = [
+            Texture::from_tile(&TILES[0..16], Texture::DEBUG_PALETTE),
+            Texture::from_tile(&TILES[16..32], Texture::DEBUG_PALETTE),
+            Texture::from_tile(&TILES[0..16], Texture::DEBUG_PALETTE),
         ];
-        let expected: Vec<Pixel> = expected.into_iter().map(|colour| {
-            match colour {
-                0 => Pixel::empty(),
-                1 => Pixel::new(255, 0, 0, 255),
-                2 => Pixel::new(0, 255, 0, 255),
-                3 => Pixel::new(0, 0, 255, 255),
-                _ => Pixel::new(255, 0, 255, 255)
-            }
-        }).collect();
-        let expected = Texture::new(&expected, 16, 8);
-
-        let result = Texture::from_bitplanes(&bytes, 16, 16, 8);
-
-        assert_eq!(result, expected);
+
+        let grid = Texture::compose_grid(&tiles, 2);
+
+        assert_eq!((grid.width, grid.height), (16, 16));
+        assert_eq!(grid.render_ascii().lines().nth(7), Some("3....222 3....222"));
+        assert_eq!(grid.render_ascii().lines().nth(8), Some(".1.....3 ........"));
+    }
+
+    #[test]
+    pub fn blit_clips_to_destination() {
+        let mut texture = Texture::new(&[Pixel::empty(); 64], 8, 8);
+        let tile = Texture::from_tile(&TILES[0..16], Texture::DEBUG_PALETTE);
+
+        texture.blit(&tile, 4, 6);
+
+        assert_eq!(texture.render_ascii(), [
+            "........",
+            "........",
+            "........",
+            "........",
+            "........",
+            "........",
+            ".....1..",
+            "....11..",
+        ].join("\n"));
+    }
+
+    #[test]
+    pub fn flipped_mirrors_both_axes() {
+        let tile = Texture::from_tile(&TILES[0..16], Texture::DEBUG_PALETTE);
+
+        let flipped = tile.flipped(true, true);
+
+        assert_eq!(flipped.render_ascii().lines().next(), Some("222....3"));
+        assert_eq!(flipped.render_ascii().lines().last(), Some("3.....1."));
     }
 }
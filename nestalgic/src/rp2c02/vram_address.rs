@@ -0,0 +1,140 @@
+/// `VramAddress` is the layout of the PPU's internal `v` and `t` registers.
+///
+/// While rendering the PPU uses the address to track which tile it is drawing, so the bits are
+/// split into a scroll position:
+///
+/// ```text
+/// yyy NN YYYYY XXXXX
+/// ||| || ||||| +++++-- Coarse X scroll (the tile column)
+/// ||| || +++++-------- Coarse Y scroll (the tile row)
+/// ||| ++-------------- Nametable select
+/// +++----------------- Fine Y scroll (the row within the tile)
+/// ```
+///
+/// Outside rendering the lower 14 bits are the address read and written by `PPUDATA`.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/PPU_scrolling
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct VramAddress(pub u16);
+
+impl VramAddress {
+    pub const COARSE_X: u16 = 0x001F;
+    pub const COARSE_Y: u16 = 0x03E0;
+    pub const NAMETABLE_X: u16 = 0x0400;
+    pub const NAMETABLE_Y: u16 = 0x0800;
+    pub const NAMETABLE: u16 = VramAddress::NAMETABLE_X | VramAddress::NAMETABLE_Y;
+    pub const FINE_Y: u16 = 0x7000;
+
+    /// The bits copied from `t` to `v` at the end of each scanline
+    pub const HORIZONTAL: u16 = VramAddress::COARSE_X | VramAddress::NAMETABLE_X;
+
+    /// The bits copied from `t` to `v` during the pre-render scanline
+    pub const VERTICAL: u16 = VramAddress::COARSE_Y | VramAddress::NAMETABLE_Y | VramAddress::FINE_Y;
+
+    pub fn new(fine_y: u16, nametable: u16, coarse_y: u16, coarse_x: u16) -> VramAddress {
+        VramAddress(
+            ((fine_y & 0b111) << 12) | ((nametable & 0b11) << 10) | ((coarse_y & 0x1F) << 5) | (coarse_x & 0x1F)
+        )
+    }
+
+    pub fn coarse_x(&self) -> u16 {
+        self.0 & VramAddress::COARSE_X
+    }
+
+    pub fn coarse_y(&self) -> u16 {
+        (self.0 & VramAddress::COARSE_Y) >> 5
+    }
+
+    pub fn nametable(&self) -> u16 {
+        (self.0 >> 10) & 0b11
+    }
+
+    pub fn fine_y(&self) -> u16 {
+        (self.0 & VramAddress::FINE_Y) >> 12
+    }
+
+    /// The address `PPUDATA` reads and writes
+    pub fn address(&self) -> u16 {
+        self.0 & 0x3FFF
+    }
+
+    /// The address of the nametable entry for the current tile
+    pub fn tile_address(&self) -> u16 {
+        0x2000 | (self.0 & 0x0FFF)
+    }
+
+    /// The address of the attribute byte covering the current tile
+    pub fn attribute_address(&self) -> u16 {
+        0x23C0 | (self.0 & 0x0C00) | ((self.0 >> 4) & 0x38) | ((self.0 >> 2) & 0x07)
+    }
+
+    /// Replace the bits in `mask` with the same bits from `other`.
+    pub fn copy_bits(&mut self, other: VramAddress, mask: u16) {
+        self.0 = (self.0 & !mask) | (other.0 & mask);
+    }
+
+    /// Move to the next tile column, wrapping into the horizontally adjacent nametable.
+    pub fn increment_x(&mut self) {
+        if self.coarse_x() == 31 {
+            self.0 &= !VramAddress::COARSE_X;
+            self.0 ^= VramAddress::NAMETABLE_X;
+        } else {
+            self.0 += 1;
+        }
+    }
+
+    /// Move to the next row of pixels, wrapping into the vertically adjacent nametable after row
+    /// 29. Rows 30 and 31 hold attributes rather than tiles, but if the scroll puts us there
+    /// we wrap to row 0 of the same nametable instead.
+    pub fn increment_y(&mut self) {
+        if self.fine_y() < 7 {
+            self.0 += 0x1000;
+            return;
+        }
+
+        self.0 &= !VramAddress::FINE_Y;
+        let coarse_y = match self.coarse_y() {
+            29 => {
+                self.0 ^= VramAddress::NAMETABLE_Y;
+                0
+            },
+            31 => 0,
+            coarse_y => coarse_y + 1,
+        };
+        self.0 = (self.0 & !VramAddress::COARSE_Y) | (coarse_y << 5);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn increment_x_wraps_into_the_next_nametable() {
+        let mut address = VramAddress::new(0, 0, 0, 31);
+        address.increment_x();
+        assert_eq!(address, VramAddress::new(0, 1, 0, 0));
+
+        address.increment_x();
+        assert_eq!(address, VramAddress::new(0, 1, 0, 1));
+    }
+
+    #[test]
+    pub fn increment_y_wraps_after_row_29() {
+        let mut address = VramAddress::new(7, 0, 29, 3);
+        address.increment_y();
+        assert_eq!(address, VramAddress::new(0, 2, 0, 3));
+
+        // Rows 30-31 wrap without switching nametable
+        let mut address = VramAddress::new(7, 0, 31, 0);
+        address.increment_y();
+        assert_eq!(address, VramAddress::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    pub fn attribute_address_covers_4x4_tiles() {
+        let address = VramAddress::new(0, 3, 17, 10);
+        assert_eq!(address.tile_address(), 0x2E2A);
+        assert_eq!(address.attribute_address(), 0x2FE2);
+    }
+}
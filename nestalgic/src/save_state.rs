@@ -0,0 +1,158 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum StateError {
+    #[error("Save state ended early, wanted {wanted} more bytes at offset {offset}")]
+    UnexpectedEnd {
+        offset: usize,
+        wanted: usize,
+    },
+
+    #[error("Save state has {actual} bytes of memory where {expected} were expected. Is it from another ROM?")]
+    SizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// Encodes emulator state as bytes for save states.
+///
+/// Values are written in order with no field names or padding, so `StateReader` must read them back
+/// in exactly the same order. Integers are little endian and blocks of memory are prefixed with
+/// their length as a `u32`.
+#[derive(Debug, Default)]
+pub struct StateWriter {
+    bytes: Vec<u8>,
+}
+
+impl StateWriter {
+    pub fn new() -> StateWriter {
+        StateWriter::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_memory(&mut self, memory: &[u8]) {
+        self.write_u32(memory.len() as u32);
+        self.bytes.extend_from_slice(memory);
+    }
+}
+
+/// Decodes state written by `StateWriter`.
+#[derive(Debug)]
+pub struct StateReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> StateReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> StateReader<'a> {
+        StateReader { bytes, offset: 0 }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, StateError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, StateError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, StateError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, StateError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("take returns exactly 8 bytes")))
+    }
+
+    /// Read a block of memory into `memory`, which must be the same size as the saved block.
+    pub fn read_memory(&mut self, memory: &mut [u8]) -> Result<(), StateError> {
+        let actual = self.read_u32()? as usize;
+        if actual != memory.len() {
+            return Err(StateError::SizeMismatch { expected: memory.len(), actual });
+        }
+
+        memory.copy_from_slice(self.take(actual)?);
+        Ok(())
+    }
+
+    fn take(&mut self, wanted: usize) -> Result<&'a [u8], StateError> {
+        let bytes = self.bytes
+            .get(self.offset..self.offset + wanted)
+            .ok_or(StateError::UnexpectedEnd { offset: self.offset, wanted })?;
+
+        self.offset += wanted;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn reads_back_what_was_written() {
+        let mut writer = StateWriter::new();
+        writer.write_u8(0x12);
+        writer.write_bool(true);
+        writer.write_u16(0x3456);
+        writer.write_u32(0x789A_BCDE);
+        writer.write_u64(0x0123_4567_89AB_CDEF);
+        writer.write_memory(&[1, 2, 3]);
+        let bytes = writer.into_bytes();
+
+        let mut reader = StateReader::new(&bytes);
+        let mut memory = [0; 3];
+        assert_eq!(reader.read_u8(), Ok(0x12));
+        assert_eq!(reader.read_bool(), Ok(true));
+        assert_eq!(reader.read_u16(), Ok(0x3456));
+        assert_eq!(reader.read_u32(), Ok(0x789A_BCDE));
+        assert_eq!(reader.read_u64(), Ok(0x0123_4567_89AB_CDEF));
+        assert_eq!(reader.read_memory(&mut memory), Ok(()));
+        assert_eq!(memory, [1, 2, 3]);
+    }
+
+    #[test]
+    pub fn rejects_truncated_and_mismatched_state() {
+        let mut reader = StateReader::new(&[0x01]);
+        assert_eq!(reader.read_u16(), Err(StateError::UnexpectedEnd { offset: 0, wanted: 2 }));
+
+        let mut writer = StateWriter::new();
+        writer.write_memory(&[0; 4]);
+        let bytes = writer.into_bytes();
+
+        let mut memory = [0; 8];
+        let mut reader = StateReader::new(&bytes);
+        assert_eq!(reader.read_memory(&mut memory), Err(StateError::SizeMismatch { expected: 8, actual: 4 }));
+    }
+}
@@ -0,0 +1,55 @@
+use std::hash::Hasher;
+
+/// A 64-bit FNV-1a hasher used to fingerprint emulator state.
+///
+/// We can't use `DefaultHasher` because its algorithm isn't guaranteed to stay the same between
+/// Rust releases, and state hashes are stored in movies that need to be verified much later.
+///
+/// See also: http://www.isthe.com/chongo/tech/comp/fnv/
+pub struct StateHasher(u64);
+
+impl StateHasher {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    pub fn new() -> StateHasher {
+        StateHasher(StateHasher::OFFSET_BASIS)
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        StateHasher::new()
+    }
+}
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(StateHasher::PRIME);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn matches_fnv1a_reference_values() {
+        let mut hasher = StateHasher::new();
+        assert_eq!(hasher.finish(), 0xCBF2_9CE4_8422_2325);
+
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xAF63_DC4C_8601_EC8C);
+
+        let mut hasher = StateHasher::new();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x8594_4171_F739_67E8);
+    }
+}
@@ -0,0 +1,68 @@
+/// A CPU access to the APU and I/O test registers at `0x4018`-`0x401F`.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct TestRegisterAccess {
+    pub address: u16,
+    pub write: bool,
+
+    /// The address of the first instruction to make this access
+    pub pc: u16,
+
+    /// How many times this access has been made
+    pub count: u64,
+}
+
+/// Records accesses to the test registers at `0x4018`-`0x401F`.
+///
+/// These registers only respond when the CPU is in test mode, which is disabled on retail
+/// consoles, so reads are open bus and writes do nothing. A few test ROMs and odd games poke them
+/// anyway. That's usually harmless but it can also be a sign the CPU has gone off the rails, so we
+/// keep track of who touched them.
+///
+/// Each address is recorded once for reads and once for writes so the log can't grow without
+/// bound.
+///
+/// See also: https://wiki.nesdev.com/w/index.php/CPU_Test_Mode
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct TestRegisterLog {
+    accesses: Vec<TestRegisterAccess>,
+}
+
+impl TestRegisterLog {
+    pub fn new() -> TestRegisterLog {
+        TestRegisterLog::default()
+    }
+
+    pub fn record(&mut self, pc: u16, address: u16, write: bool) {
+        let existing = self.accesses
+            .iter_mut()
+            .find(|access| access.address == address && access.write == write);
+
+        match existing {
+            Some(access) => access.count += 1,
+            None => self.accesses.push(TestRegisterAccess { address, write, pc, count: 1 }),
+        }
+    }
+
+    /// Every kind of access seen so far, in the order they first happened
+    pub fn accesses(&self) -> &[TestRegisterAccess] {
+        &self.accesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn repeated_accesses_are_counted_once() {
+        let mut log = TestRegisterLog::new();
+        log.record(0x8000, 0x401A, false);
+        log.record(0x8010, 0x401A, false);
+        log.record(0x8020, 0x401A, true);
+
+        assert_eq!(log.accesses(), [
+            TestRegisterAccess { address: 0x401A, write: false, pc: 0x8000, count: 2 },
+            TestRegisterAccess { address: 0x401A, write: true, pc: 0x8020, count: 1 },
+        ]);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+use crate::cartridge::{MapperInfo, SUPPORTED_MAPPERS};
+
+/// What this build of Nestalgic supports, for About windows and bug reports.
+#[derive(Clone, Copy)]
+pub struct VersionInfo {
+    pub version: &'static str,
+
+    pub mappers: &'static [MapperInfo],
+}
+
+/// The version and supported mappers of this build.
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        mappers: SUPPORTED_MAPPERS,
+    }
+}
+
+impl fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Nestalgic {}", self.version)?;
+        write!(f, "Supported mappers:")?;
+        for mapper in self.mappers {
+            write!(f, "\n  {:3} {} ({})", mapper.number, mapper.name, mapper.submappers_label())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn lists_every_supported_mapper() {
+        let text = version_info().to_string();
+
+        assert!(text.starts_with(&format!("Nestalgic {}\n", env!("CARGO_PKG_VERSION"))));
+        assert!(text.contains("\n    0 NROM (all submappers)"));
+        assert!(text.contains("\n  206 Namco 108 (all submappers)"));
+        assert_eq!(text.lines().count(), 2 + SUPPORTED_MAPPERS.len());
+    }
+}
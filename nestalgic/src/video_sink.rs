@@ -0,0 +1,15 @@
+use crate::rp2c02::{Pixel, RP2C02};
+
+/// A complete frame of output from the PPU, in row order.
+pub type FrameBuffer = [Pixel; RP2C02::SCREEN_PIXELS];
+
+/// A `VideoSink` receives every frame the console finishes, e.g. to display, record or stream it.
+///
+/// Sinks are registered with `Nestalgic::add_video_sink` and are given each frame in the order
+/// they were added, as soon as the PPU moves on to the next frame. This means every consumer sees
+/// the same frames no matter how often the front end polls `Nestalgic::pixels`.
+///
+/// Sinks must be `Send` so that a `Nestalgic` can be moved to another thread.
+pub trait VideoSink: Send {
+    fn submit_frame(&mut self, frame: &FrameBuffer);
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::rp2c02::RP2C02;
+
+/// Why `VramWriteChecker` flagged a write.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum VramWriteProblem {
+    /// The write landed outside every range in `VramWriteChecker::expected_ranges`
+    UnexpectedAddress,
+
+    /// The write happened while the PPU was rendering. The PPU is using the VRAM address to fetch
+    /// tiles at the same time, so the write lands somewhere unpredictable and disturbs the scroll.
+    DuringRendering,
+}
+
+/// A suspicious write to `PPUDATA` (`0x2007`).
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct VramWriteDiagnostic {
+    pub problem: VramWriteProblem,
+
+    /// The address of the instruction that wrote to `PPUDATA`
+    pub pc: u16,
+
+    pub frame: u64,
+    pub scanline: u16,
+    pub dot: usize,
+
+    /// The PPU address that was written to
+    pub address: u16,
+    pub data: u8,
+}
+
+impl fmt::Display for VramWriteDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problem = match self.problem {
+            VramWriteProblem::UnexpectedAddress => "outside the expected ranges",
+            VramWriteProblem::DuringRendering => "while rendering",
+        };
+
+        write!(
+            f,
+            "PC {:04X}: wrote {:02X} to {:04X} {} (frame {}, scanline {}, dot {})",
+            self.pc, self.data, self.address, problem, self.frame, self.scanline, self.dot
+        )
+    }
+}
+
+/// `VramWriteChecker` watches writes through `PPUDATA` for two signatures of a game or emulator bug:
+///
+/// - Writes outside the nametables and palettes. Add `0x0000-0x1FFF` to `expected_ranges` for
+///   cartridges with CHR RAM, where pattern table writes are normal.
+/// - Writes while the PPU is rendering instead of during vblank or with rendering disabled.
+///
+/// These usually mean a mapper is mirroring nametables wrong or the PPU's timing is off, so a
+/// checker is useful when bringing up a new mapper. Register one with
+/// `Nestalgic::set_vram_write_checker`.
+pub struct VramWriteChecker {
+    pub expected_ranges: Vec<RangeInclusive<u16>>,
+
+    /// Every flagged write, oldest first
+    pub diagnostics: Vec<VramWriteDiagnostic>,
+}
+
+impl VramWriteChecker {
+    /// A checker that expects writes to the nametables (`0x2000-0x2FFF`) and palettes
+    /// (`0x3F00-0x3F1F`).
+    pub fn new() -> VramWriteChecker {
+        VramWriteChecker {
+            expected_ranges: vec![0x2000..=0x2FFF, 0x3F00..=0x3F1F],
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn with_expected_range(mut self, range: RangeInclusive<u16>) -> VramWriteChecker {
+        self.expected_ranges.push(range);
+        self
+    }
+
+    /// Check a write of `data` to `PPUDATA` by the instruction at `pc`, before `ppu` handles it.
+    pub(crate) fn check(&mut self, pc: u16, ppu: &RP2C02, data: u8) {
+        let address = ppu.vram_address.address();
+
+        let mut problems = Vec::new();
+        if !self.expected_ranges.iter().any(|range| range.contains(&address)) {
+            problems.push(VramWriteProblem::UnexpectedAddress);
+        }
+        if ppu.rendering() {
+            problems.push(VramWriteProblem::DuringRendering);
+        }
+
+        for problem in problems {
+            self.diagnostics.push(VramWriteDiagnostic {
+                problem,
+                pc,
+                frame: ppu.frame,
+                scanline: ppu.scanline,
+                dot: ppu.cycles,
+                address,
+                data,
+            });
+        }
+    }
+}
+
+impl Default for VramWriteChecker {
+    fn default() -> Self {
+        VramWriteChecker::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rp2c02::VramAddress;
+
+    #[test]
+    pub fn flags_writes_outside_the_expected_ranges() {
+        let mut checker = VramWriteChecker::new();
+        let mut ppu = RP2C02::new();
+
+        ppu.vram_address = VramAddress(0x23C0);
+        checker.check(0x8000, &ppu, 0x55);
+        ppu.vram_address = VramAddress(0x3F1F);
+        checker.check(0x8000, &ppu, 0x0F);
+        assert!(checker.diagnostics.is_empty());
+
+        ppu.vram_address = VramAddress(0x0100);
+        checker.check(0x8010, &ppu, 0xFF);
+        assert_eq!(checker.diagnostics[0].problem, VramWriteProblem::UnexpectedAddress);
+        assert_eq!(checker.diagnostics[0].to_string(), "PC 8010: wrote FF to 0100 outside the expected ranges (frame 0, scanline 0, dot 0)");
+
+        let mut checker = VramWriteChecker::new().with_expected_range(0x0000..=0x1FFF);
+        checker.check(0x8010, &ppu, 0xFF);
+        assert!(checker.diagnostics.is_empty());
+    }
+
+    #[test]
+    pub fn flags_writes_while_rendering() {
+        let mut checker = VramWriteChecker::new();
+        let mut ppu = RP2C02::new();
+        ppu.vram_address = VramAddress(0x2000);
+        ppu.ppumask.show_background = true;
+        ppu.scanline = 100;
+
+        checker.check(0x8000, &ppu, 0x01);
+
+        ppu.scanline = 241;
+        checker.check(0x8000, &ppu, 0x01);
+
+        assert_eq!(checker.diagnostics.len(), 1);
+        assert_eq!(checker.diagnostics[0].problem, VramWriteProblem::DuringRendering);
+        assert_eq!(checker.diagnostics[0].scanline, 100);
+    }
+}
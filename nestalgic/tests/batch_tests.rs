@@ -0,0 +1,47 @@
+use nestalgic::batch::BatchRunner;
+use nestalgic::{Button, Buttons, Nestalgic, Player};
+
+mod common;
+use common::nestest;
+
+fn inputs(seed: usize) -> Vec<[Buttons; 2]> {
+    (0..10)
+        .map(|frame| {
+            let player_one = Buttons::default()
+                .with(Button::Start, (frame + seed) % 3 == 1)
+                .with(Button::Down, (frame * seed) % 4 == 1);
+            [player_one, Buttons::default()]
+        })
+        .collect()
+}
+
+#[test]
+fn nestalgic_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Nestalgic>();
+}
+
+#[test]
+fn batched_instances_match_serial_runs() {
+    let mut runner = BatchRunner::new().with_threads(3);
+    for seed in 0..5 {
+        let index = runner.push(nestest());
+        runner.instance_mut(index).inputs.extend(inputs(seed));
+    }
+
+    runner.run_frames(10);
+
+    for seed in 0..5 {
+        let mut serial = nestest();
+        for [player_one, _] in inputs(seed) {
+            serial.controller_mut(Player::One).buttons = player_one;
+            serial.run_frame();
+        }
+
+        let instance = runner.instance(seed);
+        assert_eq!(instance.outputs.len(), 10);
+        assert!(instance.inputs.is_empty());
+        assert_eq!(instance.outputs.back().unwrap().frame, 10);
+        assert_eq!(instance.outputs.back().unwrap().state_hash, serial.state_hash());
+    }
+}
@@ -0,0 +1,67 @@
+use std::hash::Hasher;
+
+use nestalgic::{NESROM, Nestalgic, StateHasher};
+
+/// Boot each ROM in `fixtures/boot_hashes.txt` and check the screen matches after the given number
+/// of frames. This catches CPU and PPU changes that break basic rendering.
+///
+/// The hash also covers the nametables, so a boot going wrong is noticed even before the ROM turns
+/// rendering on.
+///
+/// If a change is expected to alter the output, regenerate the hashes with:
+///
+/// ```text
+/// NESTALGIC_REGENERATE_HASHES=1 cargo test -p nestalgic --test boot_hash_tests
+/// ```
+#[test]
+fn boot_hashes_match() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let path = format!("{}/boot_hashes.txt", fixtures);
+    let table = std::fs::read_to_string(&path).expect("Failed to read boot_hashes.txt");
+
+    let mut regenerated = String::new();
+    let mut mismatches = Vec::new();
+    for line in table.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        let (rom, frames, expected) = match fields.as_slice() {
+            &[rom, frames, hash] if !rom.starts_with('#') => (rom, frames, hash),
+            _ => {
+                regenerated.push_str(line);
+                regenerated.push('\n');
+                continue;
+            }
+        };
+
+        let frames = frames.parse::<u64>().expect("frames must be a number");
+        let actual = format!("{:016x}", boot_hash(&format!("{}/{}", fixtures, rom), frames));
+        if actual != expected {
+            mismatches.push(format!("{} after {} frames: expected {}, got {}", rom, frames, expected, actual));
+        }
+
+        regenerated.push_str(&format!("{:<28}{:<8}{}\n", rom, frames, actual));
+    }
+
+    if std::env::var_os("NESTALGIC_REGENERATE_HASHES").is_some() {
+        std::fs::write(&path, regenerated).expect("Failed to write boot_hashes.txt");
+        return;
+    }
+
+    assert!(mismatches.is_empty(), "boot hashes changed:\n{}", mismatches.join("\n"));
+}
+
+fn boot_hash(rom_path: &str, frames: u64) -> u64 {
+    let rom_file = std::fs::read(rom_path).expect("Failed to read ROM");
+    let rom = NESROM::from_bytes(rom_file).expect("Failed to load ROM");
+    let mut nestalgic = Nestalgic::new(rom).expect("Failed to load cartridge");
+    for _ in 0..frames {
+        nestalgic.run_frame();
+    }
+
+    let mut frame = vec![0; Nestalgic::SCREEN_PIXELS * 4];
+    nestalgic.render_into(&mut frame);
+
+    let mut hasher = StateHasher::new();
+    hasher.write(&frame);
+    hasher.write(&nestalgic.nametables().to_rgba());
+    hasher.finish()
+}
@@ -0,0 +1,72 @@
+use std::sync::{Arc, Mutex};
+
+use nestalgic::BusInterceptor;
+
+mod common;
+use common::nestest;
+
+/// Records every write the CPU makes.
+struct WriteLogger {
+    writes: Arc<Mutex<Vec<(u16, u8)>>>,
+}
+
+impl BusInterceptor for WriteLogger {
+    fn after_write(&mut self, address: u16, data: u8) {
+        self.writes.lock().unwrap().push((address, data));
+    }
+}
+
+/// Forces NMI on whenever PPUCTRL is written.
+struct ForceNmi;
+
+impl BusInterceptor for ForceNmi {
+    fn before_write(&mut self, address: u16, data: u8) -> Option<u8> {
+        if address == 0x2000 { Some(data | 0b1000_0000) } else { Some(data) }
+    }
+}
+
+#[test]
+fn interceptors_observe_writes() {
+    let writes = Arc::new(Mutex::new(Vec::new()));
+    let mut nestalgic = nestest();
+    nestalgic.add_bus_interceptor(Box::new(WriteLogger { writes: writes.clone() }));
+
+    nestalgic.run_frame();
+    nestalgic.run_frame();
+
+    // nestest starts by turning off rendering
+    let writes = writes.lock().unwrap();
+    assert_eq!(writes[0..2], [(0x2000, 0x00), (0x2001, 0x00)]);
+}
+
+#[test]
+fn interceptors_can_modify_writes() {
+    let mut nestalgic = nestest();
+    nestalgic.add_bus_interceptor(Box::new(ForceNmi));
+
+    nestalgic.run_frame();
+    nestalgic.run_frame();
+
+    assert_eq!(nestalgic.ppu.ppuctrl.0, 0b1000_0000);
+}
+
+#[test]
+fn interceptors_can_replace_reads() {
+    struct ResetVector;
+
+    impl BusInterceptor for ResetVector {
+        fn before_read(&mut self, address: u16) -> Option<u8> {
+            match address {
+                0xFFFC => Some(0x34),
+                0xFFFD => Some(0x12),
+                _ => None,
+            }
+        }
+    }
+
+    let mut nestalgic = nestest();
+    nestalgic.add_bus_interceptor(Box::new(ResetVector));
+    nestalgic.reset();
+
+    assert_eq!(nestalgic.cpu.pc, 0x1234);
+}
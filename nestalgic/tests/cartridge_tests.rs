@@ -0,0 +1,57 @@
+use std::any::Any;
+
+use nestalgic::{CartridgeError, ExpansionDevice, NESROM, Nestalgic};
+
+mod common;
+use common::{nestest, nestest_bytes};
+
+#[test]
+fn unsupported_mapper_is_an_error() {
+    let mut rom_file = nestest_bytes();
+    rom_file[7] = 0x50; // Upper nibble of the mapper number
+    let rom = NESROM::from_bytes(rom_file).expect("Failed to load ROM");
+
+    let error = Nestalgic::new(rom).err().expect("mapper 80 shouldn't be supported");
+
+    assert_eq!(error, CartridgeError::UnsupportedMapper { mapper_number: 80 });
+    assert_eq!(error.to_string(), "Unsupported mapper 80");
+}
+
+#[test]
+fn truncated_prg_rom_is_an_error() {
+    let mut rom_file = nestest_bytes();
+    rom_file[6] = 0x10; // Lower nibble of the mapper number, MMC1
+    rom_file.truncate(16 + 8 * 1024); // The header promises 16kb of PRG ROM
+    let rom = NESROM::from_bytes(rom_file).expect("Failed to load ROM");
+
+    let error = Nestalgic::new(rom).err().expect("half a PRG bank shouldn't load");
+
+    assert_eq!(error, CartridgeError::InvalidPrgRomSize { bytes: 8 * 1024 });
+}
+
+struct IrqDevice;
+
+impl ExpansionDevice for IrqDevice {
+    fn write(&mut self, _data: u8) {}
+
+    fn irq_pending(&mut self) -> bool {
+        true
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[test]
+fn expansion_port_irq_is_combined_with_the_cartridge() {
+    let mut nestalgic = nestest();
+
+    nestalgic.cycle();
+    assert!(!nestalgic.cpu.irq);
+
+    // NROM never raises an IRQ, so it mustn't clear the one from the expansion port
+    nestalgic.set_expansion_device(Some(Box::new(IrqDevice)));
+    nestalgic.cycle();
+    assert!(nestalgic.cpu.irq);
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the integration tests. Each test file is its own crate and only uses some of
+//! these, so unused ones aren't warned about.
+#![allow(dead_code)]
+
+use nestalgic::{NESROM, Nestalgic};
+
+/// The raw iNES file of nestest, for tests that need to corrupt the header.
+pub fn nestest_bytes() -> Vec<u8> {
+    include_bytes!("../fixtures/nestest.nes").to_vec()
+}
+
+pub fn nestest_rom() -> NESROM {
+    NESROM::from_bytes(nestest_bytes()).expect("Failed to load ROM")
+}
+
+pub fn nestest() -> Nestalgic {
+    Nestalgic::new(nestest_rom()).expect("Failed to load cartridge")
+}
@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex};
+
+use nestalgic::TraceLine;
+
+mod common;
+use common::nestest;
+
+/// nestest's automated run in Nintendulator's format, from the first instruction at `$C000` to
+/// the `RTS` that ends the run. It covers every official and unofficial opcode nestest tests, in
+/// every addressing mode.
+///
+/// See also: https://www.qmtpro.com/~nes/misc/nestest.log
+const NESTEST_LOG: &str = include_str!("./fixtures/nestest.log");
+
+fn traced_nestest(cycles: u64) -> Vec<TraceLine> {
+    let mut nestalgic = nestest();
+
+    // Start nestest's automated mode, which is what the golden log was recorded with
+    nestalgic.cpu.pc = 0xC000;
+
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let traced = lines.clone();
+    nestalgic.set_cpu_tracer(Some(Box::new(move |line: &TraceLine| traced.lock().unwrap().push(line.clone()))));
+
+    for _ in 0..cycles {
+        nestalgic.cycle();
+    }
+
+    let lines = lines.lock().unwrap().clone();
+    lines
+}
+
+#[test]
+fn trace_matches_nestest_log() {
+    let expected = NESTEST_LOG.lines().collect::<Vec<&str>>();
+    let lines = traced_nestest(26_560);
+    assert!(lines.len() >= expected.len(), "only traced {} of {} lines", lines.len(), expected.len());
+
+    for (number, (line, expected)) in lines.iter().zip(expected).enumerate() {
+        assert_eq!(line.to_string(), expected, "line {} of nestest.log differs", number + 1);
+    }
+}
+
+#[test]
+fn trace_marks_unofficial_opcodes() {
+    let lines = traced_nestest(30_000);
+    let unofficial = lines
+        .iter()
+        .find(|line| !line.official)
+        .expect("nestest should run unofficial opcodes");
+
+    let text = unofficial.to_string();
+    assert_eq!(&text[15..16], "*");
+    assert!(text[16..].starts_with(&unofficial.disassembly));
+}
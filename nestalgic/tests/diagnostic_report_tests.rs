@@ -0,0 +1,17 @@
+use nestalgic::{DiagnosticReport, VramWriteChecker};
+
+mod common;
+use common::nestest;
+
+#[test]
+pub fn diagnostic_report_describes_the_cartridge() {
+    let mut nestalgic = nestest();
+    nestalgic.set_vram_write_checker(Some(VramWriteChecker::new()));
+    nestalgic.run_frame();
+
+    let report = nestalgic.diagnostic_report();
+    assert_eq!(report.mapper, 0);
+    assert_eq!(report.mapper_name, Some("NROM"));
+    assert!(report.settings.contains(&("Frame", "1".to_string())));
+    assert!(report.diagnostics.len() <= DiagnosticReport::MAX_DIAGNOSTICS);
+}
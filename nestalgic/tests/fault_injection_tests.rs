@@ -0,0 +1,44 @@
+use nestalgic::FaultInjector;
+
+mod common;
+use common::nestest;
+
+/// Run `frames` frames with faults injected into cartridge reads. The emulator is allowed to
+/// report an error but must never panic.
+fn run_with_faults(injector: FaultInjector, frames: u32) {
+    let mut nestalgic = nestest();
+    nestalgic.add_bus_interceptor(Box::new(injector));
+
+    for _ in 0..frames {
+        if let Err(error) = nestalgic.try_run_frame() {
+            // The message should be useful enough to diagnose what happened
+            assert!(error.to_string().contains("PC"));
+            return;
+        }
+    }
+}
+
+#[test]
+fn bit_flips_never_panic() {
+    for seed in 0..20 {
+        run_with_faults(FaultInjector::new(seed).with_bit_flip_rate(0.001), 10);
+    }
+}
+
+#[test]
+fn open_bus_never_panics() {
+    for seed in 0..20 {
+        run_with_faults(FaultInjector::new(seed).with_open_bus_rate(0.001), 10);
+    }
+}
+
+#[test]
+fn random_values_anywhere_never_panic() {
+    for seed in 0..20 {
+        let injector = FaultInjector::new(seed)
+            .with_address_range(0x0000..=0xFFFF)
+            .with_random_value_rate(0.01);
+
+        run_with_faults(injector, 10);
+    }
+}
@@ -0,0 +1,149 @@
+"""Build the homebrew boot test ROMs in this directory.
+
+These ROMs were written for Nestalgic and can be redistributed with it. They share one program,
+which uploads some tiles to CHR RAM, fills in the palette, a nametable and a few sprites, then
+turns rendering on and idles. The NROM build has the same tiles in CHR ROM, so the upload is
+ignored and both ROMs should draw the same screen.
+
+Run `python3 homebrew.py` from this directory after changing the program, then regenerate the
+hashes in `boot_hashes.txt`.
+"""
+
+PROGRAM = [
+    0x78,              # C000  SEI
+    0xD8,              # C001  CLD
+    0xA2, 0xFF,        # C002  LDX #$FF
+    0x9A,              # C004  TXS
+    0xE8,              # C005  INX             ; X = 0
+    0x8E, 0x00, 0x20,  # C006  STX $2000       ; No NMI
+    0x8E, 0x01, 0x20,  # C009  STX $2001       ; No rendering
+    0x2C, 0x02, 0x20,  # C00C  BIT $2002       ; Wait out the PPU warm-up
+    0x10, 0xFB,        # C00F  BPL $C00C
+    0x2C, 0x02, 0x20,  # C011  BIT $2002
+    0x10, 0xFB,        # C014  BPL $C011
+
+    0xAD, 0x02, 0x20,  # C016  LDA $2002       ; Copy TILES to $0000 in CHR
+    0xA9, 0x00,        # C019  LDA #$00
+    0x8D, 0x06, 0x20,  # C01B  STA $2006
+    0x8D, 0x06, 0x20,  # C01E  STA $2006
+    0xA2, 0x00,        # C021  LDX #$00
+    0xBD, 0x00, 0xC1,  # C023  LDA TILES,X
+    0x8D, 0x07, 0x20,  # C026  STA $2007
+    0xE8,              # C029  INX
+    0xE0, 0x40,        # C02A  CPX #$40
+    0xD0, 0xF5,        # C02C  BNE $C023
+
+    0xA9, 0x3F,        # C02E  LDA #$3F        ; Copy PALETTES to $3F00
+    0x8D, 0x06, 0x20,  # C030  STA $2006
+    0xA9, 0x00,        # C033  LDA #$00
+    0x8D, 0x06, 0x20,  # C035  STA $2006
+    0xA2, 0x00,        # C038  LDX #$00
+    0xBD, 0x40, 0xC1,  # C03A  LDA PALETTES,X
+    0x8D, 0x07, 0x20,  # C03D  STA $2007
+    0xE8,              # C040  INX
+    0xE0, 0x20,        # C041  CPX #$20
+    0xD0, 0xF5,        # C043  BNE $C03A
+
+    0xA9, 0x20,        # C045  LDA #$20        ; Fill $2000-$23FF with diagonal stripes
+    0x8D, 0x06, 0x20,  # C047  STA $2006
+    0xA9, 0x00,        # C04A  LDA #$00
+    0x8D, 0x06, 0x20,  # C04C  STA $2006
+    0xA0, 0x04,        # C04F  LDY #$04
+    0xA2, 0x00,        # C051  LDX #$00
+    0x8A,              # C053  TXA
+    0x4A,              # C054  LSR A
+    0x4A,              # C055  LSR A
+    0x4A,              # C056  LSR A
+    0x4A,              # C057  LSR A
+    0x4A,              # C058  LSR A           ; A = row within the page
+    0x86, 0x00,        # C059  STX $00
+    0x65, 0x00,        # C05B  ADC $00
+    0x29, 0x03,        # C05D  AND #$03
+    0x8D, 0x07, 0x20,  # C05F  STA $2007
+    0xE8,              # C062  INX
+    0xD0, 0xEE,        # C063  BNE $C053
+    0x88,              # C065  DEY
+    0xD0, 0xEB,        # C066  BNE $C053
+
+    0xA9, 0x00,        # C068  LDA #$00        ; Copy SPRITES to OAM
+    0x8D, 0x03, 0x20,  # C06A  STA $2003
+    0xA2, 0x00,        # C06D  LDX #$00
+    0xBD, 0x60, 0xC1,  # C06F  LDA SPRITES,X
+    0x8D, 0x04, 0x20,  # C072  STA $2004
+    0xE8,              # C075  INX
+    0xE0, 0x10,        # C076  CPX #$10
+    0xD0, 0xF5,        # C078  BNE $C06F
+
+    0xA9, 0x0E,        # C07A  LDA #$0E        ; MMC1: vertical mirroring, fixed $C000, 8KB CHR
+    0x8D, 0x00, 0x80,  # C07C  STA $8000
+    0x4A,              # C07F  LSR A
+    0x8D, 0x00, 0x80,  # C080  STA $8000
+    0x4A,              # C083  LSR A
+    0x8D, 0x00, 0x80,  # C084  STA $8000
+    0x4A,              # C087  LSR A
+    0x8D, 0x00, 0x80,  # C088  STA $8000
+    0x4A,              # C08B  LSR A
+    0x8D, 0x00, 0x80,  # C08C  STA $8000
+
+    0xA9, 0x00,        # C08F  LDA #$00        ; Scroll to 0,0 and start rendering
+    0x8D, 0x05, 0x20,  # C091  STA $2005
+    0x8D, 0x05, 0x20,  # C094  STA $2005
+    0x8D, 0x00, 0x20,  # C097  STA $2000
+    0xA9, 0x1E,        # C09A  LDA #$1E
+    0x8D, 0x01, 0x20,  # C09C  STA $2001
+    0x4C, 0x9F, 0xC0,  # C09F  JMP $C09F
+
+    0x40,              # C0A2  RTI             ; NMI and IRQ
+]
+
+TILES = (
+    [0x00] * 16                                           # Blank
+    + [0xFF] * 8 + [0x00] * 8                             # Solid colour 1
+    + [0x00] * 8 + [0xAA, 0x55] * 4                       # Checkerboard in colour 2
+    + [0xFF, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xFF] * 2  # Box in colour 3
+)
+
+PALETTES = [
+    0x0F, 0x16, 0x2A, 0x12,  0x0F, 0x27, 0x1A, 0x02,  0x0F, 0x30, 0x21, 0x15,  0x0F, 0x19, 0x29, 0x38,
+    0x0F, 0x05, 0x15, 0x25,  0x0F, 0x0A, 0x1A, 0x2A,  0x0F, 0x03, 0x13, 0x23,  0x0F, 0x07, 0x17, 0x27,
+]
+
+# Y, tile, attributes, X
+SPRITES = [
+    0x40, 0x03, 0x00, 0x40,
+    0x60, 0x01, 0x01, 0x80,
+    0x80, 0x02, 0x42, 0xC0,
+    0xA0, 0x03, 0x23, 0x20,
+]
+
+
+def prg_rom():
+    prg = bytearray(0x4000)
+    prg[0x0000:len(PROGRAM)] = bytes(PROGRAM)
+    prg[0x0100:0x0100 + len(TILES)] = bytes(TILES)
+    prg[0x0140:0x0140 + len(PALETTES)] = bytes(PALETTES)
+    prg[0x0160:0x0160 + len(SPRITES)] = bytes(SPRITES)
+
+    # NMI, RESET and IRQ vectors
+    prg[0x3FFA:0x4000] = bytes([0xA2, 0xC0, 0x00, 0xC0, 0xA2, 0xC0])
+    return bytes(prg)
+
+
+def ines(mapper, chr_rom):
+    chr_banks = len(chr_rom) // 0x2000
+    header = bytes([0x4E, 0x45, 0x53, 0x1A, 1, chr_banks, (mapper & 0x0F) << 4, mapper & 0xF0]) + bytes(8)
+    return header + prg_rom() + chr_rom
+
+
+def main():
+    chr_rom = bytes(TILES) + bytes(0x2000 - len(TILES))
+
+    with open("homebrew_nrom.nes", "wb") as rom:
+        rom.write(ines(0, chr_rom))
+
+    with open("homebrew_mmc1_chr_ram.nes", "wb") as rom:
+        rom.write(ines(1, b""))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,33 @@
+use nestalgic::gym::{Action, Environment};
+use nestalgic::{Button, Buttons, Nestalgic};
+
+mod common;
+use common::nestest_rom;
+
+#[test]
+fn reset_and_step_are_deterministic() {
+    let mut frames = 0;
+    let mut environment = Environment::new(nestest_rom())
+        .expect("Failed to load cartridge")
+        .with_ram_addresses(vec![0x0000, 0x0001, 0x07FF])
+        .with_frames_per_step(2)
+        .with_reward_hook(|nestalgic: &Nestalgic| nestalgic.wram()[0] as f64)
+        .with_done_hook(move |_| { frames += 1; frames >= 3 });
+
+    let action = Action {
+        player_one: Buttons::default().with(Button::Start, true),
+        player_two: Buttons::default(),
+    };
+
+    let first_observation = environment.reset();
+    assert_eq!(first_observation.frame.len(), Nestalgic::SCREEN_PIXELS);
+    assert_eq!(first_observation.ram.len(), 3);
+
+    let steps: Vec<_> = (0..3).map(|_| environment.step(action)).collect();
+    assert_eq!(environment.nestalgic().ppu.frame, 6);
+    assert!(!steps[0].done);
+    assert!(steps[2].done);
+
+    assert_eq!(environment.reset(), first_observation);
+    assert_eq!(environment.step(action).observation, steps[0].observation);
+}
@@ -0,0 +1,16 @@
+mod common;
+use common::nestest;
+
+#[test]
+fn poke_writes_mirrored_ram() {
+    let mut nestalgic = nestest();
+
+    assert!(nestalgic.poke_u8(0x0812, 0xAB));
+    assert_eq!(nestalgic.peek_u8(0x0012), 0xAB);
+    assert_eq!(nestalgic.wram()[0x12], 0xAB);
+
+    // Only RAM can be poked
+    let prg = nestalgic.peek_u8(0xC000);
+    assert!(!nestalgic.poke_u8(0xC000, prg.wrapping_add(1)));
+    assert_eq!(nestalgic.peek_u8(0xC000), prg);
+}
@@ -0,0 +1,87 @@
+use nestalgic::movie::{Movie, MovieError, MoviePlayer, MovieRecorder};
+use nestalgic::{Button, Buttons, Player};
+
+mod common;
+use common::nestest;
+
+#[test]
+fn playback_reproduces_recorded_session() {
+    let mut recorded = nestest();
+    let mut recorder = MovieRecorder::start(&recorded);
+
+    for frame in 0..30 {
+        let controller = recorded.controller_mut(Player::One);
+        controller.buttons = Buttons::default()
+            .with(Button::Down, frame % 7 == 0)
+            .with(Button::Start, frame == 20);
+        controller.turbo_buttons = Buttons::default().with(Button::A, frame > 10);
+
+        recorder.run_frame(&mut recorded);
+    }
+
+    let movie = recorder.finish();
+    assert_eq!(movie.frames.len(), 30);
+
+    let mut replayed = nestest();
+    let mut player = MoviePlayer::new(movie, &replayed).expect("Movie should match initial state");
+    while player.run_frame(&mut replayed) {}
+
+    assert!(player.is_finished());
+    assert_eq!(replayed.state_hash(), recorded.state_hash());
+}
+
+#[test]
+fn playback_from_different_state_fails() {
+    let recorded = nestest();
+    let movie = MovieRecorder::start(&recorded).finish();
+
+    let mut other = nestest();
+    other.run_frame();
+
+    let result = MoviePlayer::new(movie, &other).map(|_| ());
+    assert_eq!(
+        result,
+        Err(MovieError::InitialStateMismatch {
+            expected: recorded.state_hash(),
+            actual: other.state_hash(),
+        })
+    );
+}
+
+#[test]
+fn fm2_export_and_import_roundtrip() {
+    let mut recorded = nestest();
+    let mut recorder = MovieRecorder::start(&recorded);
+
+    for frame in 0..10 {
+        recorded.controller_mut(Player::Two).buttons = Buttons::default().with(Button::Up, frame % 2 == 0);
+        recorder.run_frame(&mut recorded);
+    }
+
+    let movie = recorder.finish();
+    let fm2 = movie.to_fm2(&recorded, "nestest");
+
+    let imported = Movie::from_fm2(&fm2, &nestest()).expect("Failed to import FM2");
+    assert_eq!(imported, movie);
+}
+
+#[test]
+fn fm2_export_identifies_the_rom() {
+    let nestalgic = nestest();
+    let fm2 = Movie::new(nestalgic.state_hash()).to_fm2(&nestalgic, "nestest");
+
+    assert!(fm2.contains("\nromFilename nestest\n"), "{}", fm2);
+    assert!(fm2.contains("\nromChecksum base64:9oQylYzYDnjzZPhydnmhcA==\n"), "{}", fm2);
+
+    let guid = fm2.lines().find_map(|line| line.strip_prefix("guid ")).expect("missing guid");
+    let groups = guid.split('-').map(str::len).collect::<Vec<_>>();
+    assert_eq!(groups, [8, 4, 4, 4, 12]);
+}
+
+#[test]
+fn fm2_import_rejects_pal_movies() {
+    let fm2 = "version 3\npalFlag 1\nport0 1\nport1 0\n|0|........|||\n";
+
+    let result = Movie::from_fm2(fm2, &nestest());
+    assert_eq!(result, Err(MovieError::UnsupportedFm2Feature("PAL timing".to_string())));
+}
@@ -0,0 +1,70 @@
+use nestalgic::{Nestalgic, PPURevision, Region, Timing};
+
+mod common;
+
+fn nestest(region: Region) -> Nestalgic {
+    common::nestest().with_region(region)
+}
+
+/// The average number of CPU cycles per frame over `frames` frames.
+fn cpu_cycles_per_frame(nestalgic: &mut Nestalgic, frames: u64) -> f64 {
+    nestalgic.run_frame();
+    let start = nestalgic.cpu.elapsed_cycles;
+    for _ in 0..frames {
+        nestalgic.run_frame();
+    }
+
+    (nestalgic.cpu.elapsed_cycles - start) as f64 / frames as f64
+}
+
+#[test]
+fn ntsc_frame_takes_29780_cpu_cycles() {
+    let cycles = cpu_cycles_per_frame(&mut nestest(Region::Ntsc), 30);
+    assert!((cycles - 341.0 * 262.0 / 3.0).abs() < 1.0, "cycles was {}", cycles);
+}
+
+#[test]
+fn pal_frame_runs_3_2_ppu_cycles_per_cpu_cycle() {
+    let cycles = cpu_cycles_per_frame(&mut nestest(Region::Pal), 30);
+    assert!((cycles - 341.0 * 312.0 / 3.2).abs() < 1.0, "cycles was {}", cycles);
+}
+
+#[test]
+fn dendy_frame_takes_35464_cpu_cycles() {
+    let cycles = cpu_cycles_per_frame(&mut nestest(Region::Dendy), 30);
+    assert!((cycles - 341.0 * 312.0 / 3.0).abs() < 1.0, "cycles was {}", cycles);
+}
+
+#[test]
+fn dendy_vblank_starts_at_scanline_291() {
+    let mut nestalgic = nestest(Region::Dendy);
+    while !nestalgic.ppu.ppustatus.in_vblank {
+        nestalgic.cycle();
+    }
+
+    assert_eq!(nestalgic.ppu.scanline, 291);
+}
+
+#[test]
+fn custom_timing_supports_arbitrary_ratios() {
+    let timing = Timing {
+        master_clock_hz: 24_000_000,
+        cpu_clock_divider: 11,
+        ppu_clock_divider: 4,
+        scanlines_per_frame: 262,
+        vblank_scanline: 241,
+        skip_odd_frame_dot: true,
+    };
+
+    let mut nestalgic = nestest(Region::Ntsc).with_timing(timing);
+    let cycles = cpu_cycles_per_frame(&mut nestalgic, 30);
+    assert!((cycles - 341.0 * 262.0 * 4.0 / 11.0).abs() < 1.0, "cycles was {}", cycles);
+}
+
+#[test]
+fn region_selects_ppu_revision_unless_overridden() {
+    assert_eq!(nestest(Region::Pal).ppu.revision, PPURevision::RP2C07);
+
+    let vs_system = nestest(Region::Ntsc).with_ppu_revision(PPURevision::RP2C03);
+    assert_eq!(vs_system.ppu.revision, PPURevision::RP2C03);
+}
@@ -0,0 +1,47 @@
+use nestalgic::Error;
+
+mod common;
+use common::nestest;
+
+#[test]
+fn run_to_frame_stops_at_the_start_of_the_frame() {
+    let mut nestalgic = nestest();
+    nestalgic.run_to_frame(5);
+
+    assert_eq!(nestalgic.ppu.frame, 5);
+    assert_eq!(nestalgic.ppu.scanline, 0);
+    assert!(nestalgic.ppu.cycles < 3, "cycles was {}", nestalgic.ppu.cycles);
+}
+
+#[test]
+fn run_to_frame_matches_run_frame() {
+    let mut run_to = nestest();
+    run_to.run_to_frame(10);
+
+    let mut run_frame = nestest();
+    for _ in 0..10 {
+        run_frame.run_frame();
+    }
+
+    assert_eq!(run_to.state_hash(), run_frame.state_hash());
+}
+
+#[test]
+fn run_to_scanline_stops_on_the_next_frame() {
+    let mut nestalgic = nestest();
+    nestalgic.run_to_frame(2);
+    nestalgic.run_to_scanline(100);
+
+    assert_eq!(nestalgic.ppu.frame, 3);
+    assert_eq!(nestalgic.ppu.scanline, 100);
+    assert!(nestalgic.ppu.cycles < 3, "cycles was {}", nestalgic.ppu.cycles);
+}
+
+#[test]
+fn run_to_scanline_rejects_scanlines_past_the_frame() {
+    let mut nestalgic = nestest();
+    let result = nestalgic.try_run_to_scanline(262);
+
+    assert!(matches!(result, Err(Error::ScanlineOutOfRange { scanline: 262, scanlines_per_frame: 262 })));
+    assert_eq!(nestalgic.ppu.frame, 0);
+}
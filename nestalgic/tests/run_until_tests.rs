@@ -0,0 +1,76 @@
+use nestalgic::{Condition, PpuAccessKind, PpuWatchpoint, StopReason};
+
+mod common;
+use common::nestest;
+
+#[test]
+fn run_until_program_counter_stops_before_the_instruction() {
+    // Find an instruction nestest reaches after its reset sequence
+    let mut reference = nestest();
+    for _ in 0..100 {
+        reference.cycle();
+    }
+    while !reference.cpu.at_instruction_boundary() {
+        reference.cycle();
+    }
+    let pc = reference.cpu.pc;
+
+    let mut nestalgic = nestest();
+    let reason = nestalgic.run_until(Condition::ProgramCounter(pc), 1_000);
+
+    assert_eq!(reason, StopReason::Condition);
+    assert_eq!(nestalgic.cpu.pc, pc);
+    assert!(nestalgic.cpu.at_instruction_boundary());
+}
+
+#[test]
+fn run_until_memory_equals_stops_on_the_write() {
+    let mut reference = nestest();
+    reference.run_to_frame(10);
+    let (address, value) = reference.wram()
+        .iter()
+        .enumerate()
+        .find(|(_, value)| **value != 0)
+        .map(|(address, value)| (address as u16, *value))
+        .expect("nestest should write to RAM in its first 10 frames");
+
+    let mut nestalgic = nestest();
+    let reason = nestalgic.run_until(Condition::MemoryEquals { address, value }, 100_000);
+
+    assert_eq!(reason, StopReason::Condition);
+    assert_eq!(nestalgic.wram()[address as usize], value);
+}
+
+#[test]
+fn run_until_vblanks_stops_at_the_start_of_vblank() {
+    let mut nestalgic = nestest();
+    let reason = nestalgic.run_until(Condition::Vblanks(2), 100_000);
+
+    assert_eq!(reason, StopReason::Condition);
+    assert_eq!(nestalgic.ppu.frame, 1);
+    assert_eq!(nestalgic.ppu.scanline, nestalgic.ppu.vblank_scanline);
+    assert!(nestalgic.ppu.ppustatus.in_vblank);
+}
+
+#[test]
+fn run_until_stops_at_the_cycle_limit() {
+    let mut nestalgic = nestest();
+    let start = nestalgic.cpu.elapsed_cycles;
+    let reason = nestalgic.run_until(Condition::MemoryEquals { address: 0x2000, value: 1 }, 500);
+
+    assert_eq!(reason, StopReason::CycleLimit);
+    assert_eq!(nestalgic.cpu.elapsed_cycles - start, 500);
+}
+
+#[test]
+fn run_until_ppu_access_stops_on_the_write() {
+    // nestest clears the nametables while its screen is off
+    let mut nestalgic = nestest();
+    let watchpoint = PpuWatchpoint { address: 0x2040, kind: PpuAccessKind::Write };
+    let reason = nestalgic.run_until(Condition::PpuAccess(watchpoint), 1_000_000);
+
+    assert_eq!(reason, StopReason::Condition);
+    let hit = nestalgic.ppu_watch_hit().expect("the write should be recorded");
+    assert_eq!((hit.address, hit.kind), (0x2040, PpuAccessKind::Write));
+    assert_eq!((hit.frame, hit.scanline), (nestalgic.ppu.frame, nestalgic.ppu.scanline));
+}
@@ -0,0 +1,35 @@
+use std::sync::{Arc, Mutex};
+
+mod common;
+use common::nestest;
+
+#[test]
+fn scanline_hook_runs_at_the_start_of_every_scanline() {
+    let scanlines = Arc::new(Mutex::new(Vec::new()));
+    let mut nestalgic = nestest();
+    nestalgic.run_frame();
+
+    let recorded = scanlines.clone();
+    nestalgic.set_scanline_hook(Some(Box::new(move |scanline, ppu| {
+        assert_eq!(ppu.cycles, 0);
+        recorded.lock().unwrap().push(scanline);
+    })));
+    nestalgic.run_frame();
+
+    // `run_frame` stops as soon as the frame wraps, which is the start of scanline 0
+    let scanlines = scanlines.lock().unwrap();
+    assert_eq!(*scanlines, (1..262).chain([0]).collect::<Vec<u16>>());
+}
+
+#[test]
+fn scanline_hook_can_be_removed() {
+    let calls = Arc::new(Mutex::new(0));
+    let mut nestalgic = nestest();
+
+    let recorded = calls.clone();
+    nestalgic.set_scanline_hook(Some(Box::new(move |_, _| *recorded.lock().unwrap() += 1)));
+    nestalgic.set_scanline_hook(None);
+    nestalgic.run_frame();
+
+    assert_eq!(*calls.lock().unwrap(), 0);
+}
@@ -0,0 +1,89 @@
+use std::sync::{Arc, Mutex};
+
+use nestalgic::{Nestalgic, TraceLine};
+
+mod common;
+use common::nestest;
+
+/// Copy `program` into RAM at `$0300` and start running it from there.
+fn run_from_ram(nestalgic: &mut Nestalgic, program: &[u8]) {
+    for (offset, &byte) in program.iter().enumerate() {
+        assert!(nestalgic.poke_u8(0x0300 + offset as u16, byte));
+    }
+    nestalgic.cpu.pc = 0x0300;
+}
+
+#[test]
+fn scroll_writes_land_on_the_last_cycle_of_the_instruction() {
+    let mut nestalgic = nestest().with_ppu_warm_up(false);
+    run_from_ram(&mut nestalgic, &[
+        0xA9, 0x07,       // LDA #$07
+        0x8D, 0x05, 0x20, // STA $2005
+        0x4C, 0x05, 0x03, // JMP $0305
+    ]);
+
+    let started = Arc::new(Mutex::new(false));
+    let traced = started.clone();
+    nestalgic.set_cpu_tracer(Some(Box::new(move |line: &TraceLine| {
+        if line.pc == 0x0302 {
+            *traced.lock().unwrap() = true;
+        }
+    })));
+
+    while !*started.lock().unwrap() {
+        nestalgic.cycle();
+    }
+
+    // The opcode fetch has happened, then come two operand reads and the write itself
+    for _ in 0..2 {
+        nestalgic.cycle();
+        assert_eq!(nestalgic.ppu.fine_x_scroll, 0);
+    }
+    nestalgic.cycle();
+    assert_eq!(nestalgic.ppu.fine_x_scroll, 7);
+}
+
+#[test]
+fn mid_frame_scroll_split_lands_on_the_same_scanline_every_frame() {
+    let mut nestalgic = nestest();
+    run_from_ram(&mut nestalgic, &[
+        0x2C, 0x02, 0x20, // BIT $2002      ; Wait for vblank
+        0x10, 0xFB,       // BPL $0300
+        0xA9, 0x0A,       // LDA #$0A
+        0x8D, 0x01, 0x20, // STA $2001      ; Show the background
+        0xA9, 0x00,       // LDA #$00
+        0x8D, 0x05, 0x20, // STA $2005      ; Scroll to 0,0 for the top of the screen
+        0x8D, 0x05, 0x20, // STA $2005
+        0xA2, 0x0C,       // LDX #$0C       ; Wait until around scanline 120
+        0xA0, 0x00,       // LDY #$00
+        0x88,             // DEY
+        0xD0, 0xFD,       // BNE $0316
+        0xCA,             // DEX
+        0xD0, 0xFA,       // BNE $0316
+        0xA9, 0x80,       // LDA #$80
+        0x8D, 0x05, 0x20, // STA $2005      ; Scroll the rest of the screen 128 pixels across
+        0x4C, 0x00, 0x03, // JMP $0300
+    ]);
+
+    // Let the PPU warm up before we start watching
+    nestalgic.run_to_frame(4);
+
+    let splits = Arc::new(Mutex::new(Vec::new()));
+    let recorded = splits.clone();
+    let mut split_frame = None;
+    nestalgic.set_scanline_hook(Some(Box::new(move |scanline, ppu| {
+        // By dot 0 the PPU has fetched the first two tiles of the scanline, so the coarse X it
+        // copied from the scroll registers at dot 257 has been incremented twice.
+        let split = ppu.vram_address.coarse_x() == 128 / 8 + 2;
+        if split && scanline < 240 && split_frame != Some(ppu.frame) {
+            split_frame = Some(ppu.frame);
+            recorded.lock().unwrap().push(scanline);
+        }
+    })));
+    nestalgic.run_to_frame(10);
+
+    let splits = splits.lock().unwrap();
+    assert_eq!(splits.len(), 6);
+    assert!(splits.iter().all(|&scanline| scanline == splits[0]), "split moved between frames: {:?}", splits);
+    assert!((100..140).contains(&splits[0]), "split landed on scanline {}", splits[0]);
+}
@@ -0,0 +1,30 @@
+use nestalgic::Nestalgic;
+
+mod common;
+use common::nestest;
+
+/// Run `LDX #strobe; STX $4016; LDX #$00` from RAM for a frame, then put the program back the way
+/// the other console has it so only the controller strobe differs.
+fn strobed(strobe: u8) -> Nestalgic {
+    let mut nestalgic = nestest();
+    let program = [
+        0xA2, strobe,     // LDX #strobe
+        0x8E, 0x16, 0x40, // STX $4016
+        0xA2, 0x00,       // LDX #$00
+        0x4C, 0x07, 0x03, // JMP $0307
+    ];
+    for (offset, &byte) in program.iter().enumerate() {
+        assert!(nestalgic.poke_u8(0x0300 + offset as u16, byte));
+    }
+    nestalgic.cpu.pc = 0x0300;
+
+    nestalgic.run_to_frame(1);
+    assert!(nestalgic.poke_u8(0x0301, 0x00));
+    nestalgic
+}
+
+#[test]
+fn state_hash_includes_the_controller_strobe() {
+    assert_eq!(strobed(0).state_hash(), strobed(0).state_hash());
+    assert_ne!(strobed(0).state_hash(), strobed(1).state_hash());
+}
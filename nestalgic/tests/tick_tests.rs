@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use nestalgic::Region;
+
+mod common;
+use common::nestest;
+
+#[test]
+fn tick_drops_time_beyond_the_cycle_budget() {
+    let mut nestalgic = nestest();
+    nestalgic.set_cycle_budget(Some(1_000));
+
+    let start = nestalgic.cpu.elapsed_cycles;
+    nestalgic.tick(Duration::from_secs(1));
+
+    let cycles_per_second = Region::Ntsc.timing().master_clock_hz / Region::Ntsc.timing().cpu_clock_divider as u64;
+    assert_eq!(nestalgic.cpu.elapsed_cycles - start, 1_000);
+    assert!(nestalgic.dropped_cycles() > cycles_per_second - 1_100);
+
+    // Nothing is owed after dropping, so the next tick starts fresh
+    nestalgic.tick(Duration::from_nanos(1));
+    assert_eq!(nestalgic.cpu.elapsed_cycles - start, 1_000);
+}
+
+#[test]
+fn tick_without_a_budget_catches_up() {
+    let mut nestalgic = nestest();
+    nestalgic.set_cycle_budget(None);
+
+    nestalgic.tick(Duration::from_millis(100));
+
+    assert!(nestalgic.cpu.elapsed_cycles > 170_000);
+    assert_eq!(nestalgic.dropped_cycles(), 0);
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use nestalgic::{FrameBuffer, Nestalgic, VideoSink};
+
+mod common;
+use common::nestest;
+
+/// Keeps a copy of every frame it's given.
+struct FrameRecorder {
+    frames: Arc<Mutex<Vec<Box<FrameBuffer>>>>,
+}
+
+impl VideoSink for FrameRecorder {
+    fn submit_frame(&mut self, frame: &FrameBuffer) {
+        self.frames.lock().unwrap().push(Box::new(*frame));
+    }
+}
+
+#[test]
+fn video_sinks_receive_each_completed_frame() {
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let mut nestalgic = nestest();
+    nestalgic.add_video_sink(Box::new(FrameRecorder { frames: frames.clone() }));
+
+    for _ in 0..3 {
+        nestalgic.run_frame();
+    }
+
+    let frames = frames.lock().unwrap();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(&frames[2][..], &nestalgic.pixels()[..]);
+}
+
+#[test]
+fn take_frame_complete_fires_once_per_frame() {
+    let mut nestalgic = nestest();
+    let mut completed = 0;
+    while nestalgic.ppu.frame < 3 {
+        nestalgic.cycle();
+        if nestalgic.take_frame_complete() {
+            completed += 1;
+        }
+    }
+
+    assert_eq!(completed, 3);
+    assert!(!nestalgic.take_frame_complete());
+}
+
+#[test]
+fn render_into_writes_rgba() {
+    let mut nestalgic = nestest();
+    nestalgic.run_frame();
+
+    let mut frame = vec![0; Nestalgic::SCREEN_PIXELS * 4];
+    nestalgic.render_into(&mut frame);
+
+    let expected = nestalgic.pixels().iter().flat_map(|pixel| pixel.into_rgba()).collect::<Vec<u8>>();
+    assert_eq!(frame, expected);
+}
+
+#[test]
+#[should_panic(expected = "frame must be 245760 bytes, got 4")]
+fn render_into_rejects_the_wrong_size_of_frame() {
+    let nestalgic = nestest();
+
+    nestalgic.render_into(&mut [0; 4]);
+}
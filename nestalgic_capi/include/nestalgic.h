@@ -0,0 +1,73 @@
+/*
+ * C API for the Nestalgic NES emulator.
+ *
+ * Link against the nestalgic_capi library built by `cargo build -p nestalgic_capi --release`.
+ * See nestalgic_capi/src/lib.rs for the documentation of each function. Keep this file in sync
+ * with it, which the header_matches_exports test checks, and bump NESTALGIC_API_VERSION for
+ * incompatible changes.
+ */
+#ifndef NESTALGIC_H
+#define NESTALGIC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NESTALGIC_API_VERSION 1
+
+#define NESTALGIC_OK 0
+#define NESTALGIC_ERROR (-1)
+
+#define NESTALGIC_SCREEN_WIDTH 256
+#define NESTALGIC_SCREEN_HEIGHT 240
+
+/* Controller bits for nestalgic_set_buttons */
+#define NESTALGIC_BUTTON_A      (1 << 0)
+#define NESTALGIC_BUTTON_B      (1 << 1)
+#define NESTALGIC_BUTTON_SELECT (1 << 2)
+#define NESTALGIC_BUTTON_START  (1 << 3)
+#define NESTALGIC_BUTTON_UP     (1 << 4)
+#define NESTALGIC_BUTTON_DOWN   (1 << 5)
+#define NESTALGIC_BUTTON_LEFT   (1 << 6)
+#define NESTALGIC_BUTTON_RIGHT  (1 << 7)
+
+typedef struct Nestalgic Nestalgic;
+
+uint32_t nestalgic_api_version(void);
+
+/* Returns NULL if the ROM can't be loaded. The ROM bytes are copied. */
+Nestalgic *nestalgic_new(const uint8_t *rom, size_t rom_len);
+void nestalgic_free(Nestalgic *nestalgic);
+
+int32_t nestalgic_run_frame(Nestalgic *nestalgic);
+
+/* NESTALGIC_SCREEN_WIDTH * NESTALGIC_SCREEN_HEIGHT RGBA pixels, valid until the console next runs */
+const uint8_t *nestalgic_framebuffer(const Nestalgic *nestalgic);
+
+/* One palette index (0x00-0x3F) per pixel, valid until the console next runs */
+const uint8_t *nestalgic_palette_indices(const Nestalgic *nestalgic);
+
+/* player is 0 or 1 */
+int32_t nestalgic_set_buttons(Nestalgic *nestalgic, uint32_t player, uint8_t buttons);
+
+/* There is no full save state yet: the cartridge and PPU states below cover everything except
+ * the CPU and console RAM.
+ *
+ * The save functions return the state size. Nothing is written if buffer_len is too small. */
+
+/* Banking, IRQ counters and cartridge RAM */
+size_t nestalgic_save_cartridge_state(const Nestalgic *nestalgic, uint8_t *buffer, size_t buffer_len);
+int32_t nestalgic_load_cartridge_state(Nestalgic *nestalgic, const uint8_t *state, size_t state_len);
+
+/* The PPU and nametable RAM */
+size_t nestalgic_save_ppu_state(const Nestalgic *nestalgic, uint8_t *buffer, size_t buffer_len);
+int32_t nestalgic_load_ppu_state(Nestalgic *nestalgic, const uint8_t *state, size_t state_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NESTALGIC_H */
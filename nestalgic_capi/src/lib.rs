@@ -0,0 +1,272 @@
+//! A C API for embedding Nestalgic in programs that aren't written in Rust.
+//!
+//! The matching header is `include/nestalgic.h`. Every function takes the `Nestalgic` returned by
+//! `nestalgic_new` and none of them are thread safe, so a console should only be used from one
+//! thread at a time.
+//!
+//! Functions that can fail return `NESTALGIC_OK` or `NESTALGIC_ERROR` rather than panicking, since
+//! unwinding into C is undefined behaviour. Every function that touches a console runs inside
+//! `guard` so an unexpected panic is reported the same way.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+
+use nestalgic::{Buttons, NESROM, Nestalgic, Player, StateError};
+
+/// Bumped whenever a function in `include/nestalgic.h` changes in an incompatible way.
+pub const NESTALGIC_API_VERSION: u32 = 1;
+
+pub const NESTALGIC_OK: i32 = 0;
+pub const NESTALGIC_ERROR: i32 = -1;
+
+/// Run `f`, returning `error` instead if it panics.
+fn guard<T>(error: T, f: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(error)
+}
+
+#[no_mangle]
+pub extern "C" fn nestalgic_api_version() -> u32 {
+    NESTALGIC_API_VERSION
+}
+
+/// Power on a console with the iNES ROM in `rom`. Returns null if the ROM can't be loaded.
+///
+/// # Safety
+///
+/// `rom` must point to `rom_len` readable bytes. The bytes are copied so they can be freed
+/// straight away.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_new(rom: *const u8, rom_len: usize) -> *mut Nestalgic {
+    if rom.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let rom = slice::from_raw_parts(rom, rom_len).to_vec();
+    guard(std::ptr::null_mut(), || {
+        match NESROM::from_bytes(rom).ok().and_then(|rom| Nestalgic::new(rom).ok()) {
+            Some(nestalgic) => Box::into_raw(Box::new(nestalgic)),
+            None => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// # Safety
+///
+/// `nestalgic` must have come from `nestalgic_new` and not already be freed. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_free(nestalgic: *mut Nestalgic) {
+    if !nestalgic.is_null() {
+        guard((), || drop(Box::from_raw(nestalgic)));
+    }
+}
+
+/// Run until the PPU finishes the current frame. Returns `NESTALGIC_ERROR` if the CPU can't
+/// continue, e.g. after an illegal opcode.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_run_frame(nestalgic: *mut Nestalgic) -> i32 {
+    let nestalgic = match nestalgic.as_mut() {
+        Some(nestalgic) => nestalgic,
+        None => return NESTALGIC_ERROR,
+    };
+
+    guard(NESTALGIC_ERROR, || match nestalgic.try_run_frame() {
+        Ok(()) => NESTALGIC_OK,
+        Err(_) => NESTALGIC_ERROR,
+    })
+}
+
+/// The screen as `NESTALGIC_SCREEN_WIDTH * NESTALGIC_SCREEN_HEIGHT` RGBA pixels, one byte per
+/// channel, in row order.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new`. The pointer is only valid until the
+/// console next runs or is freed.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_framebuffer(nestalgic: *const Nestalgic) -> *const u8 {
+    match nestalgic.as_ref() {
+        Some(nestalgic) => guard(std::ptr::null(), || nestalgic.pixels().as_ptr() as *const u8),
+        None => std::ptr::null(),
+    }
+}
+
+/// The screen as `NESTALGIC_SCREEN_WIDTH * NESTALGIC_SCREEN_HEIGHT` palette indices (0x00-0x3F),
+/// one byte per pixel, in row order. See `Nestalgic::palette_indices`.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new`. The pointer is only valid until the
+/// console next runs or is freed.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_palette_indices(nestalgic: *const Nestalgic) -> *const u8 {
+    match nestalgic.as_ref() {
+        Some(nestalgic) => guard(std::ptr::null(), || nestalgic.palette_indices().as_ptr()),
+        None => std::ptr::null(),
+    }
+}
+
+/// Set the buttons held on controller `player` (0 or 1), in the bit order of `Buttons`: A, B,
+/// Select, Start, Up, Down, Left, Right from bit 0 to bit 7.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new`.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_set_buttons(nestalgic: *mut Nestalgic, player: u32, buttons: u8) -> i32 {
+    let player = match player {
+        0 => Player::One,
+        1 => Player::Two,
+        _ => return NESTALGIC_ERROR,
+    };
+
+    match nestalgic.as_mut() {
+        Some(nestalgic) => guard(NESTALGIC_ERROR, || {
+            nestalgic.controller_mut(player).buttons = Buttons(buttons);
+            NESTALGIC_OK
+        }),
+        None => NESTALGIC_ERROR,
+    }
+}
+
+/// Save the cartridge state (banking, IRQ counters and RAM) into `buffer`, see
+/// `Nestalgic::save_cartridge_state`.
+///
+/// This isn't a full save state. Together with `nestalgic_save_ppu_state` it covers everything
+/// except the CPU and console RAM, which the C API can't save yet.
+///
+/// Returns the size of the state, or 0 if it couldn't be saved. If `buffer_len` is smaller than that nothing is written, so
+/// callers can pass a null `buffer` first to find out how much space they need.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new` and `buffer` must point to
+/// `buffer_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_save_cartridge_state(nestalgic: *const Nestalgic, buffer: *mut u8, buffer_len: usize) -> usize {
+    save_state(nestalgic, buffer, buffer_len, Nestalgic::save_cartridge_state)
+}
+
+/// Restore a cartridge state from `nestalgic_save_cartridge_state`.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new` and `state` must point to `state_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_load_cartridge_state(nestalgic: *mut Nestalgic, state: *const u8, state_len: usize) -> i32 {
+    load_state(nestalgic, state, state_len, Nestalgic::load_cartridge_state)
+}
+
+/// Save the PPU state and nametable RAM into `buffer`, see `Nestalgic::save_ppu_state`. Works
+/// like `nestalgic_save_cartridge_state`.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new` and `buffer` must point to
+/// `buffer_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_save_ppu_state(nestalgic: *const Nestalgic, buffer: *mut u8, buffer_len: usize) -> usize {
+    save_state(nestalgic, buffer, buffer_len, Nestalgic::save_ppu_state)
+}
+
+/// Restore a PPU state from `nestalgic_save_ppu_state`.
+///
+/// # Safety
+///
+/// `nestalgic` must be a live console from `nestalgic_new` and `state` must point to `state_len`
+/// readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn nestalgic_load_ppu_state(nestalgic: *mut Nestalgic, state: *const u8, state_len: usize) -> i32 {
+    load_state(nestalgic, state, state_len, Nestalgic::load_ppu_state)
+}
+
+/// Copy the state from `save` into `buffer` if it fits, returning its size.
+unsafe fn save_state(
+    nestalgic: *const Nestalgic,
+    buffer: *mut u8,
+    buffer_len: usize,
+    save: fn(&Nestalgic) -> Vec<u8>
+) -> usize {
+    let state = match nestalgic.as_ref() {
+        Some(nestalgic) => guard(Vec::new(), || save(nestalgic)),
+        None => return 0,
+    };
+
+    if !buffer.is_null() && buffer_len >= state.len() {
+        slice::from_raw_parts_mut(buffer, state.len()).copy_from_slice(&state);
+    }
+
+    state.len()
+}
+
+unsafe fn load_state(
+    nestalgic: *mut Nestalgic,
+    state: *const u8,
+    state_len: usize,
+    load: fn(&mut Nestalgic, &[u8]) -> Result<(), StateError>
+) -> i32 {
+    let nestalgic = match nestalgic.as_mut() {
+        Some(nestalgic) if !state.is_null() => nestalgic,
+        _ => return NESTALGIC_ERROR,
+    };
+
+    let state = slice::from_raw_parts(state, state_len);
+    guard(NESTALGIC_ERROR, || match load(nestalgic, state) {
+        Ok(()) => NESTALGIC_OK,
+        Err(_) => NESTALGIC_ERROR,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn runs_a_frame_through_the_c_api() {
+        let rom = include_bytes!("../../nestalgic/tests/fixtures/nestest.nes");
+
+        unsafe {
+            let nestalgic = nestalgic_new(rom.as_ptr(), rom.len());
+            assert!(!nestalgic.is_null());
+
+            assert_eq!(nestalgic_set_buttons(nestalgic, 0, 0b0000_1000), NESTALGIC_OK);
+            assert_eq!(nestalgic_set_buttons(nestalgic, 2, 0), NESTALGIC_ERROR);
+            assert_eq!(nestalgic_run_frame(nestalgic), NESTALGIC_OK);
+
+            let framebuffer = slice::from_raw_parts(nestalgic_framebuffer(nestalgic), Nestalgic::SCREEN_PIXELS * 4);
+            assert_eq!(framebuffer[3], 255);
+
+            let palette_indices = slice::from_raw_parts(nestalgic_palette_indices(nestalgic), Nestalgic::SCREEN_PIXELS);
+            assert!(palette_indices.iter().all(|&index| index <= 0x3F));
+
+            let state_len = nestalgic_save_cartridge_state(nestalgic, std::ptr::null_mut(), 0);
+            let mut state = vec![0; state_len];
+            assert_eq!(nestalgic_save_cartridge_state(nestalgic, state.as_mut_ptr(), state.len()), state_len);
+            assert_eq!(nestalgic_load_cartridge_state(nestalgic, state.as_ptr(), state.len()), NESTALGIC_OK);
+
+            let state_len = nestalgic_save_ppu_state(nestalgic, std::ptr::null_mut(), 0);
+            let mut state = vec![0; state_len];
+            assert_eq!(nestalgic_save_ppu_state(nestalgic, state.as_mut_ptr(), state.len()), state_len);
+            assert_eq!(nestalgic_load_ppu_state(nestalgic, state.as_ptr(), state.len()), NESTALGIC_OK);
+            assert_eq!(nestalgic_load_ppu_state(nestalgic, state.as_ptr(), 1), NESTALGIC_ERROR);
+
+            nestalgic_free(nestalgic);
+        }
+    }
+
+    #[test]
+    pub fn panics_are_returned_as_errors() {
+        assert_eq!(guard(NESTALGIC_ERROR, || panic!("unexpected")), NESTALGIC_ERROR);
+        assert_eq!(guard(NESTALGIC_ERROR, || NESTALGIC_OK), NESTALGIC_OK);
+    }
+
+    #[test]
+    pub fn invalid_roms_return_null() {
+        let rom = b"not a rom";
+        assert!(unsafe { nestalgic_new(rom.as_ptr(), rom.len()) }.is_null());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeSet;
+
+use nestalgic::{Button, Nestalgic};
+use nestalgic_capi::{NESTALGIC_API_VERSION, NESTALGIC_ERROR, NESTALGIC_OK};
+
+const HEADER: &str = include_str!("../include/nestalgic.h");
+const SOURCE: &str = include_str!("../src/lib.rs");
+
+/// Every `#[no_mangle]` function in `SOURCE` as a C prototype without parameter names, such as
+/// `int32_t nestalgic_run_frame(Nestalgic*)`.
+fn exported_functions() -> BTreeSet<String> {
+    let mut lines = SOURCE.lines();
+    let mut functions = BTreeSet::new();
+
+    while let Some(line) = lines.next() {
+        if line.trim() != "#[no_mangle]" {
+            continue;
+        }
+
+        let signature = lines.next().expect("#[no_mangle] on the last line");
+        let signature = signature.split_once("fn ").expect("#[no_mangle] on something other than a fn").1;
+        let (name, rest) = signature.split_once('(').unwrap();
+        let (parameters, rest) = rest.split_once(')').unwrap();
+        let return_type = rest.trim().trim_end_matches('{').trim().strip_prefix("-> ").unwrap_or("()");
+
+        let parameters = parameters
+            .split(", ")
+            .filter(|parameter| !parameter.is_empty())
+            .map(|parameter| c_type(parameter.split_once(": ").unwrap().1))
+            .collect::<Vec<_>>();
+
+        functions.insert(format!("{} {}({})", c_type(return_type), name, parameters.join(", ")));
+    }
+
+    functions
+}
+
+/// The C type the header should use for the Rust type `rust`.
+fn c_type(rust: &str) -> String {
+    if let Some(pointee) = rust.strip_prefix("*const ") {
+        return format!("const {}*", c_type(pointee));
+    }
+    if let Some(pointee) = rust.strip_prefix("*mut ") {
+        return format!("{}*", c_type(pointee));
+    }
+
+    match rust {
+        "()" => "void",
+        "u8" => "uint8_t",
+        "u32" => "uint32_t",
+        "i32" => "int32_t",
+        "usize" => "size_t",
+        "Nestalgic" => "Nestalgic",
+        _ => panic!("no C type for `{}`", rust),
+    }.to_string()
+}
+
+/// Every function prototype in `HEADER` without parameter names.
+fn declared_functions() -> BTreeSet<String> {
+    HEADER
+        .lines()
+        .filter(|line| line.ends_with(");") && !line.starts_with(' ') && !line.starts_with('#'))
+        .map(|line| {
+            let (declaration, parameters) = line.trim_end_matches(");").split_once('(').unwrap();
+            let (return_type, name) = without_name(declaration);
+
+            let parameters = parameters
+                .split(", ")
+                .filter(|parameter| *parameter != "void")
+                .map(|parameter| without_name(parameter).0)
+                .collect::<Vec<_>>();
+
+            format!("{} {}({})", return_type, name, parameters.join(", "))
+        })
+        .collect()
+}
+
+/// Split a C declaration like `const uint8_t *state` into its type, `const uint8_t*`, and name.
+fn without_name(declaration: &str) -> (String, &str) {
+    let split = declaration.rfind([' ', '*']).unwrap() + 1;
+    let (c_type, name) = declaration.split_at(split);
+    (c_type.replace(" *", "*").trim().to_string(), name)
+}
+
+/// The value of `#define name` in `HEADER`.
+fn define(name: &str) -> i64 {
+    let value = HEADER
+        .lines()
+        .find_map(|line| line.strip_prefix("#define ")?.strip_prefix(name)?.strip_prefix(' '))
+        .unwrap_or_else(|| panic!("{} isn't defined", name))
+        .trim()
+        .trim_matches(['(', ')']);
+
+    match value.split_once(" << ") {
+        Some((value, shift)) => value.parse::<i64>().unwrap() << shift.parse::<i64>().unwrap(),
+        None => value.parse().unwrap(),
+    }
+}
+
+#[test]
+fn header_matches_exports() {
+    assert_eq!(declared_functions(), exported_functions());
+}
+
+#[test]
+fn header_constants_match_the_library() {
+    assert_eq!(define("NESTALGIC_API_VERSION"), NESTALGIC_API_VERSION as i64);
+    assert_eq!(define("NESTALGIC_OK"), NESTALGIC_OK as i64);
+    assert_eq!(define("NESTALGIC_ERROR"), NESTALGIC_ERROR as i64);
+    assert_eq!(define("NESTALGIC_SCREEN_WIDTH"), Nestalgic::SCREEN_WIDTH as i64);
+    assert_eq!(define("NESTALGIC_SCREEN_HEIGHT"), Nestalgic::SCREEN_HEIGHT as i64);
+
+    for button in Button::variants() {
+        let name = format!("NESTALGIC_BUTTON_{:?}", button).to_uppercase();
+        assert_eq!(define(&name), button as i64, "{}", name);
+    }
+}
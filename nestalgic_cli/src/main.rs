@@ -0,0 +1,211 @@
+#![deny(clippy::all)]
+#![forbid(unsafe_code)]
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use nestalgic::{Dat, DumpStatus, NESROM, Nestalgic, PPURevision, Pixel, Texture};
+
+const USAGE: &str = "\
+Usage:
+    nestalgic-cli dump-chr <rom> [--palette <colours>] [--out <dir>]
+    nestalgic-cli dump-nametables <rom> [--frames <n>] [--palette <colours>] [--out <dir>]
+    nestalgic-cli verify <dat> <rom or dir>...
+
+Options:
+    --palette <colours>  Four comma separated NES colours in hex to draw tiles with [default: 0F,00,10,30]
+    --frames <n>         Frames to run before dumping the nametables [default: 60]
+    --out <dir>          Directory to write PNGs to [default: .]";
+
+/// Options shared by every command.
+struct Options {
+    rom: PathBuf,
+    palette: [Pixel; 4],
+    frames: u64,
+    out: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => bail!("missing command\n\n{}", USAGE),
+    };
+
+    if command == "verify" {
+        return verify(rest);
+    }
+
+    let options = parse_options(rest)?;
+    match command {
+        "dump-chr" => dump_chr(&options),
+        "dump-nametables" => dump_nametables(&options),
+        _ => bail!("unknown command `{}`\n\n{}", command, USAGE),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options> {
+    let mut rom = None;
+    let mut palette = parse_palette("0F,00,10,30")?;
+    let mut frames = 60;
+    let mut out = PathBuf::from(".");
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("missing value for `{}`", arg));
+
+        match arg.as_str() {
+            "--palette" => palette = parse_palette(value()?)?,
+            "--frames" => frames = value()?.parse().context("--frames must be a number")?,
+            "--out" => out = PathBuf::from(value()?),
+            _ if rom.is_none() && !arg.starts_with("--") => rom = Some(PathBuf::from(arg)),
+            _ => bail!("unexpected argument `{}`\n\n{}", arg, USAGE),
+        }
+    }
+
+    let rom = rom.ok_or_else(|| anyhow!("missing <rom>\n\n{}", USAGE))?;
+    Ok(Options { rom, palette, frames, out })
+}
+
+/// Parse four NES colours such as `0F,00,10,30` into the pixels the NTSC PPU outputs for them.
+fn parse_palette(text: &str) -> Result<[Pixel; 4]> {
+    let colours = text
+        .split(',')
+        .map(|colour| u8::from_str_radix(colour.trim(), 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .with_context(|| format!("invalid palette `{}`", text))?;
+
+    match colours.as_slice() {
+        &[a, b, c, d] => Ok([a, b, c, d].map(|colour| PPURevision::RP2C02.color(colour))),
+        _ => bail!("palette must have exactly 4 colours, got `{}`", text),
+    }
+}
+
+fn load(options: &Options) -> Result<Nestalgic> {
+    let rom_file = std::fs::read(&options.rom)
+        .with_context(|| format!("could not read {}", options.rom.display()))?;
+    let rom = NESROM::from_bytes(rom_file).context("Failed to load ROM")?;
+
+    Nestalgic::new(rom).context("Failed to load cartridge")
+}
+
+fn dump_chr(options: &Options) -> Result<()> {
+    let nestalgic = load(options)?;
+
+    write_png(&nestalgic.pattern_table(0x0000, options.palette), &options.out.join("chr_left.png"))?;
+    write_png(&nestalgic.pattern_table(0x1000, options.palette), &options.out.join("chr_right.png"))
+}
+
+fn dump_nametables(options: &Options) -> Result<()> {
+    let mut nestalgic = load(options)?;
+    for _ in 0..options.frames {
+        nestalgic.try_run_frame()?;
+    }
+
+    write_png(&nestalgic.nametables_with_palette(options.palette), &options.out.join("nametables.png"))
+}
+
+/// Check ROMs against a No-Intro DAT. Directories are scanned for `.nes` files, so a whole
+/// library can be checked at once.
+fn verify(args: &[String]) -> Result<()> {
+    let (dat_path, paths) = match args.split_first() {
+        Some((dat_path, paths)) if !paths.is_empty() => (dat_path, paths),
+        _ => bail!("verify needs a <dat> and at least one <rom>\n\n{}", USAGE),
+    };
+
+    let dat_file = std::fs::read_to_string(dat_path).with_context(|| format!("could not read {}", dat_path))?;
+    let dat = Dat::from_xml(&dat_file).with_context(|| format!("invalid DAT {}", dat_path))?;
+
+    let mut roms = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(&path)
+                .with_context(|| format!("could not read {}", path.display()))?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<PathBuf>>>()?;
+            entries.retain(|entry| entry.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("nes")));
+            entries.sort();
+            roms.extend(entries);
+        } else {
+            roms.push(path);
+        }
+    }
+
+    for rom in roms {
+        let rom_file = std::fs::read(&rom).with_context(|| format!("could not read {}", rom.display()))?;
+        match dat.verify(&rom_file) {
+            DumpStatus::Good(entry) => println!("good      {} ({})", rom.display(), entry.game),
+            DumpStatus::Overdump(entry) => println!("overdump  {} ({})", rom.display(), entry.game),
+            DumpStatus::BadDump(entry) => println!("bad dump  {} ({})", rom.display(), entry.game),
+            DumpStatus::Unknown => println!("unknown   {}", rom.display()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `texture` as an RGBA PNG.
+fn write_png(texture: &Texture, path: &Path) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("could not create {}", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), texture.width as u32, texture.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder.write_header()?.write_image_data(&texture.to_rgba())?;
+    println!("wrote {}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    pub fn parse_palette_looks_up_ntsc_colours() {
+        let palette = parse_palette("0F, 16,2a,30").unwrap();
+        assert_eq!(palette, [0x0F, 0x16, 0x2A, 0x30].map(|colour| PPURevision::RP2C02.color(colour)));
+    }
+
+    #[test]
+    pub fn parse_palette_needs_four_hex_colours() {
+        assert!(parse_palette("0F,00,10").is_err());
+        assert!(parse_palette("0F,00,10,30,20").is_err());
+        assert!(parse_palette("0F,00,10,XX").is_err());
+    }
+
+    #[test]
+    pub fn parse_options_uses_defaults() {
+        let options = parse_options(&args(&["game.nes"])).unwrap();
+
+        assert_eq!(options.rom, PathBuf::from("game.nes"));
+        assert_eq!(options.palette, parse_palette("0F,00,10,30").unwrap());
+        assert_eq!(options.frames, 60);
+        assert_eq!(options.out, PathBuf::from("."));
+    }
+
+    #[test]
+    pub fn parse_options_reads_flags_in_any_order() {
+        let options = parse_options(&args(&["--frames", "5", "game.nes", "--out", "dump", "--palette", "0F,16,2A,30"])).unwrap();
+
+        assert_eq!(options.rom, PathBuf::from("game.nes"));
+        assert_eq!(options.palette, parse_palette("0F,16,2A,30").unwrap());
+        assert_eq!(options.frames, 5);
+        assert_eq!(options.out, PathBuf::from("dump"));
+    }
+
+    #[test]
+    pub fn parse_options_rejects_bad_arguments() {
+        assert!(parse_options(&args(&[])).is_err());
+        assert!(parse_options(&args(&["game.nes", "--frames"])).is_err());
+        assert!(parse_options(&args(&["game.nes", "--frames", "lots"])).is_err());
+        assert!(parse_options(&args(&["game.nes", "other.nes"])).is_err());
+        assert!(parse_options(&args(&["game.nes", "--zoom", "2"])).is_err());
+    }
+}
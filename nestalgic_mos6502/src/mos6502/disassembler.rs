@@ -0,0 +1,284 @@
+use std::fmt;
+
+use super::Address;
+use super::addressing_mode::AddressingMode;
+use super::bus::Bus;
+use super::opcode::Opcode;
+use super::opcode_matrix::OpcodeInfo;
+
+/// A single instruction decoded from memory for display, e.g. in a debugger or a trace log.
+///
+/// Unlike `Instruction` this never fails: bytes that aren't a supported opcode, or an opcode whose
+/// operand runs past the end of the bytes we were given, are kept as a one byte `.DB` directive so
+/// disassembly can carry on with the next byte.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DisassembledInstruction {
+    /// The address of the first byte
+    pub address: Address,
+
+    /// The opcode followed by its operand bytes
+    pub bytes: Vec<u8>,
+
+    /// The decoded opcode, or `None` if this is a `.DB` directive
+    pub info: Option<OpcodeInfo>,
+}
+
+impl DisassembledInstruction {
+    /// Decode the instruction at the start of `bytes`, which were read from `address`.
+    pub fn decode(address: Address, bytes: &[u8]) -> DisassembledInstruction {
+        let info = bytes.first().and_then(|&byte| OpcodeInfo::for_byte(byte));
+        let len = info.map_or(1, |info| 1 + info.addressing_mode.operand_len() as usize);
+
+        match info {
+            Some(info) if bytes.len() >= len => DisassembledInstruction {
+                address,
+                bytes: bytes[..len].to_vec(),
+                info: Some(info),
+            },
+            _ => DisassembledInstruction {
+                address,
+                bytes: bytes.iter().take(1).copied().collect(),
+                info: None,
+            },
+        }
+    }
+
+    /// The number of bytes used by the instruction, i.e. the distance to the next one.
+    pub fn len(&self) -> u16 {
+        self.bytes.len() as u16
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// The operand in assembler syntax, e.g. `#$10`, `($80,X)` or `A`. Branch targets are
+    /// resolved so `BCS` shows the address it jumps to rather than the offset. Empty for implied
+    /// instructions and `.DB` directives.
+    pub fn operand(&self) -> String {
+        let Some(info) = self.info else {
+            return String::new();
+        };
+
+        let byte = self.operand_u8();
+        let word = self.operand_u16();
+
+        match info.addressing_mode {
+            AddressingMode::Implied => String::new(),
+            AddressingMode::Accumulator => "A".to_string(),
+            AddressingMode::Immediate => format!("#${:02X}", byte),
+            AddressingMode::ZeroPage => format!("${:02X}", byte),
+            AddressingMode::ZeroPageX => format!("${:02X},X", byte),
+            AddressingMode::ZeroPageY => format!("${:02X},Y", byte),
+            AddressingMode::Relative => format!("${:04X}", self.branch_target()),
+            AddressingMode::Indirect => format!("(${:04X})", word),
+            AddressingMode::IndexedIndirect => format!("(${:02X},X)", byte),
+            AddressingMode::IndirectIndexed => format!("(${:02X}),Y", byte),
+            AddressingMode::Absolute => format!("${:04X}", word),
+            AddressingMode::AbsoluteX => format!("${:04X},X", word),
+            AddressingMode::AbsoluteY => format!("${:04X},Y", word),
+        }
+    }
+
+    /// Like `operand` but also shows the address the instruction will touch given `x` and `y`,
+    /// and the value `peek` finds there, in the style of Nintendulator's `nestest.log`:
+    ///
+    /// ```text
+    /// LDA ($80,X) @ 82 = 0300 = 5B
+    /// LDA ($97),Y = FFFF @ 0033 = A3
+    /// JMP ($02FF) = 0300
+    /// ```
+    ///
+    /// `peek` must not have side effects, since it's called for memory the CPU may never read.
+    pub fn operand_with_memory(&self, x: u8, y: u8, peek: impl Fn(Address) -> u8) -> String {
+        let Some(info) = self.info else {
+            return String::new();
+        };
+
+        let peek_u16 = |lo: Address, hi: Address| u16::from_le_bytes([peek(lo), peek(hi)]);
+        let zero_page_u16 = |pointer: u8| peek_u16(pointer as u16, pointer.wrapping_add(1) as u16);
+
+        let operand = self.operand();
+        let byte = self.operand_u8();
+        let word = self.operand_u16();
+
+        match info.addressing_mode {
+            AddressingMode::Implied | AddressingMode::Accumulator |
+            AddressingMode::Immediate | AddressingMode::Relative => operand,
+            AddressingMode::ZeroPage => format!("{} = {:02X}", operand, peek(byte as u16)),
+            AddressingMode::ZeroPageX | AddressingMode::ZeroPageY => {
+                let index = if info.addressing_mode == AddressingMode::ZeroPageX { x } else { y };
+                let address = byte.wrapping_add(index);
+                format!("{} @ {:02X} = {:02X}", operand, address, peek(address as u16))
+            },
+            AddressingMode::Indirect => {
+                // `JMP` doesn't carry into the high byte of the pointer, see `Opcode::JMP`
+                let [lo, hi] = word.to_le_bytes();
+                let target = peek_u16(word, u16::from_le_bytes([lo.wrapping_add(1), hi]));
+                format!("{} = {:04X}", operand, target)
+            },
+            AddressingMode::IndexedIndirect => {
+                let pointer = byte.wrapping_add(x);
+                let address = zero_page_u16(pointer);
+                format!("{} @ {:02X} = {:04X} = {:02X}", operand, pointer, address, peek(address))
+            },
+            AddressingMode::IndirectIndexed => {
+                let base = zero_page_u16(byte);
+                let address = base.wrapping_add(y as u16);
+                format!("{} = {:04X} @ {:04X} = {:02X}", operand, base, address, peek(address))
+            },
+            AddressingMode::Absolute => match info.opcode {
+                Opcode::JMP | Opcode::JSR => operand,
+                _ => format!("{} = {:02X}", operand, peek(word)),
+            },
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => {
+                let index = if info.addressing_mode == AddressingMode::AbsoluteX { x } else { y };
+                let address = word.wrapping_add(index as u16);
+                format!("{} @ {:04X} = {:02X}", operand, address, peek(address))
+            },
+        }
+    }
+
+    /// Where a branch goes if it's taken. Offsets are relative to the instruction after the branch.
+    fn branch_target(&self) -> Address {
+        self.address
+            .wrapping_add(self.len())
+            .wrapping_add(self.operand_u8() as i8 as u16)
+    }
+
+    fn operand_u8(&self) -> u8 {
+        self.bytes.get(1).copied().unwrap_or(0)
+    }
+
+    fn operand_u16(&self) -> u16 {
+        u16::from_le_bytes([self.operand_u8(), self.bytes.get(2).copied().unwrap_or(0)])
+    }
+}
+
+impl fmt::Display for DisassembledInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.info {
+            Some(info) => {
+                let operand = self.operand();
+                if operand.is_empty() {
+                    write!(f, "{}", info.opcode)
+                } else {
+                    write!(f, "{} {}", info.opcode, operand)
+                }
+            },
+            None => write!(f, ".DB ${:02X}", self.bytes.first().copied().unwrap_or(0)),
+        }
+    }
+}
+
+/// Disassemble `bytes` as a run of instructions, where the first byte was read from `start`.
+pub fn disassemble(start: Address, bytes: &[u8]) -> Vec<DisassembledInstruction> {
+    let mut instructions = Vec::new();
+
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let instruction = DisassembledInstruction::decode(start.wrapping_add(offset as u16), &bytes[offset..]);
+        offset += instruction.bytes.len();
+        instructions.push(instruction);
+    }
+
+    instructions
+}
+
+/// Disassemble every instruction starting between `start` and `end` (exclusive). The last
+/// instruction's operand may be read from past `end`.
+///
+/// This reads `bus` like the CPU would, so memory with read side effects (e.g. PPU registers)
+/// should be avoided or read through a bus that peeks instead.
+pub fn disassemble_bus(bus: &mut impl Bus, start: Address, end: Address) -> Vec<DisassembledInstruction> {
+    let mut instructions = Vec::new();
+
+    let mut address = start;
+    while address < end {
+        let bytes = (0..3).map(|offset| bus.read_u8(address.wrapping_add(offset))).collect::<Vec<u8>>();
+        let instruction = DisassembledInstruction::decode(address, &bytes);
+
+        let (next, overflowed) = address.overflowing_add(instruction.len());
+        instructions.push(instruction);
+        if overflowed {
+            break;
+        }
+        address = next;
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::bus::RamBus16kb;
+
+    #[test]
+    pub fn disassemble_formats_each_addressing_mode() {
+        let program = [
+            0xA2, 0x00,       // LDX #$00
+            0x0A,             // ASL A
+            0xB5, 0x10,       // LDA $10,X
+            0x6C, 0xFF, 0x02, // JMP ($02FF)
+            0xB1, 0x89,       // LDA ($89),Y
+            0xB0, 0xFC,       // BCS
+            0x9E, 0x00, 0x02, // SHX $0200,Y
+            0xEA,             // NOP
+            0x20, 0x2D,       // JSR, missing its high byte
+        ];
+
+        let disassembly = disassemble(0xC000, &program)
+            .iter()
+            .map(|instruction| format!("{:04X} {}", instruction.address, instruction))
+            .collect::<Vec<String>>();
+
+        assert_eq!(disassembly, vec![
+            "C000 LDX #$00",
+            "C002 ASL A",
+            "C003 LDA $10,X",
+            "C005 JMP ($02FF)",
+            "C008 LDA ($89),Y",
+            "C00A BCS $C008",
+            "C00C SHX $0200,Y",
+            "C00F NOP",
+            "C010 .DB $20",
+            "C011 .DB $2D",
+        ]);
+    }
+
+    #[test]
+    pub fn operand_with_memory_resolves_addresses() {
+        let mut memory = [0u8; 0x400];
+        memory[0x0082] = 0x00;
+        memory[0x0083] = 0x03;
+        memory[0x02FF] = 0x00;
+        memory[0x0200] = 0x03;
+        memory[0x0300] = 0x5B;
+        let peek = |address: Address| memory[address as usize % memory.len()];
+
+        let operands = [
+            (vec![0xA1, 0x80], "($80,X) @ 82 = 0300 = 5B"),
+            (vec![0xB1, 0x82], "($82),Y = 0300 @ 0302 = 00"),
+            (vec![0x6C, 0xFF, 0x02], "($02FF) = 0300"),
+            (vec![0xBD, 0x00, 0x03], "$0300,X @ 0302 = 00"),
+            (vec![0x86, 0x82], "$82 = 00"),
+            (vec![0x20, 0x00, 0x03], "$0300"),
+        ];
+
+        for (bytes, expected) in operands {
+            let instruction = DisassembledInstruction::decode(0x8000, &bytes);
+            assert_eq!(instruction.operand_with_memory(0x02, 0x02, peek), expected);
+        }
+    }
+
+    #[test]
+    pub fn disassemble_bus_stops_at_end() {
+        let mut bus = RamBus16kb::new().with_memory_at(0x0600, vec![0xA9, 0x01, 0x8D, 0x00, 0x02, 0x00]);
+
+        let instructions = disassemble_bus(&mut bus, 0x0600, 0x0603);
+
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[1].to_string(), "STA $0200");
+    }
+}
@@ -2,7 +2,6 @@ use thiserror::Error;
 
 use super::instruction::Instruction;
 use super::addressing_mode::Addressing;
-use super::addressable::AddressableTarget;
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -12,15 +11,6 @@ pub enum Error {
     #[error("Invalid attempt to target address with addressing: {0:?}")]
     InvalidTargetAddressAttempt(Addressing),
 
-    #[error("Invalid attempt to access address of {0:?}")]
-    InvalidAddressAttempt(AddressableTarget),
-
-    #[error("Invalid attempt to write to unwritable addressable: {0:?} = {1:?}")]
-    InvalidAddressableWrite(AddressableTarget, u8),
-
-    #[error("Invalid attempt to modify to unmodifiable addressable: {0:?}")]
-    InvalidAddressableModify(AddressableTarget),
-
     #[error("Invalid attempt to read using invalid addressing mode with instruction: {0:?}")]
     InvalidReadValue(Instruction),
 
@@ -0,0 +1,839 @@
+use std::convert::TryFrom;
+
+use super::{Address, MOS6502, Result};
+use super::addressing_mode::AddressingMode;
+use super::bus::Bus;
+use super::instruction::InstructionSignature;
+use super::interrupt::Interrupt;
+use super::opcode::Opcode;
+use super::register::Register;
+use super::status::StatusFlag;
+
+/// Where the CPU is up to in the instruction it's executing.
+///
+/// The 6502 spends a cycle on every bus access an instruction makes, so rather than running an
+/// instruction all at once we keep enough state to carry on from the last cycle. The cycle by cycle
+/// breakdown of each addressing mode follows `6502_cpu.txt`.
+///
+/// See also: https://www.nesdev.org/6502_cpu.txt
+#[derive(Clone, Copy, Debug)]
+pub(super) struct InstructionState {
+    /// The address of the opcode
+    pub pc: Address,
+
+    pub opcode: Opcode,
+    pub addressing_mode: AddressingMode,
+
+    /// The interrupt being serviced when `opcode` is `BRK`. Hardware interrupts run the same
+    /// sequence as the `BRK` instruction.
+    pub interrupt: Interrupt,
+
+    /// The last cycle we ran, starting from `1` for the opcode fetch
+    pub cycle: u8,
+
+    /// The address being built by the addressing mode, which becomes the address the instruction
+    /// reads or writes.
+    pub address: Address,
+
+    /// The zero page pointer used by the indirect addressing modes
+    pub pointer: u8,
+
+    /// A byte the instruction needs on a later cycle, e.g. the value being modified by a
+    /// read-modify-write instruction.
+    pub value: u8,
+
+    /// True if indexing `address` carried into the high byte. The 6502 only adds the index to the
+    /// low byte at first and takes a cycle to fix up the high byte.
+    pub page_crossed: bool,
+}
+
+/// How an instruction uses the memory targeted by its addressing mode
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Access {
+    Read,
+    Write,
+    Modify,
+}
+
+impl Access {
+    fn of(opcode: Opcode) -> Access {
+        match opcode {
+            Opcode::STA | Opcode::STX | Opcode::STY | Opcode::SAX |
+            Opcode::SHA | Opcode::SHX | Opcode::SHY | Opcode::TAS => Access::Write,
+
+            Opcode::ASL | Opcode::LSR | Opcode::ROL | Opcode::ROR | Opcode::INC | Opcode::DEC |
+            Opcode::SLO | Opcode::SRE | Opcode::RLA | Opcode::RRA | Opcode::DCP | Opcode::ISC => Access::Modify,
+
+            _ => Access::Read,
+        }
+    }
+}
+
+impl InstructionState {
+    fn new(pc: Address, signature: InstructionSignature, interrupt: Interrupt) -> InstructionState {
+        InstructionState {
+            pc,
+            opcode: signature.opcode,
+            addressing_mode: signature.addressing_mode,
+            interrupt,
+            cycle: 1,
+            address: 0,
+            pointer: 0,
+            value: 0,
+            page_crossed: false,
+        }
+    }
+
+    /// False on cycles where the 6502 doesn't poll for interrupts, see `MOS6502::poll_interrupts`.
+    ///
+    /// `BRK` and the interrupt sequences never poll, so the first instruction of a handler always
+    /// runs before another interrupt. Taken branches don't poll on their operand cycle, so if they
+    /// don't cross a page an interrupt that arrives during the branch waits for the next
+    /// instruction.
+    ///
+    /// See also: https://www.nesdev.org/wiki/CPU_interrupts#Branch_instructions_and_interrupts
+    pub fn polls_interrupts(&self) -> bool {
+        match self.opcode {
+            Opcode::BRK => false,
+            Opcode::BCS | Opcode::BCC | Opcode::BEQ | Opcode::BNE |
+            Opcode::BMI | Opcode::BPL | Opcode::BVS | Opcode::BVC => self.cycle != 2,
+            _ => true,
+        }
+    }
+
+    /// Set `address` to the low byte already in `address` plus `index`, without carrying into `hi`
+    fn index_address(&mut self, hi: u8, index: u8) {
+        let (lo, page_crossed) = (self.address as u8).overflowing_add(index);
+        self.address = u16::from_le_bytes([lo, hi]);
+        self.page_crossed = page_crossed;
+    }
+}
+
+impl MOS6502 {
+    /// Run the first cycle of the next instruction, which fetches its opcode.
+    ///
+    /// If an interrupt was seen by the last `poll_interrupts` the opcode is thrown away and the
+    /// interrupt sequence runs in its place.
+    pub(super) fn start_instruction(&mut self, bus: &mut impl Bus) -> Result<()> {
+        let pc = self.pc;
+
+        let interrupt = if !self.interrupt_polled {
+            None
+        } else if self.nmi {
+            self.nmi = false;
+            Some(Interrupt::NMI)
+        } else {
+            Some(Interrupt::IRQ)
+        };
+        self.interrupt_polled = false;
+
+        let state = match interrupt {
+            Some(interrupt) => {
+                let _ = bus.read_u8(pc);
+                InstructionState::new(pc, InstructionSignature::new(Opcode::BRK, AddressingMode::Implied), interrupt)
+            }
+            None => {
+                let signature = InstructionSignature::try_from(self.fetch_u8(bus))?;
+                InstructionState::new(pc, signature, Interrupt::BRK)
+            }
+        };
+
+        if state.polls_interrupts() {
+            self.poll_interrupts();
+        }
+
+        self.instruction = Some(state);
+        Ok(())
+    }
+
+    /// Sample the interrupt lines at the end of a cycle.
+    ///
+    /// The 6502 decides whether to run an interrupt sequence rather than the next instruction
+    /// from what it sampled at the end of the second-to-last cycle of the current one. Since the
+    /// last cycle isn't polled, an instruction that changes `InterruptDisable` on its last cycle
+    /// (`CLI`, `SEI` and `PLP`) only affects interrupts after the following instruction. `RTI`
+    /// changes it earlier so takes effect straight away.
+    ///
+    /// See also: https://www.nesdev.org/wiki/CPU_interrupts#Detailed_interrupt_behavior
+    pub(super) fn poll_interrupts(&mut self) {
+        let interrupt = if self.nmi {
+            Some(Interrupt::NMI)
+        } else if self.irq {
+            Some(Interrupt::IRQ)
+        } else {
+            None
+        };
+
+        self.interrupt_polled = interrupt
+            .is_some_and(|interrupt| !(interrupt.maskable() && self.p.get(StatusFlag::InterruptDisable)));
+    }
+
+    /// Run the next cycle of `state`. Returns `true` once the instruction has finished.
+    pub(super) fn step_instruction(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.opcode {
+            // Stack Operations
+            Opcode::PHA => self.step_push_stack(bus, state, Register::A),
+            Opcode::PHP => self.step_push_stack(bus, state, Register::P),
+            Opcode::PLA => self.step_pull_stack(bus, state, Register::A),
+            Opcode::PLP => self.step_pull_stack(bus, state, Register::P),
+
+            // Jumps & Calls
+            Opcode::JMP => self.step_jump(bus, state),
+            Opcode::JSR => self.step_jump_subroutine(bus, state),
+            Opcode::RTS => self.step_return(bus, state),
+
+            // Branches
+            Opcode::BCS => self.step_branch_if(bus, state, self.p.get(StatusFlag::Carry)),
+            Opcode::BCC => self.step_branch_if(bus, state, !self.p.get(StatusFlag::Carry)),
+            Opcode::BEQ => self.step_branch_if(bus, state, self.p.get(StatusFlag::Zero)),
+            Opcode::BNE => self.step_branch_if(bus, state, !self.p.get(StatusFlag::Zero)),
+            Opcode::BMI => self.step_branch_if(bus, state, self.p.get(StatusFlag::Negative)),
+            Opcode::BPL => self.step_branch_if(bus, state, !self.p.get(StatusFlag::Negative)),
+            Opcode::BVS => self.step_branch_if(bus, state, self.p.get(StatusFlag::Overflow)),
+            Opcode::BVC => self.step_branch_if(bus, state, !self.p.get(StatusFlag::Overflow)),
+
+            // System Functions
+            Opcode::RTI => self.step_return_from_interrupt(bus, state),
+            Opcode::BRK => self.step_interrupt(bus, state),
+
+            _ => self.step_addressing(bus, state),
+        }
+    }
+
+    /// Read the byte at `pc` and move `pc` past it
+    fn fetch_u8(&mut self, bus: &mut impl Bus) -> u8 {
+        let byte = bus.read_u8(self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        byte
+    }
+
+    /// Work out the address for an instruction that operates on memory, then hand over to
+    /// `step_access` once it's known.
+    fn step_addressing(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        let index = match state.addressing_mode {
+            AddressingMode::ZeroPageY | AddressingMode::AbsoluteY | AddressingMode::IndirectIndexed => self.y,
+            _ => self.x,
+        };
+
+        match (state.addressing_mode, state.cycle) {
+            (AddressingMode::Implied, 2) | (AddressingMode::Accumulator, 2) => {
+                // The 6502 always reads the byte after the opcode, even if it doesn't use it
+                let _ = bus.read_u8(self.pc);
+                self.execute_implied(state.opcode);
+                true
+            }
+
+            (AddressingMode::Immediate, 2) => {
+                let value = self.fetch_u8(bus);
+                self.execute_read(state.opcode, value);
+                true
+            }
+
+            (AddressingMode::ZeroPage, 2) |
+            (AddressingMode::ZeroPageX, 2) |
+            (AddressingMode::ZeroPageY, 2) |
+            (AddressingMode::Absolute, 2) |
+            (AddressingMode::AbsoluteX, 2) |
+            (AddressingMode::AbsoluteY, 2) => {
+                state.address = self.fetch_u8(bus) as u16;
+                false
+            }
+
+            (AddressingMode::ZeroPageX, 3) | (AddressingMode::ZeroPageY, 3) => {
+                // The 6502 does a dummy read of the unindexed address while it adds the index.
+                // Zero page indexing wraps around within the zero page.
+                let _ = bus.read_u8(state.address);
+                state.address = (state.address as u8).wrapping_add(index) as u16;
+                false
+            }
+
+            (AddressingMode::Absolute, 3) => {
+                let hi = self.fetch_u8(bus);
+                state.address = u16::from_le_bytes([state.address as u8, hi]);
+                false
+            }
+
+            (AddressingMode::AbsoluteX, 3) | (AddressingMode::AbsoluteY, 3) => {
+                let hi = self.fetch_u8(bus);
+                state.index_address(hi, index);
+                false
+            }
+
+            (AddressingMode::IndexedIndirect, 2) | (AddressingMode::IndirectIndexed, 2) => {
+                state.pointer = self.fetch_u8(bus);
+                false
+            }
+
+            (AddressingMode::IndexedIndirect, 3) => {
+                // Adding `x` to the pointer costs a cycle, which the 6502 spends on a dummy read
+                let _ = bus.read_u8(state.pointer as u16);
+                state.pointer = state.pointer.wrapping_add(self.x);
+                false
+            }
+
+            (AddressingMode::IndexedIndirect, 4) | (AddressingMode::IndirectIndexed, 3) => {
+                state.address = bus.read_u8(state.pointer as u16) as u16;
+                false
+            }
+
+            (AddressingMode::IndexedIndirect, 5) => {
+                // Each byte of the pointer wraps around on the zero page, rather than the whole
+                // address space
+                let hi = bus.read_u8(state.pointer.wrapping_add(1) as u16);
+                state.address = u16::from_le_bytes([state.address as u8, hi]);
+                false
+            }
+
+            (AddressingMode::IndirectIndexed, 4) => {
+                let hi = bus.read_u8(state.pointer.wrapping_add(1) as u16);
+                state.index_address(hi, index);
+                false
+            }
+
+            _ => self.step_access(bus, state),
+        }
+    }
+
+    /// Read, write or modify the memory at `state.address`.
+    ///
+    /// Reads take 1 cycle, writes take 1 cycle and read-modify-writes take 3 cycles: the 6502
+    /// writes the unmodified value back while it works out the result, then writes the result.
+    ///
+    /// The indexed addressing modes spend an extra cycle reading from the address before its high
+    /// byte is fixed up. Reads that don't cross a page use the value they get and skip the fix up.
+    fn step_access(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        let access = Access::of(state.opcode);
+
+        let (first_cycle, indexed) = match state.addressing_mode {
+            AddressingMode::ZeroPage => (3, false),
+            AddressingMode::ZeroPageX | AddressingMode::ZeroPageY | AddressingMode::Absolute => (4, false),
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => (4, true),
+            AddressingMode::IndirectIndexed => (5, true),
+            AddressingMode::IndexedIndirect => (6, false),
+            addressing_mode => unreachable!("{} doesn't address memory", addressing_mode),
+        };
+
+        let mut step = state.cycle - first_cycle;
+        if indexed {
+            if step == 0 {
+                let value = bus.read_u8(state.address);
+
+                if state.page_crossed {
+                    state.address = state.address.wrapping_add(0x100);
+                } else if access == Access::Read {
+                    self.execute_read(state.opcode, value);
+                    return true;
+                }
+
+                return false;
+            }
+
+            step -= 1;
+        }
+
+        match (access, step) {
+            (Access::Read, _) => {
+                let value = bus.read_u8(state.address);
+                self.execute_read(state.opcode, value);
+                true
+            }
+
+            (Access::Write, _) => {
+                let value = self.store_value(state.opcode, &mut state.address, state.page_crossed);
+                self.write_u8(bus, state.address, value);
+                true
+            }
+
+            (Access::Modify, 0) => {
+                state.value = bus.read_u8(state.address);
+                false
+            }
+
+            (Access::Modify, 1) => {
+                self.write_u8(bus, state.address, state.value);
+                state.value = self.execute_modify(state.opcode, state.value);
+                false
+            }
+
+            (Access::Modify, _) => {
+                self.write_u8(bus, state.address, state.value);
+                true
+            }
+        }
+    }
+
+    fn step_push_stack(&mut self, bus: &mut impl Bus, state: &mut InstructionState, source: Register) -> bool {
+        if state.cycle == 2 {
+            let _ = bus.read_u8(self.pc);
+            return false;
+        }
+
+        // If we push `P` it sets `Break` to `true` for the value that is pushed to the stack
+        let value = match source {
+            Register::P => self.p.with(StatusFlag::Break, true).0,
+            _ => self.read_register(source),
+        };
+
+        self.push_stack_u8(bus, value);
+        true
+    }
+
+    fn step_pull_stack(&mut self, bus: &mut impl Bus, state: &mut InstructionState, target: Register) -> bool {
+        match state.cycle {
+            2 => {
+                let _ = bus.read_u8(self.pc);
+                false
+            }
+
+            3 => {
+                // Incrementing the stack pointer costs a cycle on the 6502
+                let _ = self.read_stack_u8(bus);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            _ => {
+                let value = self.read_stack_u8(bus);
+                self.write_register(target, value);
+                true
+            }
+        }
+    }
+
+    fn step_jump(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.cycle {
+            2 => {
+                state.address = self.fetch_u8(bus) as u16;
+                false
+            }
+
+            3 => {
+                let hi = self.fetch_u8(bus);
+                state.address = u16::from_le_bytes([state.address as u8, hi]);
+
+                if state.addressing_mode == AddressingMode::Absolute {
+                    self.pc = state.address;
+                    return true;
+                }
+
+                false
+            }
+
+            4 => {
+                state.value = bus.read_u8(state.address);
+                false
+            }
+
+            _ => {
+                // This is a bug in the original 6502 that we need to emulate: If our address
+                // spans two pages then the least signifiant byte (the "hi" byte) wraps around
+                // and is fetched from the same page. It's known as the "JMP $xxFF" bug.
+                //
+                // For example: `JMP $02FF` will fetch byte `$02FF` as the low byte and `$0200` as
+                // the high byte, instead of `$02FF` and `$0300` as we would normally expect.
+                let [lo, hi] = state.address.to_le_bytes();
+                let pch = bus.read_u8(u16::from_le_bytes([lo.wrapping_add(1), hi]));
+
+                self.pc = u16::from_le_bytes([state.value, pch]);
+                true
+            }
+        }
+    }
+
+    fn step_jump_subroutine(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.cycle {
+            2 => {
+                state.value = self.fetch_u8(bus);
+                false
+            }
+
+            3 => {
+                // The 6502 reads the stack while it waits for its internal operations
+                let _ = self.read_stack_u8(bus);
+                false
+            }
+
+            // `pc` points at the last byte of the instruction, which is the return address minus
+            // one. When pushing addresses to the stack we push the `hi` byte first.
+            4 => {
+                self.push_stack_u8(bus, (self.pc >> 8) as u8);
+                false
+            }
+
+            5 => {
+                self.push_stack_u8(bus, self.pc as u8);
+                false
+            }
+
+            _ => {
+                let hi = bus.read_u8(self.pc);
+                self.pc = u16::from_le_bytes([state.value, hi]);
+                true
+            }
+        }
+    }
+
+    fn step_return(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.cycle {
+            2 => {
+                let _ = bus.read_u8(self.pc);
+                false
+            }
+
+            3 => {
+                let _ = self.read_stack_u8(bus);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            4 => {
+                state.value = self.read_stack_u8(bus);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            5 => {
+                let hi = self.read_stack_u8(bus);
+                self.pc = u16::from_le_bytes([state.value, hi]);
+                false
+            }
+
+            _ => {
+                // The address on the stack is the return address minus one
+                let _ = self.fetch_u8(bus);
+                true
+            }
+        }
+    }
+
+    fn step_return_from_interrupt(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.cycle {
+            2 => {
+                let _ = bus.read_u8(self.pc);
+                false
+            }
+
+            3 => {
+                let _ = self.read_stack_u8(bus);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            4 => {
+                let p = self.read_stack_u8(bus);
+                self.write_register(Register::P, p);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            5 => {
+                state.value = self.read_stack_u8(bus);
+                self.sp = self.sp.wrapping_add(1);
+                false
+            }
+
+            _ => {
+                let hi = self.read_stack_u8(bus);
+                self.pc = u16::from_le_bytes([state.value, hi]);
+                true
+            }
+        }
+    }
+
+    /// Branches take 2 cycles, +1 if the branch is taken and +1 more if it lands on another page
+    fn step_branch_if(&mut self, bus: &mut impl Bus, state: &mut InstructionState, condition: bool) -> bool {
+        match state.cycle {
+            2 => {
+                let offset = self.fetch_u8(bus) as i8;
+                state.address = self.pc.wrapping_add(offset as u16);
+                !condition
+            }
+
+            3 => {
+                // The offset is added to the low byte first. If it carries the 6502 needs another
+                // cycle to fix up the high byte.
+                let _ = bus.read_u8(self.pc);
+                let [_, pch] = self.pc.to_le_bytes();
+                self.pc = u16::from_le_bytes([state.address as u8, pch]);
+
+                self.pc == state.address
+            }
+
+            _ => {
+                let _ = bus.read_u8(self.pc);
+                self.pc = state.address;
+                true
+            }
+        }
+    }
+
+    /// Runs `BRK` and the interrupt sequence for `NMI` and `IRQ`, which push `pc` and `p` to the
+    /// stack then jump to the address in the interrupt's vector.
+    ///
+    /// The vector isn't chosen until after `p` is pushed, so an `NMI` that arrives during the first
+    /// four cycles of a `BRK` or `IRQ` hijacks it: the `BRK` or `IRQ` is pushed as normal (with
+    /// `Break` set for `BRK`) but the CPU jumps to the `NMI` vector, and the `NMI` is taken.
+    ///
+    /// See also: https://www.nesdev.org/wiki/CPU_interrupts#Interrupt_hijacking
+    fn step_interrupt(&mut self, bus: &mut impl Bus, state: &mut InstructionState) -> bool {
+        match state.cycle {
+            2 => {
+                // `BRK` skips the byte after the opcode, so it returns 2 bytes past the `BRK`
+                if state.interrupt == Interrupt::BRK {
+                    let _ = self.fetch_u8(bus);
+                } else {
+                    let _ = bus.read_u8(self.pc);
+                }
+
+                false
+            }
+
+            3 => {
+                self.push_stack_u8(bus, (self.pc >> 8) as u8);
+                false
+            }
+
+            4 => {
+                self.push_stack_u8(bus, self.pc as u8);
+                false
+            }
+
+            5 => {
+                let p = self.p.with(StatusFlag::Break, state.interrupt == Interrupt::BRK);
+                self.push_stack_u8(bus, p.0);
+
+                if self.nmi && state.interrupt != Interrupt::NMI {
+                    self.nmi = false;
+                    state.interrupt = Interrupt::NMI;
+                }
+
+                false
+            }
+
+            6 => {
+                state.value = bus.read_u8(state.interrupt.vector_address());
+
+                // The InterruptDisable bit is set for all interrupts
+                self.p.set(StatusFlag::InterruptDisable, true);
+                false
+            }
+
+            _ => {
+                let hi = bus.read_u8(state.interrupt.vector_address().wrapping_add(1));
+                self.pc = u16::from_le_bytes([state.value, hi]);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::RESET_VECTOR_ADDRESS;
+    use super::super::bus::RamBus16kb;
+
+    #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+    enum BusAccess {
+        Read(Address),
+        Write(Address, u8),
+    }
+
+    /// Records every access the CPU makes so we can check them cycle by cycle
+    struct LoggingBus {
+        bus: RamBus16kb,
+        accesses: Vec<BusAccess>,
+    }
+
+    impl Bus for LoggingBus {
+        fn read_u8(&mut self, address: u16) -> u8 {
+            self.accesses.push(BusAccess::Read(address));
+            self.bus.read_u8(address)
+        }
+
+        fn write_u8(&mut self, address: u16, data: u8) {
+            self.accesses.push(BusAccess::Write(address, data));
+            self.bus.write_u8(address, data)
+        }
+    }
+
+    /// A CPU that has finished resetting and is about to run `program` from `start`
+    fn cpu_with_program_at(start: Address, program: Vec<u8>) -> (MOS6502, LoggingBus) {
+        let mut bus = RamBus16kb::new().with_memory_at(start as usize, program);
+        bus.write_u16(RESET_VECTOR_ADDRESS, start);
+        let mut bus = LoggingBus { bus, accesses: Vec::new() };
+
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+        bus.accesses.clear();
+
+        (cpu, bus)
+    }
+
+    /// Run the next instruction and return the accesses it made, one per cycle
+    fn run_instruction(cpu: &mut MOS6502, bus: &mut LoggingBus) -> Vec<BusAccess> {
+        bus.accesses.clear();
+        cpu.cycle_to_next_instruction(bus).unwrap();
+        bus.accesses.clone()
+    }
+
+    #[test]
+    pub fn read_modify_write_writes_the_old_value_back() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0xFE, 0x10, 0x02, // INC $0210,X
+        ]);
+        cpu.x = 0x01;
+        bus.bus.memory[0x0211] = 0x41;
+
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x0600),
+            BusAccess::Read(0x0601),
+            BusAccess::Read(0x0602),
+            BusAccess::Read(0x0211),
+            BusAccess::Read(0x0211),
+            BusAccess::Write(0x0211, 0x41),
+            BusAccess::Write(0x0211, 0x42),
+        ]);
+    }
+
+    #[test]
+    pub fn indexed_read_across_a_page_reads_the_wrong_page_first() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0xBD, 0xF0, 0x02, // LDA $02F0,X
+            0xBD, 0x00, 0x02, // LDA $0200,X
+        ]);
+        cpu.x = 0x20;
+
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x0600),
+            BusAccess::Read(0x0601),
+            BusAccess::Read(0x0602),
+            BusAccess::Read(0x0210),
+            BusAccess::Read(0x0310),
+        ]);
+
+        // Without a page cross the first read is the right one, so there's no extra cycle
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x0603),
+            BusAccess::Read(0x0604),
+            BusAccess::Read(0x0605),
+            BusAccess::Read(0x0220),
+        ]);
+    }
+
+    #[test]
+    pub fn branches_read_the_next_opcode_while_they_jump() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x06F0, vec![
+            0xB0, 0x02, // BCS, not taken
+            0x90, 0x00, // BCC, taken to the next instruction
+            0x90, 0x20, // BCC, taken to $0716 on the next page
+        ]);
+
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x06F0),
+            BusAccess::Read(0x06F1),
+        ]);
+
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x06F2),
+            BusAccess::Read(0x06F3),
+            BusAccess::Read(0x06F4),
+        ]);
+
+        // The offset is added to the low byte of `pc` before the high byte is fixed up
+        assert_eq!(run_instruction(&mut cpu, &mut bus), vec![
+            BusAccess::Read(0x06F4),
+            BusAccess::Read(0x06F5),
+            BusAccess::Read(0x06F6),
+            BusAccess::Read(0x0616),
+        ]);
+        assert_eq!(cpu.pc, 0x0716);
+    }
+
+    #[test]
+    pub fn interrupts_raised_on_the_last_cycle_wait_for_the_next_instruction() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0xEA, // NOP
+            0xEA, // NOP
+            0xEA, // NOP
+        ]);
+
+        // Raised before the second-to-last cycle, so the NMI runs straight after the NOP
+        cpu.nmi = true;
+        run_instruction(&mut cpu, &mut bus);
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.in_interrupt_sequence());
+
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![0xEA, 0xEA, 0xEA]);
+
+        // Raised on the last cycle, so the second NOP runs first
+        cpu.cycle(&mut bus).unwrap();
+        cpu.nmi = true;
+        cpu.cycle(&mut bus).unwrap();
+        cpu.cycle(&mut bus).unwrap();
+        assert!(!cpu.in_interrupt_sequence());
+        assert_eq!(cpu.instruction_pc(), 0x0601);
+
+        cpu.cycle(&mut bus).unwrap();
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.in_interrupt_sequence());
+    }
+
+    /// `CLI` and `SEI` change `InterruptDisable` after the interrupt lines are polled, so an `IRQ`
+    /// waits for the instruction after `CLI` and one already pending gets through `SEI`.
+    #[test]
+    pub fn cli_and_sei_affect_interrupts_an_instruction_late() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0x58, // CLI
+            0xEA, // NOP
+            0xEA, // NOP
+        ]);
+        cpu.irq = true;
+
+        run_instruction(&mut cpu, &mut bus);
+        cpu.cycle(&mut bus).unwrap();
+        assert!(!cpu.in_interrupt_sequence());
+        assert_eq!(cpu.instruction_pc(), 0x0601);
+
+        cpu.cycle(&mut bus).unwrap();
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.in_interrupt_sequence());
+
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0x58, // CLI
+            0x78, // SEI
+            0xEA, // NOP
+        ]);
+        run_instruction(&mut cpu, &mut bus);
+        cpu.irq = true;
+
+        run_instruction(&mut cpu, &mut bus);
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.in_interrupt_sequence());
+    }
+
+    /// A taken branch that stays on its page doesn't poll on its last two cycles, so an interrupt
+    /// raised during it waits for the next instruction.
+    #[test]
+    pub fn taken_branch_delays_interrupts() {
+        let (mut cpu, mut bus) = cpu_with_program_at(0x0600, vec![
+            0x90, 0x00, // BCC, taken to the next instruction
+            0xEA,       // NOP
+        ]);
+
+        cpu.cycle(&mut bus).unwrap();
+        cpu.nmi = true;
+        cpu.cycle(&mut bus).unwrap();
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.at_instruction_boundary());
+
+        cpu.cycle(&mut bus).unwrap();
+        assert!(!cpu.in_interrupt_sequence());
+        assert_eq!(cpu.instruction_pc(), 0x0602);
+
+        cpu.cycle(&mut bus).unwrap();
+        cpu.cycle(&mut bus).unwrap();
+        assert!(cpu.in_interrupt_sequence());
+    }
+}
@@ -41,7 +41,7 @@ impl Instruction {
     pub fn try_from_bus(start: Address, bus: &mut impl Bus) -> Result<(Instruction, CyclesTaken, BytesUsed)> {
         let (signature, signature_cycles_taken, signature_bytes_used) = InstructionSignature::try_from_bus(start, bus)?;
         let (addressing, addressing_cycles_taken, addressing_bytes_used) = signature.addressing_mode.read_addressing(
-            start + signature_bytes_used,
+            start.wrapping_add(signature_bytes_used),
             bus
         );
 
@@ -104,7 +104,7 @@ impl InstructionSignature {
 static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x00*/ Some(InstructionSignature::new(Opcode::BRK, AddressingMode::Implied)),
     /*0x01*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::IndexedIndirect)),
-    /*0x02*/ None,
+    /*0x02*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x03*/ Some(InstructionSignature::new(Opcode::SLO, AddressingMode::IndexedIndirect)), // Unofficial
     /*0x04*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPage)), // Unofficial
     /*0x05*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::ZeroPage)),
@@ -113,14 +113,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x08*/ Some(InstructionSignature::new(Opcode::PHP, AddressingMode::Implied)),
     /*0x09*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::Immediate)),
     /*0x0A*/ Some(InstructionSignature::new(Opcode::ASL, AddressingMode::Accumulator)),
-    /*0x0B*/ None,
+    /*0x0B*/ Some(InstructionSignature::new(Opcode::ANC, AddressingMode::Immediate)), // Unofficial
     /*0x0C*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Absolute)), // Unofficial
     /*0x0D*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::Absolute)),
     /*0x0E*/ Some(InstructionSignature::new(Opcode::ASL, AddressingMode::Absolute)),
     /*0x0F*/ Some(InstructionSignature::new(Opcode::SLO, AddressingMode::Absolute)), // Unofficial
     /*0x10*/ Some(InstructionSignature::new(Opcode::BPL, AddressingMode::Relative)),
     /*0x11*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::IndirectIndexed)),
-    /*0x12*/ None,
+    /*0x12*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x13*/ Some(InstructionSignature::new(Opcode::SLO, AddressingMode::IndirectIndexed)), // Unofficial
     /*0x14*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0x15*/ Some(InstructionSignature::new(Opcode::ORA, AddressingMode::ZeroPageX)),
@@ -136,7 +136,7 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x1F*/ Some(InstructionSignature::new(Opcode::SLO, AddressingMode::AbsoluteX)), // Unofficial
     /*0x20*/ Some(InstructionSignature::new(Opcode::JSR, AddressingMode::Absolute)),
     /*0x21*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::IndexedIndirect)),
-    /*0x22*/ None,
+    /*0x22*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x23*/ Some(InstructionSignature::new(Opcode::RLA, AddressingMode::IndexedIndirect)), // Unofficial
     /*0x24*/ Some(InstructionSignature::new(Opcode::BIT, AddressingMode::ZeroPage)),
     /*0x25*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::ZeroPage)),
@@ -145,14 +145,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x28*/ Some(InstructionSignature::new(Opcode::PLP, AddressingMode::Implied)),
     /*0x29*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::Immediate)),
     /*0x2A*/ Some(InstructionSignature::new(Opcode::ROL, AddressingMode::Accumulator)),
-    /*0x2B*/ None,
+    /*0x2B*/ Some(InstructionSignature::new(Opcode::ANC, AddressingMode::Immediate)), // Unofficial
     /*0x2C*/ Some(InstructionSignature::new(Opcode::BIT, AddressingMode::Absolute)),
     /*0x2D*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::Absolute)),
     /*0x2E*/ Some(InstructionSignature::new(Opcode::ROL, AddressingMode::Absolute)),
     /*0x2F*/ Some(InstructionSignature::new(Opcode::RLA, AddressingMode::Absolute)), // Unofficial
     /*0x30*/ Some(InstructionSignature::new(Opcode::BMI, AddressingMode::Relative)),
     /*0x31*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::IndirectIndexed)),
-    /*0x32*/ None,
+    /*0x32*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x33*/ Some(InstructionSignature::new(Opcode::RLA, AddressingMode::IndirectIndexed)), // Unofficial
     /*0x34*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0x35*/ Some(InstructionSignature::new(Opcode::AND, AddressingMode::ZeroPageX)),
@@ -168,7 +168,7 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x3F*/ Some(InstructionSignature::new(Opcode::RLA, AddressingMode::AbsoluteX)), // Unofficial
     /*0x40*/ Some(InstructionSignature::new(Opcode::RTI, AddressingMode::Implied)),
     /*0x41*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::IndexedIndirect)),
-    /*0x42*/ None,
+    /*0x42*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x43*/ Some(InstructionSignature::new(Opcode::SRE, AddressingMode::IndexedIndirect)), // Unofficial
     /*0x44*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPage)), // Unofficial
     /*0x45*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::ZeroPage)),
@@ -177,14 +177,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x48*/ Some(InstructionSignature::new(Opcode::PHA, AddressingMode::Implied)),
     /*0x49*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::Immediate)),
     /*0x4A*/ Some(InstructionSignature::new(Opcode::LSR, AddressingMode::Accumulator)),
-    /*0x4B*/ None,
+    /*0x4B*/ Some(InstructionSignature::new(Opcode::ALR, AddressingMode::Immediate)), // Unofficial
     /*0x4C*/ Some(InstructionSignature::new(Opcode::JMP, AddressingMode::Absolute)),
     /*0x4D*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::Absolute)),
     /*0x4E*/ Some(InstructionSignature::new(Opcode::LSR, AddressingMode::Absolute)),
     /*0x4F*/ Some(InstructionSignature::new(Opcode::SRE, AddressingMode::Absolute)), // Unofficial
     /*0x50*/ Some(InstructionSignature::new(Opcode::BVC, AddressingMode::Relative)),
     /*0x51*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::IndirectIndexed)),
-    /*0x52*/ None,
+    /*0x52*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x53*/ Some(InstructionSignature::new(Opcode::SRE, AddressingMode::IndirectIndexed)), // Unofficial
     /*0x54*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0x55*/ Some(InstructionSignature::new(Opcode::EOR, AddressingMode::ZeroPageX)),
@@ -200,7 +200,7 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x5F*/ Some(InstructionSignature::new(Opcode::SRE, AddressingMode::AbsoluteX)), // Unofficial
     /*0x60*/ Some(InstructionSignature::new(Opcode::RTS, AddressingMode::Implied)),
     /*0x61*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::IndexedIndirect)),
-    /*0x62*/ None,
+    /*0x62*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x63*/ Some(InstructionSignature::new(Opcode::RRA, AddressingMode::IndexedIndirect)), // Unofficial
     /*0x64*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPage)), // Unofficial
     /*0x65*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::ZeroPage)),
@@ -209,14 +209,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x68*/ Some(InstructionSignature::new(Opcode::PLA, AddressingMode::Implied)),
     /*0x69*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::Immediate)),
     /*0x6A*/ Some(InstructionSignature::new(Opcode::ROR, AddressingMode::Accumulator)),
-    /*0x6B*/ None,
+    /*0x6B*/ Some(InstructionSignature::new(Opcode::ARR, AddressingMode::Immediate)), // Unofficial
     /*0x6C*/ Some(InstructionSignature::new(Opcode::JMP, AddressingMode::Indirect)),
     /*0x6D*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::Absolute)),
     /*0x6E*/ Some(InstructionSignature::new(Opcode::ROR, AddressingMode::Absolute)),
     /*0x6F*/ Some(InstructionSignature::new(Opcode::RRA, AddressingMode::Absolute)), // Unofficial
     /*0x70*/ Some(InstructionSignature::new(Opcode::BVS, AddressingMode::Relative)),
     /*0x71*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::IndirectIndexed)),
-    /*0x72*/ None,
+    /*0x72*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0x73*/ Some(InstructionSignature::new(Opcode::RRA, AddressingMode::IndirectIndexed)), // Unofficial
     /*0x74*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0x75*/ Some(InstructionSignature::new(Opcode::ADC, AddressingMode::ZeroPageX)),
@@ -232,24 +232,24 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x7F*/ Some(InstructionSignature::new(Opcode::RRA, AddressingMode::AbsoluteX)), // Unofficial
     /*0x80*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Immediate)), // Unofficial
     /*0x81*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::IndexedIndirect)),
-    /*0x82*/ None,
+    /*0x82*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Immediate)), // Unofficial
     /*0x83*/ Some(InstructionSignature::new(Opcode::SAX, AddressingMode::IndexedIndirect)), // Unofficial
     /*0x84*/ Some(InstructionSignature::new(Opcode::STY, AddressingMode::ZeroPage)),
     /*0x85*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::ZeroPage)),
     /*0x86*/ Some(InstructionSignature::new(Opcode::STX, AddressingMode::ZeroPage)),
     /*0x87*/ Some(InstructionSignature::new(Opcode::SAX, AddressingMode::ZeroPage)),
     /*0x88*/ Some(InstructionSignature::new(Opcode::DEY, AddressingMode::Implied)),
-    /*0x89*/ None,
+    /*0x89*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Immediate)), // Unofficial
     /*0x8A*/ Some(InstructionSignature::new(Opcode::TXA, AddressingMode::Implied)),
-    /*0x8B*/ None,
+    /*0x8B*/ Some(InstructionSignature::new(Opcode::XAA, AddressingMode::Immediate)), // Unofficial
     /*0x8C*/ Some(InstructionSignature::new(Opcode::STY, AddressingMode::Absolute)),
     /*0x8D*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::Absolute)),
     /*0x8E*/ Some(InstructionSignature::new(Opcode::STX, AddressingMode::Absolute)),
     /*0x8F*/ Some(InstructionSignature::new(Opcode::SAX, AddressingMode::Absolute)), // Unofficial
     /*0x90*/ Some(InstructionSignature::new(Opcode::BCC, AddressingMode::Relative)),
     /*0x91*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::IndirectIndexed)),
-    /*0x92*/ None,
-    /*0x93*/ None,
+    /*0x92*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
+    /*0x93*/ Some(InstructionSignature::new(Opcode::SHA, AddressingMode::IndirectIndexed)), // Unofficial
     /*0x94*/ Some(InstructionSignature::new(Opcode::STY, AddressingMode::ZeroPageX)),
     /*0x95*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::ZeroPageX)),
     /*0x96*/ Some(InstructionSignature::new(Opcode::STX, AddressingMode::ZeroPageY)),
@@ -257,11 +257,11 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0x98*/ Some(InstructionSignature::new(Opcode::TYA, AddressingMode::Implied)),
     /*0x99*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::AbsoluteY)),
     /*0x9A*/ Some(InstructionSignature::new(Opcode::TXS, AddressingMode::Implied)),
-    /*0x9B*/ None,
-    /*0x9C*/ None,
+    /*0x9B*/ Some(InstructionSignature::new(Opcode::TAS, AddressingMode::AbsoluteY)), // Unofficial
+    /*0x9C*/ Some(InstructionSignature::new(Opcode::SHY, AddressingMode::AbsoluteX)), // Unofficial
     /*0x9D*/ Some(InstructionSignature::new(Opcode::STA, AddressingMode::AbsoluteX)),
-    /*0x9E*/ None,
-    /*0x9F*/ None,
+    /*0x9E*/ Some(InstructionSignature::new(Opcode::SHX, AddressingMode::AbsoluteY)), // Unofficial
+    /*0x9F*/ Some(InstructionSignature::new(Opcode::SHA, AddressingMode::AbsoluteY)), // Unofficial
     /*0xA0*/ Some(InstructionSignature::new(Opcode::LDY, AddressingMode::Immediate)),
     /*0xA1*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::IndexedIndirect)),
     /*0xA2*/ Some(InstructionSignature::new(Opcode::LDX, AddressingMode::Immediate)),
@@ -273,14 +273,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0xA8*/ Some(InstructionSignature::new(Opcode::TAY, AddressingMode::Implied)),
     /*0xA9*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::Immediate)),
     /*0xAA*/ Some(InstructionSignature::new(Opcode::TAX, AddressingMode::Implied)),
-    /*0xAB*/ None,
+    /*0xAB*/ Some(InstructionSignature::new(Opcode::LXA, AddressingMode::Immediate)), // Unofficial
     /*0xAC*/ Some(InstructionSignature::new(Opcode::LDY, AddressingMode::Absolute)),
     /*0xAD*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::Absolute)),
     /*0xAE*/ Some(InstructionSignature::new(Opcode::LDX, AddressingMode::Absolute)),
     /*0xAF*/ Some(InstructionSignature::new(Opcode::LAX, AddressingMode::Absolute)), // Unofficial
     /*0xB0*/ Some(InstructionSignature::new(Opcode::BCS, AddressingMode::Relative)),
     /*0xB1*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::IndirectIndexed)),
-    /*0xB2*/ None,
+    /*0xB2*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0xB3*/ Some(InstructionSignature::new(Opcode::LAX, AddressingMode::IndirectIndexed)), // Unofficial
     /*0xB4*/ Some(InstructionSignature::new(Opcode::LDY, AddressingMode::ZeroPageX)),
     /*0xB5*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::ZeroPageX)),
@@ -289,14 +289,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0xB8*/ Some(InstructionSignature::new(Opcode::CLV, AddressingMode::Implied)),
     /*0xB9*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::AbsoluteY)),
     /*0xBA*/ Some(InstructionSignature::new(Opcode::TSX, AddressingMode::Implied)),
-    /*0xBB*/ None,
+    /*0xBB*/ Some(InstructionSignature::new(Opcode::LAS, AddressingMode::AbsoluteY)), // Unofficial
     /*0xBC*/ Some(InstructionSignature::new(Opcode::LDY, AddressingMode::AbsoluteX)),
     /*0xBD*/ Some(InstructionSignature::new(Opcode::LDA, AddressingMode::AbsoluteX)),
     /*0xBE*/ Some(InstructionSignature::new(Opcode::LDX, AddressingMode::AbsoluteY)),
     /*0xBF*/ Some(InstructionSignature::new(Opcode::LAX, AddressingMode::AbsoluteY)), // Unofficial
     /*0xC0*/ Some(InstructionSignature::new(Opcode::CPY, AddressingMode::Immediate)),
     /*0xC1*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::IndexedIndirect)),
-    /*0xC2*/ None,
+    /*0xC2*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Immediate)), // Unofficial
     /*0xC3*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::IndexedIndirect)), // Unofficial
     /*0xC4*/ Some(InstructionSignature::new(Opcode::CPY, AddressingMode::ZeroPage)),
     /*0xC5*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::ZeroPage)),
@@ -305,14 +305,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0xC8*/ Some(InstructionSignature::new(Opcode::INY, AddressingMode::Implied)),
     /*0xC9*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::Immediate)),
     /*0xCA*/ Some(InstructionSignature::new(Opcode::DEX, AddressingMode::Implied)),
-    /*0xCB*/ None,
+    /*0xCB*/ Some(InstructionSignature::new(Opcode::AXS, AddressingMode::Immediate)), // Unofficial
     /*0xCC*/ Some(InstructionSignature::new(Opcode::CPY, AddressingMode::Absolute)),
     /*0xCD*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::Absolute)),
     /*0xCE*/ Some(InstructionSignature::new(Opcode::DEC, AddressingMode::Absolute)),
     /*0xCF*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::Absolute)), // Unofficial
     /*0xD0*/ Some(InstructionSignature::new(Opcode::BNE, AddressingMode::Relative)),
     /*0xD1*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::IndirectIndexed)),
-    /*0xD2*/ None,
+    /*0xD2*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0xD3*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::IndirectIndexed)), // Unofficial
     /*0xD4*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0xD5*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::ZeroPageX)),
@@ -321,14 +321,14 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0xD8*/ Some(InstructionSignature::new(Opcode::CLD, AddressingMode::Implied)),
     /*0xD9*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::AbsoluteY)),
     /*0xDA*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Implied)), // Unofficial
-    /*0xDB*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::AbsoluteX)), // Unofficial
+    /*0xDB*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::AbsoluteY)), // Unofficial
     /*0xDC*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::AbsoluteX)), // Unofficial
     /*0xDD*/ Some(InstructionSignature::new(Opcode::CMP, AddressingMode::AbsoluteX)),
     /*0xDE*/ Some(InstructionSignature::new(Opcode::DEC, AddressingMode::AbsoluteX)),
     /*0xDF*/ Some(InstructionSignature::new(Opcode::DCP, AddressingMode::AbsoluteX)), // Unofficial
     /*0xE0*/ Some(InstructionSignature::new(Opcode::CPX, AddressingMode::Immediate)),
     /*0xE1*/ Some(InstructionSignature::new(Opcode::SBC, AddressingMode::IndexedIndirect)),
-    /*0xE2*/ None,
+    /*0xE2*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::Immediate)), // Unofficial
     /*0xE3*/ Some(InstructionSignature::new(Opcode::ISC, AddressingMode::IndexedIndirect)), // Unofficial
     /*0xE4*/ Some(InstructionSignature::new(Opcode::CPX, AddressingMode::ZeroPage)),
     /*0xE5*/ Some(InstructionSignature::new(Opcode::SBC, AddressingMode::ZeroPage)),
@@ -344,7 +344,7 @@ static INSTRUCTION_SIGNATURES: [Option<InstructionSignature>; 256] = [
     /*0xEF*/ Some(InstructionSignature::new(Opcode::ISC, AddressingMode::Absolute)), // Unofficial
     /*0xF0*/ Some(InstructionSignature::new(Opcode::BEQ, AddressingMode::Relative)),
     /*0xF1*/ Some(InstructionSignature::new(Opcode::SBC, AddressingMode::IndirectIndexed)),
-    /*0xF2*/ None,
+    /*0xF2*/ Some(InstructionSignature::new(Opcode::KIL, AddressingMode::Implied)), // Unofficial
     /*0xF3*/ Some(InstructionSignature::new(Opcode::ISC, AddressingMode::IndirectIndexed)), // Unofficial
     /*0xF4*/ Some(InstructionSignature::new(Opcode::NOP, AddressingMode::ZeroPageX)), // Unofficial
     /*0xF5*/ Some(InstructionSignature::new(Opcode::SBC, AddressingMode::ZeroPageX)),
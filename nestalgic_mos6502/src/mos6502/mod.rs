@@ -1,8 +1,10 @@
 mod addressing_mode;
-mod addressable;
 mod bus;
+mod disassembler;
 mod dma;
+mod execution;
 mod opcode;
+mod opcode_matrix;
 mod instruction;
 mod error;
 mod register;
@@ -10,14 +12,18 @@ mod status;
 mod interrupt;
 
 use instruction::Instruction;
-use opcode::Opcode;
-use error::Error;
+use execution::InstructionState;
+pub use error::Error;
 use register::Register;
 use interrupt::Interrupt;
 use std::collections::HashMap;
 
+pub use opcode::Opcode;
+pub use addressing_mode::AddressingMode;
+pub use opcode_matrix::{OpcodeInfo, addressing_mode_short_name, opcode_matrix_markdown};
 pub use bus::Bus;
 pub use bus::RamBus16kb;
+pub use disassembler::{DisassembledInstruction, disassemble, disassemble_bus};
 pub use dma::{DMA, ActiveDMA, DMAStatus};
 pub use status::{Status, StatusFlag};
 pub use interrupt::{NMI_VECTOR_ADDRESS, IRQ_VECTOR_ADDRESS, RESET_VECTOR_ADDRESS};
@@ -31,10 +37,17 @@ pub type CyclesTaken = u32;
 const STACK_START_ADDRESS: u16 = 0x0100;
 // const STACK_END_ADDRESS: u16 = 0x01FF;
 
+/// `XAA` and `LXA` `AND` their result with a value that depends on the chip and even its
+/// temperature. `0xEE` is the most commonly observed value.
+///
+/// See also: https://www.nesdev.org/wiki/Visual6502wiki/6502_Opcode_8B_(XAA,_ANE)
+const UNSTABLE_MAGIC: u8 = 0xEE;
+
 /// `MOS6502` emulates the functionality of the MOS Technology 6502 microprocessor.
 ///
 /// The NES uses a Ricoh 2A03 which is basically a MOS6502 without the decimal mode.
-/// This means this class can be used to emulate the NES.
+/// This means this class can be used to emulate the NES. Other 6502 machines can turn decimal
+/// mode back on with `with_decimal_mode`.
 #[derive(Debug)]
 pub struct MOS6502 {
     /// `a` is the accumulator register. It has many uses including:
@@ -72,12 +85,13 @@ pub struct MOS6502 {
 
     /// `nmi` indicates whether the non maskable interrupt line is active on the CPU.
     ///
-    /// When set to true the next cycle will trigger the interrupt behavior
+    /// Like the 6502 the lines are polled at the end of each instruction's second-to-last cycle,
+    /// so an interrupt raised during an instruction runs once that instruction finishes and one
+    /// raised during its last cycle waits for the instruction after it.
     pub nmi: bool,
 
-    /// `irq` indicates whether the maskable interrupt line is active on the CPU.
-    ///
-    /// When set to true the next cycle will trigger the interrupt behavior
+    /// `irq` indicates whether the maskable interrupt line is active on the CPU. It's polled the
+    /// same way as `nmi`, and ignored while `InterruptDisable` is set.
     pub irq: bool,
 
     /// The total number of cycles that have elapsed since the CPU started running.
@@ -92,6 +106,22 @@ pub struct MOS6502 {
 
     /// Stores the current state of DMA. `None` if no DMA is happening right now.
     active_dma: Option<ActiveDMA>,
+
+    /// The number of cycles taken by the `reset` sequence.
+    reset_cycles: u32,
+
+    /// True if `ADC` and `SBC` use BCD arithmetic while `DecimalMode` is set in `p`.
+    decimal_mode: bool,
+
+    /// True once a `KIL` opcode has halted the CPU, see `is_jammed`.
+    jammed: bool,
+
+    /// The instruction being executed, or `None` between instructions. See `cycle`.
+    instruction: Option<InstructionState>,
+
+    /// True if the last poll of the interrupt lines found an interrupt to run before the next
+    /// instruction, see `poll_interrupts`.
+    interrupt_polled: bool,
 }
 
 impl MOS6502 {
@@ -114,39 +144,159 @@ impl MOS6502 {
 
             dma: HashMap::new(),
             active_dma: None,
+
+            reset_cycles: 7,
+            decimal_mode: false,
+
+            jammed: false,
+            instruction: None,
+            interrupt_polled: false,
         }
     }
 
+    /// Set the value of `sp` at power on. Defaults to `0x00` which leaves `sp` at `0xFD` after the
+    /// first `reset`.
+    pub fn with_power_on_sp(mut self, sp: u8) -> MOS6502 {
+        self.sp = sp;
+        self
+    }
+
+    /// Set the value of `p` at power on. Defaults to `Status::default()` which leaves `p` at `0x24`
+    /// after the first `reset`.
+    pub fn with_power_on_status(mut self, status: Status) -> MOS6502 {
+        self.p = status;
+        self
+    }
+
+    /// Set the number of cycles `reset` waits before the first instruction. Defaults to the 7 cycles
+    /// taken by the 6502. The reads made by `reset` are the same whatever this is set to.
+    pub fn with_reset_cycles(mut self, reset_cycles: u32) -> MOS6502 {
+        self.reset_cycles = reset_cycles;
+        self
+    }
+
+    /// Make `ADC` and `SBC` (and `RRA` and `ISC`, which use them) treat `A` and the value as two
+    /// BCD digits while `DecimalMode` is set in `p`. Defaults to `false` since the 2A03 in the NES
+    /// has decimal mode disconnected: `SED` still sets the flag but arithmetic stays binary.
+    ///
+    /// Flags follow the NMOS 6502. See `add_decimal` and `subtract_decimal`.
+    pub fn with_decimal_mode(mut self, decimal_mode: bool) -> MOS6502 {
+        self.decimal_mode = decimal_mode;
+        self
+    }
+
     /// When called: Simulates the `reset` input of the 6502.
+    ///
+    /// On a 6502 reset runs the same 7 cycle sequence as an interrupt except the stack writes are
+    /// turned into reads:
+    ///
+    /// - Cycles 1-2: Read (and discard) the byte at `pc` twice
+    /// - Cycles 3-5: Read the stack and decrement `sp`, three times
+    /// - Cycles 6-7: Read the new `pc` from `RESET_VECTOR_ADDRESS`
+    ///
+    /// Unlike instructions and interrupts, reset isn't cycle-stepped. All 7 reads happen during
+    /// this call, so `pc` is ready straight away, and then `reset_cycles` are added to
+    /// `wait_cycles`. The first instruction executes after `reset_cycles` calls to `cycle`, which
+    /// only matches the reads when `reset_cycles` is left at 7.
+    ///
+    /// `InterruptDisable` is set in `p`, all other registers keep their values. This means a 6502
+    /// with the default power on state ends up with `sp = 0xFD` and `p = 0x24`.
+    ///
+    /// Reset is the only way to recover a CPU that `is_jammed`.
     pub fn reset(&mut self, bus: &mut impl Bus) -> Result<()> {
-        self.interrupt(bus, Interrupt::RESET)
+        let _ = bus.read_u8(self.pc);
+        let _ = bus.read_u8(self.pc);
+
+        for _ in 0..3 {
+            let _ = bus.read_u8(STACK_START_ADDRESS + self.sp as u16);
+            self.sp = self.sp.wrapping_sub(1);
+        }
+
+        self.pc = bus.read_u16(Interrupt::RESET.vector_address());
+        self.p.set(StatusFlag::InterruptDisable, true);
+
+        self.jammed = false;
+        self.instruction = None;
+        self.interrupt_polled = false;
+        self.wait_cycles += self.reset_cycles;
+
+        Ok(())
+    }
+
+    /// True if the CPU has executed a `KIL` opcode.
+    ///
+    /// A jammed 6502 stops fetching instructions and ignores `nmi` and `irq`: the clock keeps
+    /// running but nothing else happens until the next `reset`. `pc` is left pointing at the `KIL`.
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    /// True if the CPU has finished the last instruction and any cycles it's waiting on, so the
+    /// next call to `cycle` fetches the opcode at `pc` (or starts a pending DMA or interrupt).
+    pub fn at_instruction_boundary(&self) -> bool {
+        self.instruction.is_none() && self.wait_cycles == 0
+    }
+
+    /// The address of the opcode of the instruction being executed. Between instructions this is
+    /// `pc`, the address of the next one.
+    pub fn instruction_pc(&self) -> u16 {
+        self.instruction.map_or(self.pc, |state| state.pc)
+    }
+
+    /// The last cycle run of the instruction being executed, starting from `1` for the opcode
+    /// fetch. `0` between instructions.
+    pub fn instruction_cycle(&self) -> u8 {
+        self.instruction.map_or(0, |state| state.cycle)
+    }
+
+    /// True if the CPU is running the `NMI` or `IRQ` sequence rather than an instruction it
+    /// fetched from `instruction_pc`.
+    pub fn in_interrupt_sequence(&self) -> bool {
+        self.instruction.is_some_and(|state| state.interrupt != Interrupt::BRK)
     }
 
     /// Execute one clock cycle.
+    ///
+    /// The 6502 reads or writes the bus on every cycle of an instruction, including dummy accesses
+    /// it throws away, so each call makes exactly the access the hardware makes on that cycle. This
+    /// lets whatever is on the other end of the bus (e.g. a PPU racing to set its vblank flag) see
+    /// each access at the right time.
     pub fn cycle(&mut self, bus: &mut impl Bus) -> Result<()> {
+        if let Some(mut state) = self.instruction.take() {
+            state.cycle += 1;
+            if !self.step_instruction(bus, &mut state) {
+                if state.polls_interrupts() {
+                    self.poll_interrupts();
+                }
+                self.instruction = Some(state);
+            }
+
+            self.elapsed_cycles += 1;
+            return Ok(())
+        }
+
+        // The CPU is stalled rather than stopped while it waits, so it keeps watching the
+        // interrupt lines
         if self.wait_cycles > 0 {
             self.wait_cycles -= 1;
+            self.poll_interrupts();
             self.elapsed_cycles += 1;
             return Ok(())
         }
 
         let dma_status = self.step_active_dma(bus);
         if dma_status == DMAStatus::Active {
+            self.poll_interrupts();
             self.elapsed_cycles += 1;
             return Ok(())
         }
 
-        self.execute_interrupts(bus)?;
-
-        let instruction_pc = self.pc;
-        let instruction = self.read_instruction(bus)?;
-        println!(
-            "{:04X}: {:15} (a:{:02X}, x:{:02X}, y:{:02X}, p:{:02X})",
-            instruction_pc, instruction,
-            self.a, self.x, self.y, self.p.0 & 0b1101_1111
-        );
-        self.execute_instruction(bus, instruction)?;
+        if self.jammed {
+            self.elapsed_cycles += 1;
+            return Ok(())
+        }
 
+        self.start_instruction(bus)?;
         self.elapsed_cycles += 1;
 
         Ok(())
@@ -189,7 +339,11 @@ impl MOS6502 {
         loop {
             self.cycle(bus)?;
 
-            if self.next_instruction(bus).map(|i| i.opcode)? == Opcode::BRK {
+            if !self.at_instruction_boundary() {
+                continue;
+            }
+
+            if self.jammed || self.next_instruction(bus).map(|i| i.opcode)? == Opcode::BRK {
                 return Ok(())
             }
         }
@@ -203,183 +357,33 @@ impl MOS6502 {
         loop {
             self.cycle(bus)?;
 
-            if self.wait_cycles == 0 {
+            if self.at_instruction_boundary() {
                 return Ok(())
             }
         }
     }
 
-    fn execute_interrupts(&mut self, bus: &mut impl Bus) -> Result<()> {
-        if self.nmi {
-            self.interrupt(bus, Interrupt::NMI)?;
-            self.nmi = false;
-        } else if self.irq {
-            self.interrupt(bus, Interrupt::IRQ)?;
-        }
-
-        Ok(())
-    }
-
-    /// Simulates maskable and non-maskable interrupts on the 6502
-    fn interrupt(&mut self, bus: &mut impl Bus, interrupt: Interrupt) -> Result<()> {
-        println!("executing interrupt {:?}", interrupt);
-        if interrupt.maskable() && self.p.get(StatusFlag::InterruptDisable) {
-            return Ok(())
-        }
-
-        self.read_instruction(bus)?;
-        self.read_instruction(bus)?;
-
-        // RESET decrements the stack three times but doesn't write the values to the stack.
-        if interrupt != Interrupt::RESET {
-            self.push_stack_u16(bus, self.pc);
-            self.push_stack_u8(bus, self.p.with(StatusFlag::Break, interrupt == Interrupt::BRK).0);
-        } else {
-            self.sp = self.sp.wrapping_sub(3);
-            self.wait_cycles += 3;
-        }
-
-        let target_address = bus.read_u16(interrupt.vector_address());
-        self.wait_cycles += 2;
-
-        // The InterruptDisable bit is set for all interrupts, including `RESET`
-        self.p.set(StatusFlag::InterruptDisable, true);
-
-        self.pc = target_address;
-
-        Ok(())
-    }
-
     pub fn next_instruction(&self, bus: &mut impl Bus) -> Result<Instruction> {
         let (instruction, _, _) = Instruction::try_from_bus(self.pc, bus)?;
         Ok(instruction)
     }
 
-    fn read_instruction(&mut self, bus: &mut impl Bus) -> Result<Instruction> {
-        // We always read an address, even for `implied` and `accumulate` addressing modes
-        // to mimic the cycle behavior of the 6502.
-        let (instruction, bytes_read, bytes_used) = Instruction::try_from_bus(self.pc, bus)?;
-        self.pc += bytes_used;
-
-        // We don't need to wait for the first cycle, we're in it!
-        self.wait_cycles += (bytes_read as u32) - 1;
-        Ok(instruction)
-    }
-
-
-    fn read_u8(&mut self, bus: &mut impl Bus, address: Address) -> u8 {
-        let byte = bus.read_u8(address);
-        self.wait_cycles += 1;
-
-        byte
-    }
-
     fn write_u8(&mut self, bus: &mut impl Bus, address: Address, value: u8) {
         if let Some(dma) = self.dma.get(&address) {
             self.active_dma = Some(ActiveDMA::from_dma(dma, (value as u16) << 8));
 
-            // Normally writing to the dma port takes 1 cycle. But it costs an extra
-            // cycle if the CPU is executing on an odd cycle count.
+            // The CPU waits a cycle for the write to finish before the transfer starts, plus
+            // another if the transfer would otherwise start on an odd cycle.
             //
             // In hardware this is caused by the behavior of the `rdy` pin but we
             // cheat and just add the correct number of wait cycles.
             self.wait_cycles += 1;
-            if self.elapsed_cycles % 2 != 0 {
+            if !(self.elapsed_cycles + 1).is_multiple_of(2) {
                 self.wait_cycles += 1;
             }
         } else {
             bus.write_u8(address, value);
         }
-
-        self.wait_cycles += 1;
-    }
-
-    fn execute_instruction(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        match instruction.opcode {
-            // Register Operations
-            Opcode::LDA => self.op_load(bus, Register::A, instruction),
-            Opcode::LDX => self.op_load(bus, Register::X, instruction),
-            Opcode::LDY => self.op_load(bus, Register::Y, instruction),
-            Opcode::LAX => self.op_lax(bus, instruction),
-            Opcode::STA => self.op_store(bus, Register::A, instruction),
-            Opcode::STX => self.op_store(bus, Register::X, instruction),
-            Opcode::STY => self.op_store(bus, Register::Y, instruction),
-            Opcode::SAX => self.op_sax(bus, instruction),
-            Opcode::TAX => self.op_transfer(Register::A, Register::X),
-            Opcode::TAY => self.op_transfer(Register::A, Register::Y),
-            Opcode::TXA => self.op_transfer(Register::X, Register::A),
-            Opcode::TYA => self.op_transfer(Register::Y, Register::A),
-
-            // Stack Operations
-            Opcode::TSX => self.op_transfer(Register::SP, Register::X),
-            Opcode::TXS => self.op_transfer(Register::X, Register::SP),
-            Opcode::PHA => self.op_push_stack(bus, Register::A),
-            Opcode::PHP => self.op_push_stack(bus, Register::P),
-            Opcode::PLA => self.op_pull_stack(bus, Register::A),
-            Opcode::PLP => self.op_pull_stack(bus, Register::P),
-
-            // Logical Operations
-            Opcode::AND => self.op_logical(bus, instruction, |a, b| a & b),
-            Opcode::EOR => self.op_logical(bus, instruction, |a, b| a ^ b),
-            Opcode::ORA => self.op_logical(bus, instruction, |a, b| a | b),
-            Opcode::BIT => self.op_bit(bus, instruction),
-
-            // Arithmetic
-            Opcode::ADC => self.op_add(bus, instruction),
-            Opcode::SBC => self.op_sub(bus, instruction),
-            Opcode::CMP => self.op_compare(bus, Register::A, instruction),
-            Opcode::CPX => self.op_compare(bus, Register::X, instruction),
-            Opcode::CPY => self.op_compare(bus, Register::Y, instruction),
-
-            // Increments & Decrements
-            Opcode::INC => self.try_modify_instruction_value(bus, instruction, |v| v.wrapping_add(1)).map(|_| ()),
-            Opcode::INX => Ok(self.modify_register(Register::X, |x| x.wrapping_add(1))),
-            Opcode::INY => Ok(self.modify_register(Register::Y, |y| y.wrapping_add(1))),
-            Opcode::ISC => self.op_increment_subtract(bus, instruction),
-            Opcode::DEC => self.try_modify_instruction_value(bus, instruction, |v| v.wrapping_sub(1)).map(|_| ()),
-            Opcode::DEX => Ok(self.modify_register(Register::X, |x| x.wrapping_sub(1))),
-            Opcode::DEY => Ok(self.modify_register(Register::Y, |y| y.wrapping_sub(1))),
-            Opcode::DCP => self.op_decrement_compare(bus, instruction),
-
-            // Shifts
-            Opcode::ASL => self.op_shift_left(bus, instruction).map(|_| ()),
-            Opcode::LSR => self.op_shift_right(bus, instruction).map(|_| ()),
-            Opcode::ROR => self.op_rotate_right(bus, instruction).map(|_| ()),
-            Opcode::ROL => self.op_rotate_left(bus, instruction).map(|_| ()),
-            Opcode::SLO => self.op_shift_left_then_or(bus, instruction),
-            Opcode::SRE => self.op_shift_right_then_xor(bus, instruction),
-            Opcode::RLA => self.op_rotate_left_then_and(bus, instruction),
-            Opcode::RRA => self.op_rotate_right_then_add(bus, instruction),
-
-            // Jumps & Calls
-            Opcode::JMP => self.op_jump(bus, instruction),
-            Opcode::JSR => self.op_jump_subroutine(bus, instruction),
-            Opcode::RTS => self.op_return(bus),
-
-            // Branches
-            Opcode::BCS => self.op_branch_if(bus, instruction, self.p.get(StatusFlag::Carry)),
-            Opcode::BCC => self.op_branch_if(bus, instruction, !self.p.get(StatusFlag::Carry)),
-            Opcode::BEQ => self.op_branch_if(bus, instruction, self.p.get(StatusFlag::Zero)),
-            Opcode::BNE => self.op_branch_if(bus, instruction, !self.p.get(StatusFlag::Zero)),
-            Opcode::BMI => self.op_branch_if(bus, instruction, self.p.get(StatusFlag::Negative)),
-            Opcode::BPL => self.op_branch_if(bus, instruction, !self.p.get(StatusFlag::Negative)),
-            Opcode::BVS => self.op_branch_if(bus, instruction, self.p.get(StatusFlag::Overflow)),
-            Opcode::BVC => self.op_branch_if(bus, instruction, !self.p.get(StatusFlag::Overflow)),
-
-            // Status Flag Functions
-            Opcode::CLC => Ok(self.p.set(StatusFlag::Carry, false)),
-            Opcode::CLD => Ok(self.p.set(StatusFlag::DecimalMode, false)),
-            Opcode::CLI => Ok(self.p.set(StatusFlag::InterruptDisable, false)),
-            Opcode::CLV => Ok(self.p.set(StatusFlag::Overflow, false)),
-            Opcode::SEC => Ok(self.p.set(StatusFlag::Carry, true)),
-            Opcode::SED => Ok(self.p.set(StatusFlag::DecimalMode, true)),
-            Opcode::SEI => Ok(self.p.set(StatusFlag::InterruptDisable, true)),
-
-            // System Functions
-            Opcode::NOP => self.op_nop(bus, instruction),
-            Opcode::RTI => self.op_return_from_interrupt(bus),
-            Opcode::BRK => self.interrupt(bus, Interrupt::BRK),
-        }
     }
 
     fn read_register(&self, register: Register) -> u8 {
@@ -425,242 +429,238 @@ impl MOS6502 {
         self.write_register(register, result);
     }
 
-    fn push_stack(&mut self, bus: &mut impl Bus, values: &[u8]) {
-        for &value in values {
-            self.write_u8(bus, STACK_START_ADDRESS + self.sp as u16, value);
-            self.sp = self.sp.wrapping_sub(1);
-        }
-    }
-
-    fn pull_stack(&mut self, bus: &mut impl Bus, n: u32) -> Vec<u8> {
-        // Incrementing the stack pointer costs a cycle on the 6502
-        self.sp = self.sp.wrapping_add(1);
-        self.wait_cycles += 1;
-
-        let mut vec = Vec::new();
-        for _ in 0..n-1 {
-            vec.push(self.read_u8(bus, STACK_START_ADDRESS + self.sp as u16));
-            self.sp = self.sp.wrapping_add(1);
-        }
-
-        // The last read doesn't do `wrapping_add`
-        vec.push(self.read_u8(bus, STACK_START_ADDRESS + self.sp as u16));
-
-        vec
+    /// Copy the contents of `source` into `target`
+    fn transfer(&mut self, source: Register, target: Register) {
+        let value = self.read_register(source);
+        self.write_register(target, value);
     }
 
     fn push_stack_u8(&mut self, bus: &mut impl Bus, value: u8) {
-        self.push_stack(bus, &[value]);
-    }
-
-    fn pull_stack_u8(&mut self, bus: &mut impl Bus) -> u8{
-        match self.pull_stack(bus, 1)[..] {
-            [byte] => byte,
-            _ => panic!("self.pull_stack(1) returned unexpected number of elements")
-        }
-    }
-
-    fn push_stack_u16(&mut self, bus: &mut impl Bus, value: u16) {
-        let [lo, hi] = value.to_le_bytes();
-
-        // When pushing addresses to the stack we push the `hi` bit first
-        self.push_stack(bus, &[hi, lo]);
+        self.write_u8(bus, STACK_START_ADDRESS + self.sp as u16, value);
+        self.sp = self.sp.wrapping_sub(1);
     }
 
-    fn pull_stack_u16(&mut self, bus: &mut impl Bus) -> u16 {
-        match self.pull_stack(bus, 2)[..] {
-            [lo, hi] => u16::from_le_bytes([lo, hi]),
-            _ => panic!("self.pull_stack(1) returned unexpected number of elements")
-        }
+    /// Read the byte `sp` points at without moving `sp`
+    fn read_stack_u8(&mut self, bus: &mut impl Bus) -> u8 {
+        bus.read_u8(STACK_START_ADDRESS + self.sp as u16)
     }
 
-    fn try_read_instruction_target_address(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<Address> {
-        let (addressable, read_addressable_cycles) = instruction.addressing.read_addressable(&self, bus)?;
-        self.wait_cycles += read_addressable_cycles;
-
-        let address = addressable.address()?;
-        Ok(address)
-    }
+    /// Run an instruction that only uses the registers, i.e. one with an `Implied` or `Accumulator`
+    /// addressing mode.
+    fn execute_implied(&mut self, opcode: Opcode) {
+        match opcode {
+            // Register Operations
+            Opcode::TAX => self.transfer(Register::A, Register::X),
+            Opcode::TAY => self.transfer(Register::A, Register::Y),
+            Opcode::TXA => self.transfer(Register::X, Register::A),
+            Opcode::TYA => self.transfer(Register::Y, Register::A),
 
-    fn try_read_instruction_value(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<u8> {
-        let (addressable, read_addressable_cycles) = instruction.addressing.read_addressable(&self, bus)?;
-        self.wait_cycles += read_addressable_cycles;
+            // Stack Operations
+            Opcode::TSX => self.transfer(Register::SP, Register::X),
+            Opcode::TXS => self.transfer(Register::X, Register::SP),
 
-        let value = addressable.read(self, bus);
+            // Increments & Decrements
+            Opcode::INX => self.modify_register(Register::X, |x| x.wrapping_add(1)),
+            Opcode::INY => self.modify_register(Register::Y, |y| y.wrapping_add(1)),
+            Opcode::DEX => self.modify_register(Register::X, |x| x.wrapping_sub(1)),
+            Opcode::DEY => self.modify_register(Register::Y, |y| y.wrapping_sub(1)),
 
-        Ok(value)
-    }
+            // Shifts
+            Opcode::ASL | Opcode::LSR | Opcode::ROL | Opcode::ROR => {
+                self.a = self.execute_modify(opcode, self.a);
+            }
 
-    fn try_write_instruction_value(&mut self, bus: &mut impl Bus, instruction: Instruction, value: u8) -> Result<()> {
-        let (addressable, read_addressable_cycles) = instruction.addressing.read_addressable(&self, bus)?;
-        self.wait_cycles += read_addressable_cycles;
+            // Status Flag Functions
+            Opcode::CLC => self.p.set(StatusFlag::Carry, false),
+            Opcode::CLD => self.p.set(StatusFlag::DecimalMode, false),
+            Opcode::CLI => self.p.set(StatusFlag::InterruptDisable, false),
+            Opcode::CLV => self.p.set(StatusFlag::Overflow, false),
+            Opcode::SEC => self.p.set(StatusFlag::Carry, true),
+            Opcode::SED => self.p.set(StatusFlag::DecimalMode, true),
+            Opcode::SEI => self.p.set(StatusFlag::InterruptDisable, true),
 
-        addressable.try_write(self, bus, value)?;
+            // System Functions
+            Opcode::NOP => {},
+            Opcode::KIL => {
+                // `KIL` is always one byte so this leaves `pc` on the `KIL`
+                self.pc = self.pc.wrapping_sub(1);
+                self.jammed = true;
+            }
 
-        Ok(())
+            _ => unreachable!("{} doesn't have an implied addressing mode", opcode),
+        }
     }
 
-    fn try_modify_instruction_value(
-        &mut self,
-        bus: &mut impl Bus,
-        instruction: Instruction,
-        f: impl FnOnce(u8) -> u8
-    ) -> Result<(u8, u8)> {
-        let (addressable, read_addressable_cycles) = instruction.addressing.read_addressable(&self, bus)?;
-        self.wait_cycles += read_addressable_cycles;
+    /// Run an instruction that reads `value` from memory (or from the instruction itself, for
+    /// `Immediate` addressing).
+    fn execute_read(&mut self, opcode: Opcode, value: u8) {
+        match opcode {
+            // Register Operations
+            Opcode::LDA => self.write_register(Register::A, value),
+            Opcode::LDX => self.write_register(Register::X, value),
+            Opcode::LDY => self.write_register(Register::Y, value),
+            Opcode::LAX => {
+                self.write_register(Register::A, value);
+                self.write_register(Register::X, value);
+            }
+            Opcode::LAS => {
+                let result = value & self.sp;
+                self.write_register(Register::A, result);
+                self.write_register(Register::X, result);
+                self.sp = result;
+            }
+            Opcode::LXA => {
+                let result = (self.a | UNSTABLE_MAGIC) & value;
+                self.write_register(Register::A, result);
+                self.write_register(Register::X, result);
+            }
+            Opcode::XAA => self.write_register(Register::A, (self.a | UNSTABLE_MAGIC) & self.x & value),
 
-        let (input, output) = addressable.try_modify(self, bus, f)?;
+            // Logical Operations
+            Opcode::AND => self.write_register(Register::A, self.a & value),
+            Opcode::EOR => self.write_register(Register::A, self.a ^ value),
+            Opcode::ORA => self.write_register(Register::A, self.a | value),
+            Opcode::BIT => {
+                self.p.set(StatusFlag::Zero, value & self.a == 0);
+                self.p.set(StatusFlag::Overflow, value & 0b0100_0000 > 0);
+                self.p.set(StatusFlag::Negative, value & 0b1000_0000 > 0);
+            }
+            Opcode::ANC => {
+                self.write_register(Register::A, self.a & value);
+                self.p.set(StatusFlag::Carry, self.p.get(StatusFlag::Negative));
+            }
 
-        Ok((input, output))
-    }
+            // Arithmetic
+            Opcode::ADC => self.add(value),
+            Opcode::SBC => self.subtract(value),
+            Opcode::CMP => self.compare(self.a, value),
+            Opcode::CPX => self.compare(self.x, value),
+            Opcode::CPY => self.compare(self.y, value),
+            Opcode::AXS => {
+                let lhs = self.a & self.x;
+                self.p.set(StatusFlag::Carry, lhs >= value);
+                self.write_register(Register::X, lhs.wrapping_sub(value));
+            }
 
-    fn op_nop(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        // Nop is identical to any other read instruction except it throws away the value
-        //
-        // We ignore errors with reading during NOP since the "legal" NOP has an implied addressing
-        // mode which usually results in an invalid read
-        let _ = self.try_read_instruction_value(bus, instruction);
+            // Shifts
+            Opcode::ALR => {
+                let value = value & self.a;
+                self.p.set(StatusFlag::Carry, value & 0b0000_0001 > 0);
+                self.write_register(Register::A, value >> 1);
+            }
+            Opcode::ARR => {
+                let value = value & self.a;
+                let carry = u8::from(self.p.get(StatusFlag::Carry)) << 7;
+                let result = (value >> 1) | carry;
+
+                // The carry out of the rotate is thrown away: `Carry` and `Overflow` come from
+                // bits 6 and 5 of the result instead, as a side effect of the adder being involved.
+                let bit_6 = result & 0b0100_0000 > 0;
+                let bit_5 = result & 0b0010_0000 > 0;
+                self.p.set(StatusFlag::Carry, bit_6);
+                self.p.set(StatusFlag::Overflow, bit_6 != bit_5);
+                self.write_register(Register::A, result);
+            }
 
-        Ok(())
-    }
+            // System Functions
+            //
+            // Nop is identical to any other read instruction except it throws away the value
+            Opcode::NOP => {},
 
-    fn op_load(&mut self, bus: &mut impl Bus, register: Register, instruction: Instruction) -> Result<()> {
-        let value = self.try_read_instruction_value(bus, instruction)?;
-        self.write_register(register, value);
-        Ok(())
+            _ => unreachable!("{} doesn't read memory", opcode),
+        }
     }
 
-    /// Special variant of `op_load` that loads into `A` and `X`
+    /// The value a store instruction writes to `address`.
     ///
-    /// Takes the same amount of time as a single `op_load`
-    fn op_lax(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let value = self.try_read_instruction_value(bus, instruction)?;
-        self.write_register(Register::A, value);
-        self.write_register(Register::X, value);
-        Ok(())
-    }
-
-    fn op_store(&mut self, bus: &mut impl Bus, register: Register, instruction: Instruction) -> Result<()> {
-        let value = self.read_register(register);
-        self.try_write_instruction_value(bus, instruction, value)?;
-        Ok(())
-    }
+    /// `SHA`, `SHX`, `SHY` and `TAS` store a register `AND`ed with `H`, the high byte of the
+    /// address before indexing plus one. They work this value out while the CPU is still fixing up
+    /// the high byte of the indexed address, so when the index crosses a page the stored value also
+    /// replaces the high byte of `address`.
+    ///
+    /// See also: https://www.nesdev.org/wiki/Programming_with_unofficial_opcodes
+    fn store_value(&mut self, opcode: Opcode, address: &mut Address, page_crossed: bool) -> u8 {
+        let register = match opcode {
+            Opcode::STA => return self.a,
+            Opcode::STX => return self.x,
+            Opcode::STY => return self.y,
+            Opcode::SAX => return self.a & self.x,
+
+            Opcode::SHA => self.a & self.x,
+            Opcode::SHX => self.x,
+            Opcode::SHY => self.y,
+            Opcode::TAS => {
+                self.sp = self.a & self.x;
+                self.sp
+            }
 
-    /// Special variant of `op_store` that stores `A & X` into the target address
-    fn op_sax(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let value = self.a & self.x;
-        self.try_write_instruction_value(bus, instruction, value)?;
-        Ok(())
-    }
+            _ => unreachable!("{} doesn't write memory", opcode),
+        };
 
-    /// Copy the contents of `source` into `target`
-    fn op_transfer(&mut self, source: Register, target: Register) -> Result<()> {
-        let value = self.read_register(source);
-        self.write_register(target, value);
-        Ok(())
-    }
+        // `address` has already been fixed up, so if we crossed a page it's one page past the base
+        let [lo, hi] = address.to_le_bytes();
+        let base_hi = if page_crossed { hi.wrapping_sub(1) } else { hi };
+        let value = register & base_hi.wrapping_add(1);
 
-    fn op_push_stack(&mut self, bus: &mut impl Bus, source: Register) -> Result<()> {
-        let mut value = self.read_register(source);
-        // If we push `P` it sets `Break` to `true` for the value that is pushed to the stack
-        if source == Register::P {
-            let mut status = Status(value);
-            status.set(StatusFlag::Break, true);
-            value = status.0
+        if page_crossed {
+            *address = u16::from_le_bytes([lo, value]);
         }
 
-        self.push_stack_u8(bus, value);
-
-        Ok(())
-    }
-
-    fn op_pull_stack(&mut self, bus: &mut impl Bus, target: Register) -> Result<()> {
-        let value = self.pull_stack_u8(bus);
-        self.write_register(target, value);
-        Ok(())
+        value
     }
 
-    fn op_jump(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let address = self.try_read_instruction_target_address(bus, instruction)?;
-        self.pc = address;
-        Ok(())
-    }
-
-    fn op_jump_subroutine(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let address = self.try_read_instruction_target_address(bus, instruction)?;
-
-        // Calculating the return_address costs 1 cycle on the 6502
-        let return_address = self.pc - 1;
-        self.wait_cycles += 1;
-
-        self.push_stack_u16(bus, return_address);
-
-        self.pc = address;
-        Ok(())
-    }
-
-    fn op_return(&mut self, bus: &mut impl Bus) -> Result<()> {
-        let address = self.pull_stack_u16(bus);
-
-        // Calculating the offset address costs 1 cycle on the 6502
-        self.pc = address + 1;
-        self.wait_cycles += 1;
-        Ok(())
-    }
-
-    fn op_return_from_interrupt(&mut self, bus: &mut impl Bus) -> Result<()> {
-        if let [p, pcl, pch] = self.pull_stack(bus, 3)[..] {
-            self.write_register(Register::P, p);
-            self.pc = u16::from_le_bytes([pcl, pch]);
-            Ok(())
-        } else {
-            panic!("self.pull_stack(3) returned unexpected number of elements");
-        }
-    }
-
-    fn op_branch_if(&mut self, bus: &mut impl Bus, instruction: Instruction, condition: bool) -> Result<()> {
-        let (addressable, read_addressable_cycles) = instruction.addressing.read_addressable(&self, bus)?;
-        self.wait_cycles += read_addressable_cycles;
-
-        let address = addressable.address()?;
-        if condition {
-            self.pc = address;
-            self.wait_cycles += 1;
-
-            if addressable.page_boundary_crossed {
-                self.wait_cycles += 1;
+    /// Run a read-modify-write instruction on `input`, returning the value to write back.
+    ///
+    /// The unofficial combinations (`SLO`, `DCP`, etc...) also combine the result with `A`.
+    fn execute_modify(&mut self, opcode: Opcode, input: u8) -> u8 {
+        let output = match opcode {
+            Opcode::ASL | Opcode::SLO => {
+                self.p.set(StatusFlag::Carry, input & 0b1000_0000 > 0);
+                input.wrapping_shl(1)
             }
-        }
-        Ok(())
-    }
-
-    fn op_logical(&mut self, bus: &mut impl Bus, instruction: Instruction, f: fn(u8, u8) -> u8) -> Result<()> {
-        let value = self.try_read_instruction_value(bus, instruction)?;
-        let result = f(self.a, value);
-        self.write_register(Register::A, result);
-        Ok(())
-    }
+            Opcode::LSR | Opcode::SRE => {
+                self.p.set(StatusFlag::Carry, input & 0b0000_0001 > 0);
+                input.wrapping_shr(1)
+            }
+            Opcode::ROL | Opcode::RLA => {
+                let carry = u8::from(self.p.get(StatusFlag::Carry));
+                self.p.set(StatusFlag::Carry, input & 0b1000_0000 > 0);
+                input.wrapping_shl(1) | carry
+            }
+            Opcode::ROR | Opcode::RRA => {
+                let carry = u8::from(self.p.get(StatusFlag::Carry)) << 7;
+                self.p.set(StatusFlag::Carry, input & 0b0000_0001 > 0);
+                input.wrapping_shr(1) | carry
+            }
+            Opcode::INC | Opcode::ISC => input.wrapping_add(1),
+            Opcode::DEC | Opcode::DCP => input.wrapping_sub(1),
 
-    fn op_bit(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let value = self.try_read_instruction_value(bus, instruction)?;
-        let result = value & self.a;
+            _ => unreachable!("{} doesn't modify memory", opcode),
+        };
 
-        self.p.set(StatusFlag::Zero, result == 0);
-        self.p.set(StatusFlag::Overflow, value & 0b0100_0000 > 0);
-        self.p.set(StatusFlag::Negative, value & 0b1000_0000 > 0);
-        Ok(())
-    }
+        // When doing a `modify` we affect `Zero` and `Negative` even when
+        // writing to memory
+        self.p.set(StatusFlag::Zero, output == 0);
+        self.p.set(StatusFlag::Negative, output & 0b1000_0000 > 0);
+
+        match opcode {
+            Opcode::SLO => self.write_register(Register::A, self.a | output),
+            Opcode::SRE => self.write_register(Register::A, self.a ^ output),
+            Opcode::RLA => self.write_register(Register::A, self.a & output),
+            Opcode::RRA => self.add(output),
+            Opcode::DCP => self.compare(self.a, output),
+            Opcode::ISC => self.subtract(output),
+            _ => {},
+        }
 
-    fn op_add(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let rhs = self.try_read_instruction_value(bus, instruction)?;
-        self.add(Register::A, rhs)
+        output
     }
 
-    fn add(&mut self, lhs_register: Register, rhs: u8) -> Result<()> {
-        let lhs = self.read_register(lhs_register);
+    fn add(&mut self, rhs: u8) {
+        let lhs = self.a;
         let carry: u8 = self.p.get(StatusFlag::Carry).into();
 
-        let (result, result_overflow) = self.a.overflowing_add(rhs);
+        let (result, result_overflow) = lhs.overflowing_add(rhs);
         let (result, carry_overflow) = result.overflowing_add(carry);
 
         let result_carry = result_overflow || carry_overflow;
@@ -681,29 +681,43 @@ impl MOS6502 {
         let overflow = (lhs_sign == rhs_sign) && (lhs_sign != result_sign);
         self.p.set(StatusFlag::Overflow, overflow);
 
-        self.write_register(lhs_register, result);
-
-        Ok(())
-    }
+        self.write_register(Register::A, result);
 
-    fn op_sub(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let rhs = self.try_read_instruction_value(bus, instruction)?;
-        self.subtract(Register::A, rhs)
+        if self.decimal_mode && self.p.get(StatusFlag::DecimalMode) {
+            self.add_decimal(lhs, rhs, carry);
+        }
     }
 
-    /// Increment the addressed memory then subtract the result from `a`
+    /// Redo an `ADC` of `lhs`, `rhs` and `carry` as BCD, replacing `A`, `Carry`, `Negative` and
+    /// `Overflow`. The NMOS 6502 leaves `Zero` set from the binary sum and takes `Negative` and
+    /// `Overflow` from the sum after only the low digit has been adjusted.
     ///
-    /// This is an unofficial opcode
-    fn op_increment_subtract(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let (_, output) = self.try_modify_instruction_value(bus, instruction, |v| v.wrapping_add(1))?;
-        self.subtract(Register::A, output)
+    /// See also: http://www.6502.org/tutorials/decimal_mode.html#A
+    fn add_decimal(&mut self, lhs: u8, rhs: u8, carry: u8) {
+        let mut low = (lhs & 0x0F) + (rhs & 0x0F) + carry;
+        if low >= 0x0A {
+            low = ((low + 0x06) & 0x0F) + 0x10;
+        }
+
+        let signed_sum = (lhs & 0xF0) as i8 as i16 + (rhs & 0xF0) as i8 as i16 + low as i16;
+        self.p.set(StatusFlag::Overflow, !(-128..=127).contains(&signed_sum));
+
+        let mut result = (lhs & 0xF0) as u16 + (rhs & 0xF0) as u16 + low as u16;
+        self.p.set(StatusFlag::Negative, result & 0b1000_0000 > 0);
+
+        if result >= 0xA0 {
+            result += 0x60;
+        }
+
+        self.p.set(StatusFlag::Carry, result >= 0x100);
+        self.a = result as u8;
     }
 
-    fn subtract(&mut self, lhs_register: Register, rhs: u8) -> Result<()> {
-        let lhs = self.read_register(lhs_register);
+    fn subtract(&mut self, rhs: u8) {
+        let lhs = self.a;
         let carry: u8 = self.p.get(StatusFlag::Carry).into();
 
-        let (result, result_overflow) = self.a.overflowing_sub(rhs);
+        let (result, result_overflow) = lhs.overflowing_sub(rhs);
         let (result, carry_overflow) = result.overflowing_sub(1 - carry);
 
         let result_carry = result_overflow || carry_overflow;
@@ -720,14 +734,34 @@ impl MOS6502 {
         let overflow = (lhs_sign != rhs_sign) && (lhs_sign != result_sign);
         self.p.set(StatusFlag::Overflow, overflow);
 
-        self.write_register(lhs_register, result);
+        self.write_register(Register::A, result);
 
-        Ok(())
+        if self.decimal_mode && self.p.get(StatusFlag::DecimalMode) {
+            self.subtract_decimal(lhs, rhs, carry);
+        }
     }
 
-    fn op_compare(&mut self, bus: &mut impl Bus, register: Register, instruction: Instruction) -> Result<()> {
-        let register = self.read_register(register);
-        let value = self.try_read_instruction_value(bus, instruction)?;
+    /// Redo an `SBC` of `lhs`, `rhs` and `carry` as BCD, replacing `A`. Unlike `ADC` every flag
+    /// on the NMOS 6502 comes from the binary subtraction.
+    ///
+    /// See also: http://www.6502.org/tutorials/decimal_mode.html#A
+    fn subtract_decimal(&mut self, lhs: u8, rhs: u8, carry: u8) {
+        let borrow = 1 - carry as i16;
+
+        let mut low = (lhs & 0x0F) as i16 - (rhs & 0x0F) as i16 - borrow;
+        if low < 0 {
+            low = ((low - 0x06) & 0x0F) - 0x10;
+        }
+
+        let mut result = (lhs & 0xF0) as i16 - (rhs & 0xF0) as i16 + low;
+        if result < 0 {
+            result -= 0x60;
+        }
+
+        self.a = result as u8;
+    }
+
+    fn compare(&mut self, register: u8, value: u8) {
         let result = register.wrapping_sub(value);
 
         // Compare can be thought of a subtraction that doesn't affect the register. I.e. these
@@ -735,105 +769,165 @@ impl MOS6502 {
         self.p.set(StatusFlag::Carry, register >= value);
         self.p.set(StatusFlag::Zero, result == 0);
         self.p.set(StatusFlag::Negative, result & 0b1000_0000 > 0);
-        Ok(())
     }
+}
 
-    /// Decrements the addressed memory then compares the result with `a`
-    ///
-    /// This is an unofficial opcode
-    fn op_decrement_compare(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let (_, output) = self.try_modify_instruction_value(bus, instruction, |v| v.wrapping_sub(1))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::bus::RamBus16kb;
 
-        // Compare can be thought of a subtraction that doesn't affect the register. I.e. these
-        // flags are the result of (register - value).
-        let comparison = self.a.wrapping_sub(output);
-        self.p.set(StatusFlag::Carry, self.a >= comparison);
-        self.p.set(StatusFlag::Zero, comparison == 0);
-        self.p.set(StatusFlag::Negative, comparison & 0b1000_0000 > 0);
+    /// When the `MOS6502` initializes it should start the program counter
+    /// at the address stored in 0xFFFC
+    #[test]
+    pub fn program_counter_is_initialized_correctly() {
+        let mut bus = RamBus16kb::new();
+        bus.write_u16(0xFFFC, 0xFF00);
 
-        Ok(())
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+
+        assert_eq!(cpu.pc, 0xFF00);
     }
 
-    fn op_shift_left(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<u8> {
-        let (value, result) = self.try_modify_instruction_value(bus, instruction, |value| value.wrapping_shl(1))?;
-        self.p.set(StatusFlag::Carry, value & 0b1000_0000 > 0);
+    #[test]
+    pub fn reset_takes_seven_cycles_and_initializes_registers() {
+        let mut bus = RamBus16kb::new().with_program(vec![0xEA]); // NOP
 
-        Ok(result)
-    }
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
 
-    fn op_shift_left_then_or(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let result = self.op_shift_left(bus, instruction)?;
-        self.modify_register(Register::A, |a| a | result);
-        Ok(())
+        assert_eq!(cpu.sp, 0xFD);
+        assert_eq!(cpu.p.0, 0x24);
+
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+        assert_eq!(cpu.elapsed_cycles, 7);
     }
 
-    fn op_shift_right(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<u8> {
-        let (value, result) = self.try_modify_instruction_value(bus, instruction, |value| value.wrapping_shr(1))?;
-        self.p.set(StatusFlag::Carry, value & 0b0000_0001 > 0);
+    /// Reset reads the byte at `pc` but never decodes it, so garbage in memory
+    /// (such as a `KIL` opcode) shouldn't stop the CPU from resetting.
+    #[test]
+    pub fn reset_ignores_invalid_opcode_at_pc() {
+        let mut bus = RamBus16kb::new().with_memory(vec![0x02]);
 
-        Ok(result)
+        let mut cpu = MOS6502::new();
+        assert!(cpu.reset(&mut bus).is_ok());
     }
 
-    fn op_shift_right_then_xor(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let result = self.op_shift_right(bus, instruction)?;
-        self.modify_register(Register::A, |a| a ^ result);
-        Ok(())
-    }
+    #[test]
+    pub fn kil_jams_until_reset() {
+        let program = vec![
+            0xA9, 0x01, // LDA #$01
+            0x02,       // KIL
+            0xA9, 0x02, // LDA #$02
+        ];
+        let mut bus = RamBus16kb::new().with_program(program);
 
-    fn op_rotate_left(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<u8> {
-        let carry = u8::from(self.p.get(StatusFlag::Carry));
-        let (value, result) = self.try_modify_instruction_value(bus, instruction, |value| {
-            let result = value.wrapping_shl(1);
-            let result = result | carry;
-            result
-        })?;
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_to_next_instruction(&mut bus).unwrap(); // Reset
+        cpu.cycle_to_next_instruction(&mut bus).unwrap(); // LDA #$01
+        cpu.cycle_to_next_instruction(&mut bus).unwrap(); // KIL
+        assert!(cpu.is_jammed());
+
+        let jam_pc = cpu.pc;
+        cpu.nmi = true;
+        for _ in 0..100 {
+            cpu.cycle(&mut bus).unwrap();
+        }
 
-        self.p.set(StatusFlag::Carry, value & 0b1000_0000 > 0);
+        assert_eq!(cpu.a, 0x01);
+        assert_eq!(cpu.pc, jam_pc);
+        assert_eq!(bus.memory[jam_pc as usize], 0x02);
 
-        Ok(result)
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        assert!(!cpu.is_jammed());
     }
 
-    fn op_rotate_right(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<u8> {
-        let carry = u8::from(self.p.get(StatusFlag::Carry)) << 7;
-        let (value, result) = self.try_modify_instruction_value(bus, instruction, |value| {
-            let result = value.wrapping_shr(1);
-            let result = result | carry;
-            result
-        })?;
+    /// Stores only reach the bus on the last cycle of the instruction, and read-modify-write
+    /// instructions write the unmodified value back before the result.
+    #[test]
+    pub fn writes_land_on_their_own_cycle() {
+        let program = vec![
+            0xA9, 0x42,       // LDA #$42
+            0x8D, 0x00, 0x02, // STA $0200
+            0xEE, 0x01, 0x02, // INC $0201
+        ];
+        let mut bus = RamBus16kb::new().with_program(program).with_memory_at(0x0201, vec![0x10]);
 
-        self.p.set(StatusFlag::Carry, value & 0b0000_0001 > 0);
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_to_next_instruction(&mut bus).unwrap(); // Reset
+        cpu.cycle_to_next_instruction(&mut bus).unwrap(); // LDA #$42
 
-        Ok(result)
-    }
+        for _ in 0..3 {
+            cpu.cycle(&mut bus).unwrap();
+            assert_eq!(bus.memory[0x0200], 0x00);
+        }
+        cpu.cycle(&mut bus).unwrap();
+        assert_eq!(bus.memory[0x0200], 0x42);
+        assert!(cpu.at_instruction_boundary());
 
-    fn op_rotate_left_then_and(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let result = self.op_rotate_left(bus, instruction)?;
-        self.modify_register(Register::A, |a| a & result);
-        Ok(())
-    }
+        for _ in 0..4 {
+            cpu.cycle(&mut bus).unwrap();
+        }
+        assert_eq!(cpu.instruction_cycle(), 4);
 
-    fn op_rotate_right_then_add(&mut self, bus: &mut impl Bus, instruction: Instruction) -> Result<()> {
-        let result = self.op_rotate_right(bus, instruction)?;
-        self.add(Register::A, result)
+        // Clobber the value INC has already read so we can see the dummy write restore it
+        bus.memory[0x0201] = 0xFF;
+        cpu.cycle(&mut bus).unwrap();
+        assert_eq!(bus.memory[0x0201], 0x10);
+        cpu.cycle(&mut bus).unwrap();
+        assert_eq!(bus.memory[0x0201], 0x11);
+        assert!(cpu.at_instruction_boundary());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use super::bus::RamBus16kb;
+    #[test]
+    pub fn nmi_hijacks_brk_until_status_is_pushed() {
+        let run_brk = |nmi_cycle: u32| {
+            let mut bus = RamBus16kb::new()
+                .with_nmi_vector_address(0xBEEF)
+                .with_program(vec![0x00, 0x00]); // BRK
+            bus.write_u16(IRQ_VECTOR_ADDRESS, 0x1234);
+
+            let mut cpu = MOS6502::new();
+            cpu.reset(&mut bus).expect("CPU Reset Failed");
+            cpu.cycle_to_next_instruction(&mut bus).unwrap(); // Reset
+
+            for cycle in 1..=7 {
+                cpu.nmi |= cycle == nmi_cycle;
+                cpu.cycle(&mut bus).unwrap();
+            }
+            assert!(cpu.at_instruction_boundary());
+
+            (cpu, bus)
+        };
+
+        let (cpu, bus) = run_brk(5);
+        assert_eq!(cpu.pc, 0xBEEF);
+        assert!(!cpu.nmi);
+        assert!(Status(bus.memory[0x01FB]).get(StatusFlag::Break));
+
+        let (cpu, _) = run_brk(6);
+        assert_eq!(cpu.pc, 0x1234);
+        assert!(cpu.nmi);
+    }
 
-    /// When the `MOS6502` initializes it should start the program counter
-    /// at the address stored in 0xFFFC
     #[test]
-    pub fn program_counter_is_initialized_correctly() {
-        let mut bus = RamBus16kb::new();
-        bus.write_u16(0xFFFC, 0xFF00);
+    pub fn reset_with_custom_power_on_state() {
+        let mut bus = RamBus16kb::new().with_program(vec![0xEA]); // NOP
 
-        let mut cpu = MOS6502::new();
+        let mut cpu = MOS6502::new()
+            .with_power_on_sp(0xFF)
+            .with_power_on_status(Status::default().with(StatusFlag::Carry, true))
+            .with_reset_cycles(6);
         cpu.reset(&mut bus).expect("CPU Reset Failed");
 
-        assert_eq!(cpu.pc, 0xFF00);
+        assert_eq!(cpu.sp, 0xFC);
+        assert_eq!(cpu.p.0, 0x25);
+
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+        assert_eq!(cpu.elapsed_cycles, 6);
     }
 
     #[test]
@@ -875,6 +969,143 @@ mod tests {
         assert_eq!(bus.memory[0x02], 0xFF);
     }
 
+    #[test]
+    pub fn op_unofficial_immediate() {
+        let program = vec![
+            0xA9, 0xF0,  // LDA #$F0
+            0x0B, 0x81,  // ANC #$81
+            0x85, 0x00,  // STA $00
+            0xA9, 0x07,  // LDA #$07
+            0x4B, 0x03,  // ALR #$03
+            0x85, 0x01,  // STA $01
+            0x38,        // SEC
+            0xA9, 0xFF,  // LDA #$FF
+            0x6B, 0xC0,  // ARR #$C0
+            0x85, 0x02,  // STA $02
+            0xA9, 0x0F,  // LDA #$0F
+            0xA2, 0x3C,  // LDX #$3C
+            0xCB, 0x02,  // AXS #$02
+        ];
+        let mut bus = RamBus16kb::new().with_program(program);
+
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_until_brk(&mut bus).unwrap();
+
+        assert_eq!(bus.memory[0x00], 0x80);
+        assert_eq!(bus.memory[0x01], 0x01);
+        assert_eq!(bus.memory[0x02], 0xE0);
+        assert_eq!(cpu.x, 0x0A);
+        assert!(cpu.p.get(StatusFlag::Carry));
+    }
+
+    #[test]
+    pub fn op_unofficial_read_modify_write() {
+        let program = vec![
+            0xA9, 0x05,       // LDA #$05
+            0xA2, 0x00,       // LDX #$00
+            0xA0, 0x03,       // LDY #$03
+            0xDB, 0x00, 0x02, // DCP $0200,Y
+        ];
+        let mut bus = RamBus16kb::new()
+            .with_program(program)
+            .with_memory_at(0x0200, vec![0x10, 0x00, 0x00, 0x06]);
+
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_until_brk(&mut bus).unwrap();
+
+        // `DCP` decrements the byte at $0203 then compares it with `A`, leaving $0200 alone
+        assert_eq!(bus.memory[0x0203], 0x05);
+        assert_eq!(bus.memory[0x0200], 0x10);
+        assert!(cpu.p.get(StatusFlag::Zero));
+        assert!(cpu.p.get(StatusFlag::Carry));
+    }
+
+    /// `SHX` and `SHY` store the register `AND`ed with the high byte of the address plus one. If
+    /// indexing crosses a page that value replaces the high byte of the address too.
+    #[test]
+    pub fn op_store_high_byte_and() {
+        let program = vec![
+            0xA0, 0xFF,       // LDY #$FF
+            0xA2, 0x01,       // LDX #$01
+            0x9C, 0x00, 0x02, // SHY $0200,X
+            0xA2, 0x05,       // LDX #$05
+            0xA0, 0x20,       // LDY #$20
+            0x9E, 0xF0, 0x02, // SHX $02F0,Y
+        ];
+        let mut bus = RamBus16kb::new().with_program(program);
+
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_until_brk(&mut bus).unwrap();
+
+        assert_eq!(bus.memory[0x0201], 0x03);
+        assert_eq!(bus.memory[0x0110], 0x01);
+        assert_eq!(bus.memory[0x0310], 0x00);
+    }
+
+    #[test]
+    pub fn op_las() {
+        let program = vec![
+            0xA0, 0x01,       // LDY #$01
+            0xBB, 0xFF, 0x00, // LAS $00FF,Y
+        ];
+        let mut bus = RamBus16kb::new().with_program(program);
+        bus.memory[0x0100] = 0x7F;
+
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+        cpu.cycle_to_next_instruction(&mut bus).unwrap();
+
+        // `SP` starts at `0xFD`
+        assert_eq!(cpu.a, 0x7D);
+        assert_eq!(cpu.x, 0x7D);
+        assert_eq!(cpu.sp, 0x7D);
+
+        // +7 cycles for reset, +2 for LDY and +5 for LAS since it crossed a page
+        assert_eq!(cpu.elapsed_cycles, 14);
+    }
+
+    #[test]
+    pub fn op_add_subtract_decimal() {
+        let program = vec![
+            0xF8,        // SED
+            0x18,        // CLC
+            0xA9, 0x58,  // LDA #$58
+            0x69, 0x46,  // ADC #$46
+            0x85, 0x00,  // STA $00
+            0x08,        // PHP
+            0x38,        // SEC
+            0xA9, 0x12,  // LDA #$12
+            0xE9, 0x21,  // SBC #$21
+            0x85, 0x01,  // STA $01
+        ];
+
+        let mut bus = RamBus16kb::new().with_program(program.clone());
+        let mut cpu = MOS6502::new().with_decimal_mode(true);
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_until_brk(&mut bus).unwrap();
+
+        // 58 + 46 = 104, 12 - 21 = -9 (91 with a borrow)
+        assert_eq!(bus.memory[0x00], 0x04);
+        assert!(Status(bus.memory[0x01FD]).get(StatusFlag::Carry));
+        assert_eq!(bus.memory[0x01], 0x91);
+        assert!(!cpu.p.get(StatusFlag::Carry));
+
+        // The 2A03 ignores `SED`
+        let mut bus = RamBus16kb::new().with_program(program);
+        let mut cpu = MOS6502::new();
+        cpu.reset(&mut bus).expect("CPU Reset Failed");
+        cpu.cycle_until_brk(&mut bus).unwrap();
+
+        assert_eq!(bus.memory[0x00], 0x9E);
+        assert_eq!(bus.memory[0x01], 0xF1);
+        assert!(cpu.p.get(StatusFlag::DecimalMode));
+    }
+
     /// Pushing a 16 bit address on the stack is a bit fiddly. This test is to check that `JSR` and `RTS` have the correct
     /// interactions and that they write exactly the right bytes to the stack _in the right order_.
     #[test]
@@ -34,6 +34,58 @@ pub enum Opcode {
     /// This is an "Unofficial" opcode but shows up in some binaries regardless
     SAX,
 
+    /// Load `A & SP` into `A`, `X` and `SP`
+    ///
+    /// Also known as `LAR`
+    ///
+    /// This is an unofficial opcode
+    LAS,
+
+    /// Load the immediate value `AND`ed with `A` into `A` and `X`. The result is also `AND`ed with
+    /// an unstable "magic" value, see `UNSTABLE_MAGIC` in `mos6502`.
+    ///
+    /// Also known as `ATX` or `OAL`
+    ///
+    /// This is an unofficial opcode
+    LXA,
+
+    /// Set `A` to `X & M` where `M` is the immediate value. The result is also `AND`ed with an
+    /// unstable "magic" value, see `UNSTABLE_MAGIC` in `mos6502`.
+    ///
+    /// Also known as `ANE`
+    ///
+    /// This is an unofficial opcode
+    XAA,
+
+    /// Store `A & X & H` into memory where `H` is the high byte of the target address plus one
+    ///
+    /// Also known as `AHX`
+    ///
+    /// This is an unofficial opcode
+    SHA,
+
+    /// Store `X & H` into memory where `H` is the high byte of the target address plus one
+    ///
+    /// Also known as `SXA`
+    ///
+    /// This is an unofficial opcode
+    SHX,
+
+    /// Store `Y & H` into memory where `H` is the high byte of the target address plus one
+    ///
+    /// Also known as `SYA`
+    ///
+    /// This is an unofficial opcode
+    SHY,
+
+    /// Copy `A & X` into `SP` then store `SP & H` into memory where `H` is the high byte of the
+    /// target address plus one
+    ///
+    /// Also known as `SHS`
+    ///
+    /// This is an unofficial opcode
+    TAS,
+
     /// Copy the contents of `A` into `X`
     TAX,
 
@@ -87,6 +139,13 @@ pub enum Opcode {
     /// are written into `P` under the `Zero`, `Overflow` and `Negative` flags.
     BIT,
 
+    /// `AND` the immediate value with `A` then copy bit 7 of the result into `Carry`
+    ///
+    /// Also known as `AAC`
+    ///
+    /// This is an unofficial opcode
+    ANC,
+
     // =====================================================================================
     // ====================================== Arithmetic ===================================
     // =====================================================================================
@@ -110,6 +169,14 @@ pub enum Opcode {
     /// Same as `CMP` but compares `Y` and the target memory location
     CPY,
 
+    /// Set `X` to `(A & X) - M` where `M` is the immediate value. `Carry` is set like `CMP`
+    /// and the existing `Carry` is ignored.
+    ///
+    /// Also known as `SBX`
+    ///
+    /// This is an unofficial opcode
+    AXS,
+
     // =====================================================================================
     // =============================== Increments & Decrements =============================
     // =====================================================================================
@@ -190,6 +257,19 @@ pub enum Opcode {
     /// This is an unofficial opcode
     RRA,
 
+    /// `AND` the immediate value with `A` then shift `A` one bit to the right
+    ///
+    /// Also known as `ASR`
+    ///
+    /// This is an unofficial opcode
+    ALR,
+
+    /// `AND` the immediate value with `A` then rotate `A` one bit to the right. `Carry` is set from
+    /// bit 6 of the result and `Overflow` from bit 6 XOR bit 5.
+    ///
+    /// This is an unofficial opcode
+    ARR,
+
     // =====================================================================================
     // =================================== Jumps & Calls ===================================
     // =====================================================================================
@@ -270,6 +350,14 @@ pub enum Opcode {
 
     /// Return from Interrupt: Pull `P` from the stack followed by `PC`
     RTI,
+
+    /// Halt the CPU. It stops fetching instructions and ignores interrupts until it is reset,
+    /// see `MOS6502::is_jammed`.
+    ///
+    /// Also known as `JAM` or `HLT`
+    ///
+    /// This is an unofficial opcode
+    KIL,
 }
 
 impl fmt::Display for Opcode {
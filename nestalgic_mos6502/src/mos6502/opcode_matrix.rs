@@ -0,0 +1,162 @@
+use std::convert::TryFrom;
+
+use super::addressing_mode::AddressingMode;
+use super::instruction::InstructionSignature;
+use super::opcode::Opcode;
+
+/// Everything we know about a single opcode byte.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct OpcodeInfo {
+    pub byte: u8,
+    pub opcode: Opcode,
+    pub addressing_mode: AddressingMode,
+
+    /// The number of cycles taken before any penalties for crossing pages or taking branches.
+    pub base_cycles: u8,
+
+    /// False if this opcode isn't part of the documented 6502 instruction set.
+    pub official: bool,
+}
+
+impl OpcodeInfo {
+    /// Look up the `OpcodeInfo` for `byte`. Returns `None` if we don't support the opcode.
+    pub fn for_byte(byte: u8) -> Option<OpcodeInfo> {
+        let signature = InstructionSignature::try_from(byte).ok()?;
+
+        let official = match signature.opcode {
+            Opcode::LAX | Opcode::SAX | Opcode::DCP | Opcode::ISC |
+            Opcode::SLO | Opcode::SRE | Opcode::RLA | Opcode::RRA |
+            Opcode::ANC | Opcode::ALR | Opcode::ARR | Opcode::AXS | Opcode::LAS |
+            Opcode::LXA | Opcode::XAA | Opcode::SHA | Opcode::SHX | Opcode::SHY | Opcode::TAS | Opcode::KIL => false,
+            Opcode::NOP => byte == 0xEA,
+            Opcode::SBC => byte != 0xEB,
+            _ => true,
+        };
+
+        Some(OpcodeInfo {
+            byte,
+            opcode: signature.opcode,
+            addressing_mode: signature.addressing_mode,
+            base_cycles: BASE_CYCLES[byte as usize],
+            official,
+        })
+    }
+
+    /// All supported opcodes in byte order.
+    pub fn all() -> impl Iterator<Item = OpcodeInfo> {
+        (0..=0xFF).filter_map(OpcodeInfo::for_byte)
+    }
+}
+
+/// The short assembler-style name of each `AddressingMode`, used in generated documentation.
+pub fn addressing_mode_short_name(addressing_mode: AddressingMode) -> &'static str {
+    match addressing_mode {
+        AddressingMode::Implied => "imp",
+        AddressingMode::Accumulator => "acc",
+        AddressingMode::Immediate => "imm",
+        AddressingMode::ZeroPage => "zp",
+        AddressingMode::ZeroPageX => "zp,x",
+        AddressingMode::ZeroPageY => "zp,y",
+        AddressingMode::Relative => "rel",
+        AddressingMode::Indirect => "ind",
+        AddressingMode::IndexedIndirect => "(ind,x)",
+        AddressingMode::IndirectIndexed => "(ind),y",
+        AddressingMode::Absolute => "abs",
+        AddressingMode::AbsoluteX => "abs,x",
+        AddressingMode::AbsoluteY => "abs,y",
+    }
+}
+
+/// Render the opcode table as markdown.
+///
+/// The output is checked in as `OPCODES.md` and a test makes sure it matches, so the documentation
+/// can't drift from `INSTRUCTION_SIGNATURES`.
+pub fn opcode_matrix_markdown() -> String {
+    let mut markdown = String::new();
+
+    markdown.push_str("# MOS6502 Opcodes\n\n");
+    markdown.push_str("<!-- Generated by `opcode_matrix_markdown`. Do not edit by hand. -->\n\n");
+    markdown.push_str("Each cell shows the mnemonic, addressing mode and base cycle count of an opcode byte. ");
+    markdown.push_str("Unofficial opcodes are marked with `*`. Empty cells are not supported.\n\n");
+
+    markdown.push_str("|    |");
+    for lo in 0..=0xF {
+        markdown.push_str(&format!(" x{:X} |", lo));
+    }
+    markdown.push('\n');
+
+    markdown.push_str("|----|");
+    for _ in 0..=0xF {
+        markdown.push_str("----|");
+    }
+    markdown.push('\n');
+
+    for hi in 0..=0xF {
+        markdown.push_str(&format!("| {:X}x |", hi));
+        for lo in 0..=0xF {
+            let cell = match OpcodeInfo::for_byte((hi << 4) | lo) {
+                Some(info) => format!(
+                    " {}{}<br>{} {} |",
+                    info.opcode,
+                    if info.official { "" } else { "*" },
+                    addressing_mode_short_name(info.addressing_mode),
+                    info.base_cycles
+                ),
+                None => "  |".to_string(),
+            };
+            markdown.push_str(&cell);
+        }
+        markdown.push('\n');
+    }
+
+    markdown
+}
+
+/// Base cycle counts for every opcode byte, including ones we don't support.
+///
+/// Branches take +1 cycle when taken and reads take +1 cycle when crossing a page boundary.
+///
+/// See also: http://www.obelisk.me.uk/6502/reference.html
+static BASE_CYCLES: [u8; 256] = [
+    /*        0  1  2  3  4  5  6  7  8  9  A  B  C  D  E  F */
+    /* 0 */   7, 6, 2, 8, 3, 3, 5, 5, 3, 2, 2, 2, 4, 4, 6, 6,
+    /* 1 */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+    /* 2 */   6, 6, 2, 8, 3, 3, 5, 5, 4, 2, 2, 2, 4, 4, 6, 6,
+    /* 3 */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+    /* 4 */   6, 6, 2, 8, 3, 3, 5, 5, 3, 2, 2, 2, 3, 4, 6, 6,
+    /* 5 */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+    /* 6 */   6, 6, 2, 8, 3, 3, 5, 5, 4, 2, 2, 2, 5, 4, 6, 6,
+    /* 7 */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+    /* 8 */   2, 6, 2, 6, 3, 3, 3, 3, 2, 2, 2, 2, 4, 4, 4, 4,
+    /* 9 */   2, 6, 2, 6, 4, 4, 4, 4, 2, 5, 2, 5, 5, 5, 5, 5,
+    /* A */   2, 6, 2, 6, 3, 3, 3, 3, 2, 2, 2, 2, 4, 4, 4, 4,
+    /* B */   2, 5, 2, 5, 4, 4, 4, 4, 2, 4, 2, 4, 4, 4, 4, 4,
+    /* C */   2, 6, 2, 8, 3, 3, 5, 5, 2, 2, 2, 2, 4, 4, 6, 6,
+    /* D */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+    /* E */   2, 6, 2, 8, 3, 3, 5, 5, 2, 2, 2, 2, 4, 4, 6, 6,
+    /* F */   2, 5, 2, 8, 4, 4, 6, 6, 2, 4, 2, 7, 4, 4, 7, 7,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn for_byte_describes_official_opcode() {
+        let info = OpcodeInfo::for_byte(0xBD).unwrap();
+
+        assert_eq!(info.opcode, Opcode::LDA);
+        assert_eq!(info.addressing_mode, AddressingMode::AbsoluteX);
+        assert_eq!(info.base_cycles, 4);
+        assert!(info.official);
+    }
+
+    #[test]
+    pub fn for_byte_marks_unofficial_opcodes() {
+        assert!(!OpcodeInfo::for_byte(0xA7).unwrap().official); // LAX
+        assert!(!OpcodeInfo::for_byte(0xEB).unwrap().official); // SBC
+        assert!(!OpcodeInfo::for_byte(0x1A).unwrap().official); // NOP
+        assert!(!OpcodeInfo::for_byte(0xCB).unwrap().official); // AXS
+        assert!(OpcodeInfo::for_byte(0xEA).unwrap().official);  // NOP
+    }
+}
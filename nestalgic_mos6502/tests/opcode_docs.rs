@@ -0,0 +1,19 @@
+use nestalgic_mos6502::mos6502::opcode_matrix_markdown;
+
+/// `OPCODES.md` is generated from the opcode table. If this test fails, regenerate it with:
+///
+/// ```text
+/// NESTALGIC_REGENERATE_DOCS=1 cargo test -p nestalgic_mos6502 --test opcode_docs
+/// ```
+#[test]
+fn opcodes_md_matches_opcode_table() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/OPCODES.md");
+    let generated = opcode_matrix_markdown();
+
+    if std::env::var_os("NESTALGIC_REGENERATE_DOCS").is_some() {
+        std::fs::write(path, &generated).expect("Failed to write OPCODES.md");
+    }
+
+    let checked_in = std::fs::read_to_string(path).expect("Failed to read OPCODES.md");
+    assert!(checked_in == generated, "OPCODES.md is out of date with the opcode table");
+}
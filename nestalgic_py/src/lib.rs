@@ -0,0 +1,119 @@
+//! Python bindings for Nestalgic, built with maturin:
+//!
+//! ```text
+//! cd nestalgic_py && maturin develop --release
+//! ```
+//!
+//! ```text
+//! import nestalgic_py
+//!
+//! nes = nestalgic_py.Nestalgic(open("game.nes", "rb").read())
+//! nes.set_buttons(0, 0b0000_1000)  # Start
+//! nes.run_frames(60)
+//! screen = nes.screen()            # numpy array of shape (240, 256, 4)
+//! ```
+//!
+//! The console is deterministic, so the same ROM and inputs always give the same frames.
+//!
+//! The smoke tests in `tests/` run against the installed module, see `.github/workflows/ci.yml`.
+
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3, PyArrayMethods};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use nestalgic::{Buttons, NESROM, Nestalgic, Player};
+
+/// A console with a cartridge inserted. See `nestalgic::Nestalgic`.
+#[pyclass(name = "Nestalgic", unsendable)]
+struct PyNestalgic {
+    nestalgic: Nestalgic,
+}
+
+#[pymethods]
+impl PyNestalgic {
+    /// Power on a console with the iNES ROM in `rom`.
+    #[new]
+    fn new(rom: &[u8]) -> PyResult<Self> {
+        let rom = NESROM::from_bytes(rom.to_vec()).map_err(|error| PyValueError::new_err(error.to_string()))?;
+        let nestalgic = Nestalgic::new(rom).map_err(|error| PyValueError::new_err(error.to_string()))?;
+
+        Ok(PyNestalgic { nestalgic })
+    }
+
+    /// The number of frames completed since power on.
+    #[getter]
+    fn frame(&self) -> u64 {
+        self.nestalgic.ppu.frame
+    }
+
+    /// Run until `frames` more frames have finished.
+    #[pyo3(signature = (frames = 1))]
+    fn run_frames(&mut self, frames: u64) -> PyResult<()> {
+        for _ in 0..frames {
+            self.nestalgic.try_run_frame().map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Hold `buttons` on controller `player` (0 or 1). Bit 0 is A, then B, Select, Start, Up,
+    /// Down, Left and Right.
+    fn set_buttons(&mut self, player: u8, buttons: u8) -> PyResult<()> {
+        let player = match player {
+            0 => Player::One,
+            1 => Player::Two,
+            _ => return Err(PyValueError::new_err("player must be 0 or 1")),
+        };
+
+        self.nestalgic.controller_mut(player).buttons = Buttons(buttons);
+        Ok(())
+    }
+
+    /// Read CPU memory without side effects.
+    fn peek(&self, address: u16) -> u8 {
+        self.nestalgic.peek_u8(address)
+    }
+
+    /// Change a byte of RAM (`0x0000`-`0x1FFF`).
+    fn poke(&mut self, address: u16, value: u8) -> PyResult<()> {
+        if self.nestalgic.poke_u8(address, value) {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err(format!("0x{:04X} is not in RAM", address)))
+        }
+    }
+
+    /// A copy of the console's 2KB of RAM.
+    fn wram<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<u8>> {
+        PyArray1::from_slice(py, self.nestalgic.wram())
+    }
+
+    /// A copy of the screen as RGBA bytes with shape `(240, 256, 4)`.
+    fn screen<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let mut rgba = vec![0; Nestalgic::SCREEN_PIXELS * 4];
+        self.nestalgic.render_into(&mut rgba);
+
+        rgba.into_pyarray(py).reshape([Nestalgic::SCREEN_HEIGHT, Nestalgic::SCREEN_WIDTH, 4])
+    }
+
+    /// A copy of the palette index (0x00-0x3F) of each pixel with shape `(240, 256)`. This is
+    /// smaller than `screen` and doesn't depend on the colour palette, which suits training.
+    fn palette_indices<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<u8>>> {
+        self.nestalgic
+            .palette_indices()
+            .to_vec()
+            .into_pyarray(py)
+            .reshape([Nestalgic::SCREEN_HEIGHT, Nestalgic::SCREEN_WIDTH])
+    }
+
+    /// A fingerprint of the machine state, see `Nestalgic::state_hash`.
+    fn state_hash(&self) -> u64 {
+        self.nestalgic.state_hash()
+    }
+}
+
+#[pymodule]
+fn nestalgic_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyNestalgic>()?;
+    Ok(())
+}
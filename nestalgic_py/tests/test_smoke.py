@@ -0,0 +1,61 @@
+"""Smoke tests for the Python bindings. Build the module first with `maturin develop`, then run
+`python -m unittest discover nestalgic_py/tests` from the repository root."""
+
+import pathlib
+import unittest
+
+import nestalgic_py
+
+NESTEST = pathlib.Path(__file__).parents[2] / "nestalgic" / "tests" / "fixtures" / "nestest.nes"
+
+try:
+    import numpy
+except ImportError:
+    numpy = None
+
+
+def nestest():
+    return nestalgic_py.Nestalgic(NESTEST.read_bytes())
+
+
+class SmokeTest(unittest.TestCase):
+    def test_runs_frames(self):
+        nes = nestest()
+        nes.run_frames(2)
+        self.assertEqual(nes.frame, 2)
+
+    def test_is_deterministic(self):
+        first, second = nestest(), nestest()
+        for nes in (first, second):
+            nes.set_buttons(0, 0b0000_1000)
+            nes.run_frames(5)
+
+        self.assertEqual(first.state_hash(), second.state_hash())
+
+    def test_pokes_ram(self):
+        nes = nestest()
+        nes.poke(0x0010, 0xAB)
+        self.assertEqual(nes.peek(0x0010), 0xAB)
+
+        with self.assertRaises(ValueError):
+            nes.poke(0xC000, 0)
+
+    def test_rejects_invalid_input(self):
+        with self.assertRaises(ValueError):
+            nestalgic_py.Nestalgic(b"not a rom")
+
+        with self.assertRaises(ValueError):
+            nestest().set_buttons(2, 0)
+
+    @unittest.skipIf(numpy is None, "numpy isn't installed")
+    def test_returns_arrays(self):
+        nes = nestest()
+        nes.run_frames(1)
+
+        self.assertEqual(nes.screen().shape, (240, 256, 4))
+        self.assertEqual(nes.palette_indices().shape, (240, 256))
+        self.assertEqual(nes.wram().shape, (2048,))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+/// A No-Intro DAT file: the sizes and checksums of known good ROM dumps.
+///
+/// DATs use the Logiqx XML format. We only care about the `<game>` and `<rom>` elements:
+///
+/// ```text
+/// <game name="Example Game (USA)">
+///     <rom name="Example Game (USA).nes" size="24576" crc="158B0388" status="verified"/>
+/// </game>
+/// ```
+///
+/// No-Intro checksums are calculated without the 16 byte iNES header, so `verify` skips the
+/// header before comparing.
+///
+/// See also: https://datomatic.no-intro.org/
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Dat {
+    pub entries: Vec<DatEntry>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct DatEntry {
+    /// The name of the game this ROM belongs to
+    pub game: String,
+
+    /// The size of the ROM in bytes, excluding the iNES header
+    pub size: usize,
+
+    pub crc32: u32,
+
+    /// No-Intro sometimes lists a dump that's known to be bad when no good dump exists
+    pub bad_dump: bool,
+}
+
+/// The result of checking a ROM against a `Dat`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DumpStatus<'a> {
+    /// The ROM exactly matches a good dump.
+    Good(&'a DatEntry),
+
+    /// The ROM starts with a good dump but has extra data on the end. Usually harmless, but the
+    /// header may describe the wrong amount of PRG or CHR.
+    Overdump(&'a DatEntry),
+
+    /// The ROM matches a dump that No-Intro has marked as bad.
+    BadDump(&'a DatEntry),
+
+    /// The ROM isn't in the DAT. It may be corrupt, hacked or just missing from this DAT.
+    Unknown,
+}
+
+#[derive(PartialEq, Debug, Error)]
+pub enum DatError {
+    #[error("<{tag}> is missing the `{attribute}` attribute")]
+    MissingAttribute { tag: &'static str, attribute: &'static str },
+
+    #[error("`{value}` is not a valid `{attribute}`")]
+    InvalidAttribute { attribute: &'static str, value: String },
+
+    #[error("<rom> found outside of a <game>")]
+    RomOutsideGame,
+}
+
+impl Dat {
+    pub fn from_xml(xml: &str) -> Result<Dat, DatError> {
+        let mut entries = Vec::new();
+        let mut game = None;
+
+        for tag in xml.split('<').skip(1) {
+            let tag = tag.split('>').next().unwrap_or("");
+            let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+
+            match name {
+                // Newer DATs call games machines
+                "game" | "machine" => {
+                    let name = attribute(attributes, "name")
+                        .ok_or(DatError::MissingAttribute { tag: "game", attribute: "name" })?;
+                    game = Some(name);
+                },
+                "/game" | "/machine" => game = None,
+                "rom" => {
+                    let game = game.clone().ok_or(DatError::RomOutsideGame)?;
+                    let size = required_attribute(attributes, "size")?;
+                    let crc32 = required_attribute(attributes, "crc")?;
+
+                    entries.push(DatEntry {
+                        game,
+                        size: size.parse().map_err(|_| DatError::InvalidAttribute { attribute: "size", value: size.clone() })?,
+                        crc32: u32::from_str_radix(&crc32, 16).map_err(|_| DatError::InvalidAttribute { attribute: "crc", value: crc32.clone() })?,
+                        bad_dump: attribute(attributes, "status").as_deref() == Some("baddump"),
+                    });
+                },
+                _ => {},
+            }
+        }
+
+        Ok(Dat { entries })
+    }
+
+    /// Check the contents of a ROM file, including its iNES header if it has one.
+    pub fn verify(&self, rom_file: &[u8]) -> DumpStatus<'_> {
+        let data = match rom_file {
+            [b'N', b'E', b'S', 0x1A, ..] if rom_file.len() >= 16 => &rom_file[16..],
+            _ => rom_file,
+        };
+
+        let checksum = crc32(data);
+        if let Some(entry) = self.entries.iter().find(|entry| entry.size == data.len() && entry.crc32 == checksum) {
+            return if entry.bad_dump { DumpStatus::BadDump(entry) } else { DumpStatus::Good(entry) };
+        }
+
+        // Many entries share a size, so only checksum each prefix once
+        let mut prefix_crc32s = HashMap::new();
+        self.entries
+            .iter()
+            .filter(|entry| !entry.bad_dump && entry.size < data.len())
+            .find(|entry| *prefix_crc32s.entry(entry.size).or_insert_with(|| crc32(&data[..entry.size])) == entry.crc32)
+            .map_or(DumpStatus::Unknown, DumpStatus::Overdump)
+    }
+}
+
+fn required_attribute(attributes: &str, key: &'static str) -> Result<String, DatError> {
+    attribute(attributes, key).ok_or(DatError::MissingAttribute { tag: "rom", attribute: key })
+}
+
+/// Find `key="value"` in the attributes of a tag and return the unescaped value.
+fn attribute(attributes: &str, key: &str) -> Option<String> {
+    let mut rest = attributes;
+    while let Some((name, value)) = rest.split_once("=\"") {
+        let (value, remaining) = value.split_once('"')?;
+        if name.trim() == key {
+            return Some(unescape(value));
+        }
+        rest = remaining;
+    }
+
+    None
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// The CRC-32 (IEEE 802.3) checksum used by DAT files.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFF;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
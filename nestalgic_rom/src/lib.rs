@@ -1 +1,3 @@
 pub mod nesrom;
+pub mod dat;
+pub mod md5;
@@ -1,7 +1,7 @@
 use super::Result;
 use super::error::Error;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum FileType {
     /// The iNES file type
     INES,
@@ -5,7 +5,7 @@ use super::mirroring_type::MirroringType;
 
 use std::convert::TryInto;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Header {
     pub file_type: FileType,
 
@@ -23,6 +23,15 @@ pub struct Header {
     /// The number of bytes containing the character rom data.
     pub chr_rom_bytes: u32,
 
+    /// The number of bytes of PRG RAM mapped from `0x6000`, including any battery-backed RAM.
+    ///
+    /// iNES headers rarely give this so they default to 8kb, which covers almost every board.
+    pub prg_ram_bytes: u32,
+
+    /// The number of bytes of CHR RAM, including any battery-backed RAM. Only boards without
+    /// character rom use CHR RAM.
+    pub chr_ram_bytes: u32,
+
     /// The type of Nametable Mirroring used by this ROM.
     ///
     /// TODO: Figure out what this is and write a better comment
@@ -68,6 +77,13 @@ impl Header {
         // As above we need to multiply the value to get the real number.
         let chr_rom_bytes = (rom_bytes[5] as u32) * 8192;
 
+        // Byte 8 gives the PRG RAM size in increments of 8kb. A value of 0 means 8kb for compatibility
+        // with headers that predate the field.
+        let prg_ram_bytes = (rom_bytes[8].max(1) as u32) * 8192;
+
+        // iNES can't describe CHR RAM, but boards without character rom always have 8kb of it.
+        let chr_ram_bytes = if chr_rom_bytes == 0 { 8192 } else { 0 };
+
         let mirroring_type = MirroringType::from_ines_byte_6(rom_bytes[6]);
         let has_persistent_memory = (rom_bytes[6] & 0b0000_0010 >> 1) != 0;
         let has_trainer = (rom_bytes[6] & 0b0000_0100 >> 2) != 0;
@@ -80,6 +96,8 @@ impl Header {
             file_type: FileType::INES,
             prg_rom_bytes,
             chr_rom_bytes,
+            prg_ram_bytes,
+            chr_ram_bytes,
             mirroring_type,
             has_persistent_memory,
             has_trainer,
@@ -91,13 +109,39 @@ impl Header {
 
     /// Load a header from the "NES 2.0" file format.
     ///
-    /// At the moment we don't actually use any NES 2.0 file format features
-    /// and the format is backwards compatible with INES so we just parse it
-    /// with `from_bytes_ines` and change the file type.
+    /// NES 2.0 is backwards compatible with iNES so we start from `from_bytes_ines` and then
+    /// replace the fields NES 2.0 gives more precisely.
+    ///
+    /// Bytes 10 and 11 give the PRG and CHR RAM sizes as shift counts:
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// NNNN VVVV
+    /// |||| ||||
+    /// |||| ++++- Volatile RAM size is 64 << V bytes, or none if V is 0
+    /// ++++------ Battery-backed RAM size is 64 << N bytes, or none if N is 0
+    /// ```
+    ///
+    /// See also: https://wiki.nesdev.com/w/index.php/NES_2.0#PRG-.28NV.29RAM.2FEEPROM
     fn from_bytes_nes2(rom_bytes: [u8; 16]) -> Result<Header> {
-        let mut ines_header = Header::from_bytes_ines(rom_bytes)?;
-        ines_header.file_type = FileType::NES2;
+        let mut header = Header::from_bytes_ines(rom_bytes)?;
+        header.file_type = FileType::NES2;
+        header.prg_ram_bytes = Header::nes2_ram_bytes(rom_bytes[10]);
+        header.chr_ram_bytes = Header::nes2_ram_bytes(rom_bytes[11]);
+
+        // Some tools write NES 2.0 headers with no CHR RAM size. Every board without character
+        // rom needs CHR RAM, so fall back to the iNES default of 8kb.
+        if header.chr_rom_bytes == 0 && header.chr_ram_bytes == 0 {
+            header.chr_ram_bytes = 8192;
+        }
+
+        Ok(header)
+    }
+
+    fn nes2_ram_bytes(byte: u8) -> u32 {
+        let shift_to_bytes = |shift: u8| if shift == 0 { 0 } else { 64 << shift };
 
-        Ok(ines_header)
+        shift_to_bytes(byte & 0b0000_1111) + shift_to_bytes(byte >> 4)
     }
 }
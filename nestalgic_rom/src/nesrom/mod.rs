@@ -9,7 +9,7 @@ pub use mirroring_type::MirroringType;
 
 pub type Result<A> = std::result::Result<A, error::Error>;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct NESROM {
     pub header: Header,
 
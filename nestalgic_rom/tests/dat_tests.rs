@@ -0,0 +1,59 @@
+use nestalgic_rom::dat::{self, Dat, DatError, DumpStatus};
+
+const NESTEST_DAT: &str = r#"<?xml version="1.0"?>
+<datafile>
+    <header>
+        <name>Nintendo - Nintendo Entertainment System (Headerless)</name>
+    </header>
+    <game name="Other Game (USA)">
+        <rom name="Other Game (USA).nes" size="24576" crc="12345678" status="baddump"/>
+    </game>
+    <game name="nestest &amp; friends">
+        <rom name="nestest.nes" size="24576" crc="158B0388" status="verified"/>
+    </game>
+    <game name="nestest PRG">
+        <rom name="nestest PRG.nes" size="16384" crc="7C5060F0"/>
+    </game>
+</datafile>
+"#;
+
+#[test]
+fn crc32_matches_the_standard_check_value() {
+    assert_eq!(dat::crc32(b"123456789"), 0xCBF43926);
+}
+
+#[test]
+fn verify_skips_the_ines_header() {
+    let dat = Dat::from_xml(NESTEST_DAT).expect("Failed to parse DAT");
+    let rom_file = include_bytes!("./fixtures/nestest.nes");
+
+    match dat.verify(rom_file) {
+        DumpStatus::Good(entry) => assert_eq!(entry.game, "nestest & friends"),
+        status => panic!("expected a good dump, got {:?}", status),
+    }
+}
+
+#[test]
+fn verify_detects_overdumps_and_bad_dumps() {
+    let dat = Dat::from_xml(NESTEST_DAT).expect("Failed to parse DAT");
+    let rom_file = include_bytes!("./fixtures/nestest.nes");
+
+    // Only the PRG is listed for "nestest PRG", so the full ROM is an overdump of it
+    let prg_dat = Dat { entries: dat.entries[2..].to_vec() };
+    match prg_dat.verify(rom_file) {
+        DumpStatus::Overdump(entry) => assert_eq!(entry.game, "nestest PRG"),
+        status => panic!("expected an overdump, got {:?}", status),
+    }
+
+    let mut corrupt = rom_file.to_vec();
+    corrupt[100] ^= 0xFF;
+    assert_eq!(dat.verify(&corrupt), DumpStatus::Unknown);
+
+    assert!(dat.entries[0].bad_dump);
+}
+
+#[test]
+fn rom_outside_game_is_an_error() {
+    let dat = Dat::from_xml(r#"<datafile><rom name="a.nes" size="1" crc="0"/></datafile>"#);
+    assert_eq!(dat, Err(DatError::RomOutsideGame));
+}
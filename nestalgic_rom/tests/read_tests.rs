@@ -10,6 +10,8 @@ fn load_nestest_with_expected_header() {
         file_type: nesrom::FileType::INES,
         prg_rom_bytes: 16384,
         chr_rom_bytes: 8192,
+        prg_ram_bytes: 8192,
+        chr_ram_bytes: 0,
         mirroring_type: nesrom::MirroringType::Horizontal,
         has_persistent_memory: false,
         has_trainer: false,
@@ -27,3 +29,31 @@ fn load_nestest_with_consistent_header_and_data() {
     assert_eq!(rom.header.prg_rom_bytes as usize, rom.prg_rom.len());
     assert_eq!(rom.header.chr_rom_bytes as usize, rom.chr_rom.len());
 }
+
+#[test]
+fn nes2_header_gives_ram_sizes() {
+    let mut rom_file = include_bytes!("./fixtures/nestest.nes").to_vec();
+    rom_file[5] = 0; // No CHR ROM
+    rom_file[7] |= 0b0000_1000; // NES 2.0
+    rom_file[10] = 0x07; // 8kb of volatile PRG RAM
+    rom_file[11] = 0x09; // 32kb of volatile CHR RAM
+
+    let header = NESROM::from_bytes(rom_file).expect("Failed to load file").header;
+
+    assert_eq!(header.file_type, nesrom::FileType::NES2);
+    assert_eq!(header.prg_ram_bytes, 8 * 1024);
+    assert_eq!(header.chr_ram_bytes, 32 * 1024);
+}
+
+#[test]
+fn nes2_header_without_ram_sizes_has_no_prg_ram() {
+    let mut rom_file = include_bytes!("./fixtures/nestest.nes").to_vec();
+    rom_file[7] |= 0b0000_1000;
+    rom_file[10] = 0;
+    rom_file[11] = 0;
+
+    let header = NESROM::from_bytes(rom_file).expect("Failed to load file").header;
+
+    assert_eq!(header.prg_ram_bytes, 0);
+    assert_eq!(header.chr_ram_bytes, 0);
+}
@@ -0,0 +1,37 @@
+use imgui::Ui;
+
+/// Shows the emulator version and which mappers it supports, so a game that won't boot can be
+/// told apart from an unsupported cartridge board.
+pub struct AboutWindow {
+    pub open: bool
+}
+
+impl AboutWindow {
+    pub fn render(&mut self, ui: &Ui) {
+        if !self.open { return; }
+
+        let window = imgui::Window::new("About");
+        let version_info = nestalgic::version_info();
+
+        window
+            .opened(&mut self.open)
+            .always_auto_resize(true)
+            .build(&ui, || {
+                ui.text(format!("Nestalgic {}", version_info.version));
+
+                ui.separator();
+                ui.text("Supported mappers");
+                for mapper in version_info.mappers {
+                    ui.text(format!("  {:>3}", mapper.number));
+                    ui.same_line();
+                    ui.text_disabled(format!("{} ({})", mapper.name, mapper.submappers_label()));
+                }
+            });
+    }
+}
+
+impl Default for AboutWindow {
+    fn default() -> Self {
+        Self { open: false }
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result, Context};
+
+use crate::theme::Theme;
+
+/// Settings that are kept between sessions, saved in `PATH` as `key = value` lines:
+///
+/// ```text
+/// theme = custom 0.26 0.59 0.98
+/// ```
+///
+/// Unknown keys are ignored so a config written by a newer version still loads.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Config {
+    pub theme: Theme,
+}
+
+impl Config {
+    const PATH: &'static str = "nestalgic_ui.cfg";
+
+    /// The config saved by `save`, or the defaults if nothing has been saved yet.
+    pub fn load() -> Result<Config> {
+        let path = Path::new(Config::PATH);
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let text = fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+        Config::parse(&text).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let text = format!("theme = {}\n", self.theme.to_config());
+        fs::write(Config::PATH, text).with_context(|| format!("Could not write {}", Config::PATH))
+    }
+
+    fn parse(text: &str) -> Result<Config> {
+        let mut config = Config::default();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected `key = value`, got `{}`", line))?;
+
+            if key.trim() == "theme" {
+                config.theme = Theme::from_config(value.trim())
+                    .ok_or_else(|| anyhow!("unknown theme `{}`", value.trim()))?;
+            }
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,71 @@
+use nestalgic::{Button, Buttons, Player};
+use winit::event::VirtualKeyCode;
+use winit_input_helper::WinitInputHelper;
+
+/// Maps keyboard keys to the buttons of a single controller.
+///
+/// Each player gets their own profile so that two players can share one keyboard.
+pub struct KeyboardProfile {
+    pub player: Player,
+    pub buttons: Vec<(Button, VirtualKeyCode)>,
+    pub turbo_buttons: Vec<(Button, VirtualKeyCode)>,
+}
+
+impl KeyboardProfile {
+    /// Arrow keys to move, `X`/`Z` for A/B, `V`/`C` for turbo A/B, `Right Shift` for Select and
+    /// `Return` for Start.
+    pub fn player_one() -> KeyboardProfile {
+        KeyboardProfile {
+            player: Player::One,
+            buttons: vec![
+                (Button::A, VirtualKeyCode::X),
+                (Button::B, VirtualKeyCode::Z),
+                (Button::Select, VirtualKeyCode::RShift),
+                (Button::Start, VirtualKeyCode::Return),
+                (Button::Up, VirtualKeyCode::Up),
+                (Button::Down, VirtualKeyCode::Down),
+                (Button::Left, VirtualKeyCode::Left),
+                (Button::Right, VirtualKeyCode::Right),
+            ],
+            turbo_buttons: vec![
+                (Button::A, VirtualKeyCode::V),
+                (Button::B, VirtualKeyCode::C),
+            ],
+        }
+    }
+
+    /// `WASD` to move, `G`/`F` for A/B, `T`/`R` for turbo A/B, `Q` for Select and `E` for Start.
+    pub fn player_two() -> KeyboardProfile {
+        KeyboardProfile {
+            player: Player::Two,
+            buttons: vec![
+                (Button::A, VirtualKeyCode::G),
+                (Button::B, VirtualKeyCode::F),
+                (Button::Select, VirtualKeyCode::Q),
+                (Button::Start, VirtualKeyCode::E),
+                (Button::Up, VirtualKeyCode::W),
+                (Button::Down, VirtualKeyCode::S),
+                (Button::Left, VirtualKeyCode::A),
+                (Button::Right, VirtualKeyCode::D),
+            ],
+            turbo_buttons: vec![
+                (Button::A, VirtualKeyCode::T),
+                (Button::B, VirtualKeyCode::R),
+            ],
+        }
+    }
+
+    pub fn held_buttons(&self, input: &WinitInputHelper) -> Buttons {
+        KeyboardProfile::held(&self.buttons, input)
+    }
+
+    pub fn held_turbo_buttons(&self, input: &WinitInputHelper) -> Buttons {
+        KeyboardProfile::held(&self.turbo_buttons, input)
+    }
+
+    fn held(keys: &[(Button, VirtualKeyCode)], input: &WinitInputHelper) -> Buttons {
+        keys.iter().fold(Buttons::default(), |buttons, &(button, key)| {
+            buttons.with(button, buttons.get(button) || input.key_held(key))
+        })
+    }
+}
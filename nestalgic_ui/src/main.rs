@@ -2,12 +2,19 @@
 #![forbid(unsafe_code)]
 
 mod ui;
+mod config;
 mod nes_texture_window;
 mod nes_ppu_window;
+mod nes_input_window;
+mod nes_input_stats_window;
+mod shortcuts_window;
+mod about_window;
+mod theme;
+mod keyboard_profile;
 mod nestalgic_ui;
 mod ext;
 
-use anyhow::{Result, Context};
+use anyhow::{anyhow, Result, Context};
 use log::error;
 use nestalgic::{NESROM, Nestalgic};
 use nestalgic_ui::NestalgicUI;
@@ -20,12 +27,15 @@ use winit_input_helper::WinitInputHelper;
 const WIDTH: u32 = 1280;
 const HEIGHT: u32 = 960;
 
+pub const QUIT_KEY: VirtualKeyCode = VirtualKeyCode::Escape;
+
 fn main() -> Result<()> {
     env_logger::init();
 
-    let rom_file = include_bytes!("../../roms/donkey-kong.nes").to_vec();
+    let rom_path = std::env::args().nth(1).ok_or_else(|| anyhow!("usage: nestalgic_ui <rom.nes>"))?;
+    let rom_file = std::fs::read(&rom_path).with_context(|| format!("Could not read {}", rom_path))?;
     let rom = NESROM::from_bytes(rom_file).context("Failed to load ROM")?;
-    let nestalgic = Nestalgic::new(rom);
+    let nestalgic = Nestalgic::new(rom).context("Failed to load cartridge")?;
 
     let event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
@@ -53,7 +63,10 @@ fn main() -> Result<()> {
 
         nestalgic_ui.handle_event(&window, &event);
         if input.update(&event) {
-            if input.key_pressed(VirtualKeyCode::Escape) || input.quit() {
+            if input.key_pressed(QUIT_KEY) || input.quit() {
+                if let Err(error) = nestalgic_ui.save() {
+                    error!("save failed: {}", error);
+                }
                 *control_flow = ControlFlow::Exit;
                 return;
             }
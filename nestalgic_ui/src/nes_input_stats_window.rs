@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Result, Context};
+use imgui::Ui;
+use nestalgic::{Button, Nestalgic, Player};
+
+/// Shows how often each button has been pressed in a game, with an option to export the numbers as
+/// CSV.
+///
+/// The stats are kept per game in `STATS_DIR`, so they carry on from the last session when the
+/// same ROM is loaded again.
+pub struct NesInputStatsWindow {
+    pub open: bool,
+
+    /// The result of the last export, shown under the export button
+    export_status: Option<String>,
+}
+
+impl NesInputStatsWindow {
+    const EXPORT_PATH: &'static str = "input_stats.csv";
+    const STATS_DIR: &'static str = "input_stats";
+
+    /// Restore the stats saved by `save_stats` the last time this game was played, if there are any.
+    pub fn load_stats(nestalgic: &mut Nestalgic) -> Result<()> {
+        let path = NesInputStatsWindow::stats_path(nestalgic);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let state = fs::read(&path).with_context(|| format!("Could not read {}", path.display()))?;
+        nestalgic.load_input_stats(&state).with_context(|| format!("Invalid input stats in {}", path.display()))
+    }
+
+    pub fn save_stats(nestalgic: &Nestalgic) -> Result<()> {
+        let path = NesInputStatsWindow::stats_path(nestalgic);
+        fs::create_dir_all(NesInputStatsWindow::STATS_DIR).context("Could not create input stats directory")?;
+        fs::write(&path, nestalgic.save_input_stats()).with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    fn stats_path(nestalgic: &Nestalgic) -> PathBuf {
+        PathBuf::from(NesInputStatsWindow::STATS_DIR).join(format!("{:08X}.stats", nestalgic.rom_crc32()))
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &Ui,
+        nestalgic: &mut Nestalgic,
+    ) {
+        if !self.open { return; }
+
+        let window = imgui::Window::new("Input Stats");
+        let export_status = &mut self.export_status;
+
+        window
+            .opened(&mut self.open)
+            .always_auto_resize(true)
+            .build(&ui, || {
+                let stats = nestalgic.input_stats();
+                ui.text(format!("Frames: {}", stats.frames));
+
+                ui.separator();
+                ui.text(format!("{:<8}{:>10}{:>10}", "Button", "P1", "P2"));
+                for button in Button::variants() {
+                    ui.text(format!(
+                        "{:<8}{:>10}{:>10}",
+                        format!("{:?}", button),
+                        stats.presses(Player::One, button),
+                        stats.presses(Player::Two, button),
+                    ));
+                }
+
+                ui.separator();
+                if ui.button("Export CSV") {
+                    let status = match fs::write(NesInputStatsWindow::EXPORT_PATH, stats.to_csv()) {
+                        Ok(()) => format!("Saved to {}", NesInputStatsWindow::EXPORT_PATH),
+                        Err(error) => format!("Export failed: {}", error),
+                    };
+                    *export_status = Some(status);
+                }
+                ui.same_line();
+                if ui.button("Reset") {
+                    nestalgic.reset_input_stats();
+                }
+
+                if let Some(status) = export_status {
+                    ui.text(status.as_str());
+                }
+            });
+    }
+}
+
+impl Default for NesInputStatsWindow {
+    fn default() -> Self {
+        Self { open: false, export_status: None }
+    }
+}
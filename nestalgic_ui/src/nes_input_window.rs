@@ -0,0 +1,64 @@
+use imgui::{Condition, Ui};
+use nestalgic::{Button, Nestalgic, Player};
+
+/// Overlay showing the buttons currently pressed on each controller.
+pub struct NesInputWindow {
+    pub open: bool
+}
+
+impl NesInputWindow {
+    const PRESSED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+    pub fn render(
+        &mut self,
+        ui: &Ui,
+        nestalgic: &Nestalgic,
+    ) {
+        if !self.open { return; }
+
+        let window = imgui::Window::new("Input Display");
+
+        window
+            .position([10.0, 30.0], Condition::FirstUseEver)
+            .title_bar(false)
+            .resizable(false)
+            .always_auto_resize(true)
+            .bg_alpha(0.35)
+            .build(&ui, || {
+                for (label, player) in [("P1", Player::One), ("P2", Player::Two)] {
+                    let buttons = nestalgic.controller(player).effective_buttons(nestalgic.ppu.frame);
+
+                    ui.text(label);
+                    for button in Button::variants() {
+                        ui.same_line();
+
+                        let name = NesInputWindow::button_label(button);
+                        if buttons.get(button) {
+                            ui.text_colored(NesInputWindow::PRESSED_COLOR, name);
+                        } else {
+                            ui.text_disabled(name);
+                        }
+                    }
+                }
+            });
+    }
+
+    fn button_label(button: Button) -> &'static str {
+        match button {
+            Button::A => "A",
+            Button::B => "B",
+            Button::Select => "Sel",
+            Button::Start => "Sta",
+            Button::Up => "Up",
+            Button::Down => "Dn",
+            Button::Left => "Lt",
+            Button::Right => "Rt",
+        }
+    }
+}
+
+impl Default for NesInputWindow {
+    fn default() -> Self {
+        Self { open: false }
+    }
+}
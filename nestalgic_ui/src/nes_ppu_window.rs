@@ -1,16 +1,32 @@
 use imgui::Ui;
-use nestalgic::Nestalgic;
+use nestalgic::{Condition, Nestalgic, PPUCtrlFlag, PpuAccessKind, PpuWatchHit, PpuWatchpoint};
 
 /// Debug Window to inspect the PPU state.
+///
+/// PPUCTRL and PPUMASK can be edited live. Changes are made directly to the registers without any
+/// of the side effects of a CPU write.
+///
+/// Emulation can be paused and run to a specific frame or scanline to reproduce raster bugs,
+/// e.g. "at frame 1234 the status bar jumps". It can also run until a VRAM address is written
+/// through PPUDATA or fetched by the renderer, to find out when and where on screen it happened.
 pub struct NesPpuWindow {
-    pub open: bool
+    pub open: bool,
+
+    /// While paused the emulator only advances when one of the "Run to" buttons is pressed.
+    pub paused: bool,
+
+    target_frame: i32,
+    target_scanline: i32,
+
+    watch_address: i32,
+    watch_hit: Option<PpuWatchHit>,
 }
 
 impl NesPpuWindow {
     pub fn render(
         &mut self,
         ui: &Ui,
-        nestalgic: &Nestalgic,
+        nestalgic: &mut Nestalgic,
     ) {
         if !self.open { return; }
 
@@ -19,19 +35,133 @@ impl NesPpuWindow {
         window
             .opened(&mut self.open)
             .build(&ui, || {
-                ui.text(format!("ADDR: {:016b}", nestalgic.ppu.addr));
+                NesPpuWindow::run_controls(ui, nestalgic, &mut self.paused, &mut self.target_frame, &mut self.target_scanline);
+                NesPpuWindow::watch_controls(ui, nestalgic, &mut self.paused, &mut self.watch_address, &mut self.watch_hit);
+
                 ui.separator();
-                ui.text(format!("PPUCTRL: {:08b}", nestalgic.ppu.ppuctrl.0));
-                ui.text(format!("PPUMASK: {:08b}", u8::from(nestalgic.ppu.ppumask)));
-                ui.text(format!("PPUSTAT: {:08b}", u8::from(nestalgic.ppu.ppustatus)));
+                let ppu = &mut nestalgic.ppu;
+                ui.text(format!("Scanline: {:3}  Dot: {:3}", ppu.scanline, ppu.cycles));
+                ui.text(format!("Frame: {}", ppu.frame));
+                ui.text(format!("V: 0x{:04X}  T: 0x{:04X}  W: {}", ppu.vram_address.0, ppu.temp_vram_address.0, ppu.write_latch as u8));
+                ui.text(format!("OAMADDR: 0x{:02X}", ppu.oam_addr));
+
+                let registers = ppu.debug_registers_mut();
+                let scroll = *registers.temp_vram_address;
+                let scroll_x = (scroll.nametable() & 1) * 256 + scroll.coarse_x() * 8 + *registers.fine_x_scroll as u16;
+                let scroll_y = (scroll.nametable() >> 1) * 240 + scroll.coarse_y() * 8 + scroll.fine_y();
+                ui.text(format!("Scroll X: {:3}  Y: {:3}", scroll_x, scroll_y));
+
                 ui.separator();
-                ui.text(format!("OAMADDR: {:08b}", nestalgic.ppu.oam_addr));
+                ui.text(format!("PPUCTRL: {:08b}", registers.ppuctrl.0));
+                NesPpuWindow::ppuctrl_checkbox(ui, registers.ppuctrl, "NMI on VBlank", PPUCtrlFlag::GenerateNmiOnVblank);
+                NesPpuWindow::ppuctrl_checkbox(ui, registers.ppuctrl, "8x16 Sprites", PPUCtrlFlag::SpriteSize);
+                NesPpuWindow::ppuctrl_checkbox(ui, registers.ppuctrl, "Background at 0x1000", PPUCtrlFlag::BackgroundPatternTable);
+                NesPpuWindow::ppuctrl_checkbox(ui, registers.ppuctrl, "Sprites at 0x1000", PPUCtrlFlag::SpritePatternTable);
+                NesPpuWindow::ppuctrl_checkbox(ui, registers.ppuctrl, "Increment by 32", PPUCtrlFlag::VramAddressIncrement);
+                ui.text(format!("Base Nametable: 0x{:04X}", registers.ppuctrl.base_nametable_address()));
+
+                ui.separator();
+                ui.text(format!("PPUMASK: {:08b}", u8::from(*registers.ppumask)));
+                let mask = &mut *registers.ppumask;
+                ui.checkbox("Greyscale", &mut mask.greyscale);
+                ui.checkbox("Show Background", &mut mask.show_background);
+                ui.checkbox("Show Sprites", &mut mask.show_sprites);
+                ui.checkbox("Show Background in Left 8px", &mut mask.show_background_on_left_8_pixels);
+                ui.checkbox("Show Sprites in Left 8px", &mut mask.show_sprites_on_left_8_pixels);
+                ui.checkbox("Emphasise Red", &mut mask.emphasise_red);
+                ui.checkbox("Emphasise Green", &mut mask.emphasise_green);
+                ui.checkbox("Emphasise Blue", &mut mask.emphasise_blue);
+
+                ui.separator();
+                let status = *registers.ppustatus;
+                ui.text(format!("PPUSTATUS: {:08b}", u8::from(status)));
+                ui.text(format!("VBlank: {}", status.in_vblank));
+                ui.text(format!("Sprite 0 Hit: {}", status.sprite_0_hit));
+                ui.text(format!("Sprite Overflow: {}", status.sprite_overflow));
             });
     }
+
+    fn run_controls(
+        ui: &Ui,
+        nestalgic: &mut Nestalgic,
+        paused: &mut bool,
+        target_frame: &mut i32,
+        target_scanline: &mut i32,
+    ) {
+        if ui.button(if *paused { "Resume" } else { "Pause" }) {
+            *paused = !*paused;
+        }
+
+        ui.input_int("Frame", target_frame).build();
+        ui.same_line();
+        if ui.button("Run to frame") {
+            *paused = true;
+            nestalgic.run_to_frame((*target_frame).max(0) as u64);
+        }
+
+        ui.input_int("Scanline", target_scanline).build();
+        ui.same_line();
+        if ui.button("Run to scanline of next frame") {
+            *paused = true;
+            let scanline = (*target_scanline).clamp(0, nestalgic.ppu.scanlines_per_frame as i32 - 1);
+            nestalgic.run_to_scanline(scanline as u16);
+        }
+    }
+
+    fn watch_controls(
+        ui: &Ui,
+        nestalgic: &mut Nestalgic,
+        paused: &mut bool,
+        watch_address: &mut i32,
+        watch_hit: &mut Option<PpuWatchHit>,
+    ) {
+        ui.input_int("VRAM Address", watch_address).chars_hexadecimal(true).build();
+        *watch_address = (*watch_address).clamp(0, 0x3FFF);
+
+        let mut run_to = |kind| {
+            *paused = true;
+            let watchpoint = PpuWatchpoint { address: *watch_address as u16, kind };
+            nestalgic.run_until(Condition::PpuAccess(watchpoint), NesPpuWindow::WATCH_CYCLE_LIMIT);
+            nestalgic.ppu_watch_hit()
+        };
+
+        if ui.button("Run to write") {
+            *watch_hit = run_to(PpuAccessKind::Write);
+        }
+        ui.same_line();
+        if ui.button("Run to fetch") {
+            *watch_hit = run_to(PpuAccessKind::Fetch);
+        }
+
+        match watch_hit {
+            Some(hit) => ui.text(format!(
+                "{:?} of 0x{:04X} at frame {}, scanline {}, dot {}",
+                hit.kind, hit.address, hit.frame, hit.scanline, hit.dot
+            )),
+            None => ui.text("No access found"),
+        }
+    }
+
+    /// About 10 NTSC frames
+    const WATCH_CYCLE_LIMIT: u64 = 300_000;
+
+    fn ppuctrl_checkbox(ui: &Ui, ppuctrl: &mut nestalgic::PPUCtrl, label: &str, flag: PPUCtrlFlag) {
+        let mut value = ppuctrl.get(flag);
+        if ui.checkbox(label, &mut value) {
+            ppuctrl.set(flag, value);
+        }
+    }
 }
 
 impl Default for NesPpuWindow {
     fn default() -> Self {
-        Self { open: false }
+        Self {
+            open: false,
+            paused: false,
+            target_frame: 0,
+            target_scanline: 0,
+            watch_address: 0x2000,
+            watch_hit: None,
+        }
     }
 }
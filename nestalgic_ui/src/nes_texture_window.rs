@@ -12,7 +12,10 @@ pub struct NesTextureWindow {
 
     pub open: bool,
 
-    get_nes_texture: fn(&Nestalgic) -> nestalgic::Texture,
+    /// Which palette RAM palette to draw with, or `None` for the placeholder colours
+    pub palette: Option<u8>,
+
+    get_nes_texture: fn(&Nestalgic, Option<u8>) -> nestalgic::Texture,
 
     texture_id: TextureId
 }
@@ -29,7 +32,10 @@ impl NesTextureWindow {
             128,
             128,
             6,
-            |nestalgic| nestalgic.pattern_table_left()
+            |nestalgic, palette| match palette {
+                Some(palette) => nestalgic.pattern_table_left_in_palette(palette),
+                None => nestalgic.pattern_table_left(),
+            }
         )
     }
 
@@ -44,7 +50,10 @@ impl NesTextureWindow {
             128,
             128,
             6,
-            |nestalgic| nestalgic.pattern_table_right()
+            |nestalgic, palette| match palette {
+                Some(palette) => nestalgic.pattern_table_right_in_palette(palette),
+                None => nestalgic.pattern_table_right(),
+            }
         )
     }
 
@@ -55,7 +64,7 @@ impl NesTextureWindow {
         width: usize,
         height: usize,
         default_scale: usize,
-        get_nes_texture: fn(&Nestalgic) -> nestalgic::Texture
+        get_nes_texture: fn(&Nestalgic, Option<u8>) -> nestalgic::Texture
     ) -> NesTextureWindow {
         let texture_config = TextureConfig {
             size: Extent3d {
@@ -78,6 +87,7 @@ impl NesTextureWindow {
             default_scale,
             get_nes_texture,
             open: false,
+            palette: None,
             texture_id
         }
     }
@@ -94,7 +104,7 @@ impl NesTextureWindow {
         let window_name = ImString::new(&self.name);
         let window = imgui::Window::new(&window_name);
 
-        let nes_texture = (self.get_nes_texture)(nestalgic);
+        let nes_texture = (self.get_nes_texture)(nestalgic, self.palette);
         if let Some(chr_texture) = imgui_renderer.textures.get(self.texture_id) {
             let wgpu_texture_data = nes_texture.to_rgba();
             chr_texture.write(&wgpu_queue, &wgpu_texture_data, self.width as u32, self.height as u32);
@@ -103,10 +113,13 @@ impl NesTextureWindow {
         let style = ui.push_style_var(WindowPadding([10.0, 10.0]));
 
         let texture_id = self.texture_id;
+        let palette = &mut self.palette;
         window
             .size([(self.width * self.default_scale) as f32, (self.width * self.default_scale) as f32], Condition::FirstUseEver)
             .opened(&mut self.open)
             .build(&ui, || {
+                NesTextureWindow::render_palette_picker(ui, palette);
+
                 let window_size = ui.window_size();
                 let content_region = ui.content_region_avail();
                 let smallest_dimension = content_region[0].min(content_region[1]);
@@ -125,4 +138,17 @@ impl NesTextureWindow {
 
         style.pop();
     }
+
+    fn render_palette_picker(ui: &Ui, palette: &mut Option<u8>) {
+        const PALETTES: [&str; 9] = [
+            "Placeholder",
+            "Background 0", "Background 1", "Background 2", "Background 3",
+            "Sprite 0", "Sprite 1", "Sprite 2", "Sprite 3",
+        ];
+
+        let mut selected = palette.map_or(0, |palette| palette as usize + 1);
+        if ui.combo_simple_string("Palette", &mut selected, &PALETTES) {
+            *palette = selected.checked_sub(1).map(|palette| palette as u8);
+        }
+    }
 }
@@ -4,8 +4,12 @@ use nestalgic::Nestalgic;
 use pixels::{Pixels, SurfaceTexture};
 
 use anyhow::{Result, Context};
+use log::warn;
 use winit_input_helper::WinitInputHelper;
 
+use crate::config::Config;
+use crate::keyboard_profile::KeyboardProfile;
+use crate::nes_input_stats_window::NesInputStatsWindow;
 use crate::ui::UI;
 
 pub struct NestalgicUI {
@@ -16,6 +20,8 @@ pub struct NestalgicUI {
 
     ui: UI,
 
+    keyboard_profiles: Vec<KeyboardProfile>,
+
     pixels: Pixels
 }
 
@@ -24,9 +30,14 @@ impl NestalgicUI {
     const HEIGHT: u32 = 240;
 
     pub fn new(
-        nestalgic: Nestalgic,
+        mut nestalgic: Nestalgic,
         window: &winit::window::Window
     ) -> Result<NestalgicUI> {
+        // Starting without last session's stats is better than not starting at all
+        if let Err(error) = NesInputStatsWindow::load_stats(&mut nestalgic) {
+            warn!("could not load input stats: {:#}", error);
+        }
+
         let pixels = {
             let window_size = window.inner_size();
             let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, window);
@@ -34,13 +45,18 @@ impl NestalgicUI {
                 .context("Could not create pixels surface")?
         };
 
-        let ui = UI::new(window, pixels.device(), pixels.queue());
+        let config = Config::load().unwrap_or_else(|error| {
+            warn!("could not load config: {:#}", error);
+            Config::default()
+        });
+        let ui = UI::new(window, pixels.device(), pixels.queue(), config.theme);
 
         Ok(NestalgicUI {
             nestalgic,
             time_of_last_update: Instant::now(),
             scale_factor: window.scale_factor(),
             ui,
+            keyboard_profiles: vec![KeyboardProfile::player_one(), KeyboardProfile::player_two()],
             pixels,
         })
     }
@@ -72,23 +88,47 @@ impl NestalgicUI {
             // pixels.resize_buffer(width, height);
         }
 
-        self.nestalgic.tick(delta);
+        NestalgicUI::update_controllers(&mut self.nestalgic, &self.keyboard_profiles, input);
+
+        if !self.ui.paused() {
+            self.nestalgic.tick(delta);
+        }
         self.ui.update(delta);
     }
 
+    /// Save anything that should outlive this session, e.g. the game's input stats.
+    pub fn save(&self) -> Result<()> {
+        Config { theme: self.ui.theme() }.save()?;
+        NesInputStatsWindow::save_stats(&self.nestalgic)
+    }
+
+    fn update_controllers(
+        nestalgic: &mut Nestalgic,
+        keyboard_profiles: &[KeyboardProfile],
+        input: &WinitInputHelper
+    ) {
+        for profile in keyboard_profiles {
+            let controller = nestalgic.controller_mut(profile.player);
+            controller.buttons = profile.held_buttons(input);
+            controller.turbo_buttons = profile.held_turbo_buttons(input);
+        }
+    }
+
     pub fn render(&mut self, window: &winit::window::Window) -> Result<()> {
         let frame = self.pixels.get_frame();
-        NestalgicUI::render_nes(&self.nestalgic, frame);
+        self.nestalgic.render_into(frame);
 
         self.ui.prepare(window)?;
 
-        let nestalgic = &self.nestalgic;
+        let nestalgic = &mut self.nestalgic;
+        let keyboard_profiles = &self.keyboard_profiles;
         let ui = &mut self.ui;
         self.pixels.render_with(|encoder, render_target, context| {
             context.scaling_renderer.render(encoder, render_target);
 
             ui.render(
                 nestalgic,
+                keyboard_profiles,
                 render_target,
                 encoder,
                 &context.queue,
@@ -100,12 +140,4 @@ impl NestalgicUI {
 
         Ok(())
     }
-
-    fn render_nes(_nestalgic: &Nestalgic, frame: &mut [u8]) {
-        for pixel in frame.chunks_exact_mut(4) {
-            let rgba = [0x48, 0xb2, 0xe8, 0xff];
-
-            pixel.copy_from_slice(&rgba);
-        }
-    }
 }
@@ -0,0 +1,61 @@
+use imgui::Ui;
+use nestalgic::Player;
+use winit::event::VirtualKeyCode;
+
+use crate::keyboard_profile::KeyboardProfile;
+
+/// Cheatsheet listing every key binding, grouped by what it controls.
+///
+/// The bindings are read from the active keyboard profiles so the list can't drift from what the
+/// keys actually do.
+pub struct ShortcutsWindow {
+    pub open: bool
+}
+
+impl ShortcutsWindow {
+    pub fn render(
+        &mut self,
+        ui: &Ui,
+        keyboard_profiles: &[KeyboardProfile],
+    ) {
+        if !self.open { return; }
+
+        let window = imgui::Window::new("Shortcuts");
+
+        window
+            .opened(&mut self.open)
+            .always_auto_resize(true)
+            .build(&ui, || {
+                ui.text("Emulator");
+                ShortcutsWindow::binding(ui, "Quit", crate::QUIT_KEY);
+
+                for profile in keyboard_profiles {
+                    ui.separator();
+                    ui.text(match profile.player {
+                        Player::One => "Player 1",
+                        Player::Two => "Player 2",
+                    });
+
+                    for &(button, key) in &profile.buttons {
+                        ShortcutsWindow::binding(ui, &format!("{:?}", button), key);
+                    }
+
+                    for &(button, key) in &profile.turbo_buttons {
+                        ShortcutsWindow::binding(ui, &format!("Turbo {:?}", button), key);
+                    }
+                }
+            });
+    }
+
+    fn binding(ui: &Ui, action: &str, key: VirtualKeyCode) {
+        ui.text(format!("  {:<10}", action));
+        ui.same_line();
+        ui.text_disabled(format!("{:?}", key));
+    }
+}
+
+impl Default for ShortcutsWindow {
+    fn default() -> Self {
+        Self { open: false }
+    }
+}
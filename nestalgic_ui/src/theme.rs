@@ -0,0 +1,106 @@
+use imgui::{Style, StyleColor};
+
+/// The look of the debug UI.
+///
+/// Themes are defined in sRGB and converted for our sRGB framebuffer when applied, so every colour
+/// goes through `Theme::apply` exactly once.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+
+    /// The dark theme with buttons, headers and other highlights drawn in `accent`.
+    Custom { accent: [f32; 3] },
+}
+
+impl Theme {
+    const GAMMA: f32 = 2.2;
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::Custom { .. } => "Custom",
+        }
+    }
+
+    /// How the theme is written in the config file, e.g. `dark` or `custom 0.26 0.59 0.98`.
+    pub fn to_config(self) -> String {
+        match self {
+            Theme::Dark => "dark".to_string(),
+            Theme::Light => "light".to_string(),
+            Theme::Custom { accent: [red, green, blue] } => format!("custom {} {} {}", red, green, blue),
+        }
+    }
+
+    /// The inverse of `to_config`, or `None` if `value` isn't a theme.
+    pub fn from_config(value: &str) -> Option<Theme> {
+        let words = value.split_whitespace().collect::<Vec<&str>>();
+        match words.as_slice() {
+            ["dark"] => Some(Theme::Dark),
+            ["light"] => Some(Theme::Light),
+            ["custom", red, green, blue] => Some(Theme::Custom {
+                accent: [red.parse().ok()?, green.parse().ok()?, blue.parse().ok()?],
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn apply(&self, style: &mut Style) {
+        match self {
+            Theme::Dark => { style.use_dark_colors(); },
+            Theme::Light => { style.use_light_colors(); },
+            Theme::Custom { accent } => {
+                style.use_dark_colors();
+                Theme::apply_accent(style, *accent);
+            },
+        }
+
+        for color in style.colors.iter_mut() {
+            *color = Theme::gamma_to_linear(*color);
+        }
+    }
+
+    /// Recolour the highlights of the current style, keeping their original transparency so
+    /// hovered and active states can still be told apart.
+    fn apply_accent(style: &mut Style, [red, green, blue]: [f32; 3]) {
+        let highlights = [
+            StyleColor::FrameBgHovered,
+            StyleColor::FrameBgActive,
+            StyleColor::TitleBgActive,
+            StyleColor::CheckMark,
+            StyleColor::SliderGrab,
+            StyleColor::SliderGrabActive,
+            StyleColor::Button,
+            StyleColor::ButtonHovered,
+            StyleColor::ButtonActive,
+            StyleColor::Header,
+            StyleColor::HeaderHovered,
+            StyleColor::HeaderActive,
+            StyleColor::SeparatorHovered,
+            StyleColor::SeparatorActive,
+            StyleColor::ResizeGrip,
+            StyleColor::ResizeGripHovered,
+            StyleColor::ResizeGripActive,
+            StyleColor::Tab,
+            StyleColor::TabHovered,
+            StyleColor::TabActive,
+            StyleColor::TextSelectedBg,
+        ];
+
+        for highlight in highlights {
+            let alpha = style[highlight][3];
+            style[highlight] = [red, green, blue, alpha];
+        }
+    }
+
+    fn gamma_to_linear(color: [f32; 4]) -> [f32; 4] {
+        let x = color[0].powf(Theme::GAMMA);
+        let y = color[1].powf(Theme::GAMMA);
+        let z = color[2].powf(Theme::GAMMA);
+        let w = 1.0 - (1.0 - color[3]).powf(Theme::GAMMA);
+
+        [x, y, z, w]
+    }
+}
@@ -4,16 +4,31 @@ use anyhow::{Result, Context};
 use nestalgic::Nestalgic;
 use imgui::Ui;
 
-use crate::{nes_texture_window::NesTextureWindow, nes_ppu_window::NesPpuWindow};
+use crate::{nes_texture_window::NesTextureWindow, nes_ppu_window::NesPpuWindow, nes_input_window::NesInputWindow};
+use crate::keyboard_profile::KeyboardProfile;
+use crate::nes_input_stats_window::NesInputStatsWindow;
+use crate::shortcuts_window::ShortcutsWindow;
+use crate::about_window::AboutWindow;
+use crate::theme::Theme;
 
 pub struct UI {
     imgui: imgui::Context,
     imgui_platform: imgui_winit_support::WinitPlatform,
     imgui_renderer: imgui_wgpu::Renderer,
 
+    theme: Theme,
+
+    /// Set when a new theme is picked from the menu. The style can't change mid-frame so this is
+    /// applied at the start of the next update.
+    pending_theme: Option<Theme>,
+
     ppu_window: NesPpuWindow,
+    input_window: NesInputWindow,
+    input_stats_window: NesInputStatsWindow,
     chr_left_window: NesTextureWindow,
     chr_right_window: NesTextureWindow,
+    shortcuts_window: ShortcutsWindow,
+    about_window: AboutWindow,
 }
 
 impl UI {
@@ -21,6 +36,7 @@ impl UI {
         window: &winit::window::Window,
         wgpu_device: &wgpu::Device,
         wgpu_queue: &wgpu::Queue,
+        theme: Theme,
     ) -> UI {
         let mut imgui = imgui::Context::create();
         imgui.set_ini_filename(None);
@@ -46,11 +62,7 @@ impl UI {
                 }),
             }]);
 
-        // Fix incorrect colors with sRGB framebuffer
-        let style = imgui.style_mut();
-        for color in 0..style.colors.len() {
-            style.colors[color] = gamma_to_linear(style.colors[color]);
-        }
+        theme.apply(imgui.style_mut());
 
         let texture_format = wgpu::TextureFormat::Bgra8UnormSrgb;
         let config = imgui_wgpu::RendererConfig {
@@ -62,6 +74,10 @@ impl UI {
         );
 
         let ppu_window = NesPpuWindow::default();
+        let input_window = NesInputWindow::default();
+        let input_stats_window = NesInputStatsWindow::default();
+        let shortcuts_window = ShortcutsWindow::default();
+        let about_window = AboutWindow::default();
 
         let chr_left_window = NesTextureWindow::new_chr_left_window(
             wgpu_device, &mut imgui_renderer
@@ -76,9 +92,16 @@ impl UI {
             imgui_platform,
             imgui_renderer,
 
+            theme,
+            pending_theme: None,
+
             ppu_window,
+            input_window,
+            input_stats_window,
             chr_left_window,
             chr_right_window,
+            shortcuts_window,
+            about_window,
         }
     }
 
@@ -91,9 +114,24 @@ impl UI {
     }
 
     pub fn update(&mut self, delta: Duration) {
+        if let Some(theme) = self.pending_theme.take() {
+            self.theme = theme;
+            self.theme.apply(self.imgui.style_mut());
+        }
+
         self.imgui.io_mut().update_delta_time(delta);
     }
 
+    /// The theme in use, including one picked from the menu this frame.
+    pub fn theme(&self) -> Theme {
+        self.pending_theme.unwrap_or(self.theme)
+    }
+
+    /// Whether emulation has been paused from the debugger.
+    pub fn paused(&self) -> bool {
+        self.ppu_window.paused
+    }
+
     pub fn prepare(&mut self, window: &winit::window::Window) -> Result<()> {
         self.imgui_platform.prepare_frame(self.imgui.io_mut(), window)
             .context("Could not prepare UI")
@@ -101,7 +139,8 @@ impl UI {
 
     pub fn render(
         &mut self,
-        nestalgic: &Nestalgic,
+        nestalgic: &mut Nestalgic,
+        keyboard_profiles: &[KeyboardProfile],
         render_target: &wgpu::TextureView,
         wgpu_encoder: &mut wgpu::CommandEncoder,
         wgpu_queue: &wgpu::Queue,
@@ -111,13 +150,24 @@ impl UI {
 
         UI::render_menu(
             &ui,
+            nestalgic,
+            self.theme,
+            &mut self.pending_theme,
             &mut self.ppu_window,
+            &mut self.input_window,
+            &mut self.input_stats_window,
             &mut self.chr_left_window,
             &mut self.chr_right_window,
+            &mut self.shortcuts_window,
+            &mut self.about_window,
         );
         self.ppu_window.render(&ui, nestalgic);
+        self.input_window.render(&ui, nestalgic);
+        self.input_stats_window.render(&ui, nestalgic);
         self.chr_left_window.render(&ui, nestalgic, wgpu_queue, &mut self.imgui_renderer);
         self.chr_right_window.render(&ui, nestalgic, wgpu_queue, &mut self.imgui_renderer);
+        self.shortcuts_window.render(&ui, keyboard_profiles);
+        self.about_window.render(&ui);
 
         // Render Dear ImGui with WGPU
         let mut rpass = wgpu_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -140,11 +190,26 @@ impl UI {
 
     fn render_menu(
         ui: &Ui,
+        nestalgic: &mut Nestalgic,
+        theme: Theme,
+        pending_theme: &mut Option<Theme>,
         ppu_window: &mut NesPpuWindow,
+        input_window: &mut NesInputWindow,
+        input_stats_window: &mut NesInputStatsWindow,
         chr_left_window: &mut NesTextureWindow,
         chr_right_window: &mut NesTextureWindow,
+        shortcuts_window: &mut ShortcutsWindow,
+        about_window: &mut AboutWindow,
     ) {
         ui.main_menu_bar(|| {
+            ui.menu("View", || {
+                imgui::MenuItem::new("Input Display")
+                    .build_with_ref(&ui, &mut input_window.open);
+                imgui::MenuItem::new("Input Stats")
+                    .build_with_ref(&ui, &mut input_stats_window.open);
+                ui.menu("Theme", || UI::render_theme_menu(ui, theme, pending_theme));
+            });
+            ui.menu("Emulation", || UI::render_emulation_menu(ui, nestalgic));
             ui.menu("Debug", || {
                 imgui::MenuItem::new("PPU")
                     .build_with_ref(&ui, &mut ppu_window.open);
@@ -153,17 +218,56 @@ impl UI {
                 imgui::MenuItem::new("CHR Right")
                     .build_with_ref(&ui, &mut chr_right_window.open);
             });
+            ui.menu("Help", || {
+                imgui::MenuItem::new("Shortcuts")
+                    .build_with_ref(&ui, &mut shortcuts_window.open);
+                imgui::MenuItem::new("About")
+                    .build_with_ref(&ui, &mut about_window.open);
+                if imgui::MenuItem::new("Copy diagnostic report").build(ui) {
+                    let report = nestalgic
+                        .diagnostic_report()
+                        .with_host_info("Frontend", "nestalgic_ui")
+                        .with_host_info("Theme", theme.name())
+                        .with_host_info("Paused", ppu_window.paused.to_string());
+                    ui.set_clipboard_text(report.redacted());
+                }
+            });
         })
     }
-}
 
-fn gamma_to_linear(color: [f32; 4]) -> [f32; 4] {
-    const GAMMA: f32 = 2.2;
+    fn render_emulation_menu(ui: &Ui, nestalgic: &mut Nestalgic) {
+        let mut limited = nestalgic.cycle_budget().is_some();
+        if imgui::MenuItem::new("Limit catch-up").build_with_ref(ui, &mut limited) {
+            nestalgic.set_cycle_budget(limited.then_some(Nestalgic::DEFAULT_CYCLE_BUDGET));
+        }
+
+        if let Some(mut budget) = nestalgic.cycle_budget() {
+            if imgui::Slider::new("Max cycles per tick", 30_000u64, 300_000).build(ui, &mut budget) {
+                nestalgic.set_cycle_budget(Some(budget));
+            }
+        }
+
+        ui.text(format!("Dropped cycles: {}", nestalgic.dropped_cycles()));
+    }
+
+    fn render_theme_menu(ui: &Ui, theme: Theme, pending_theme: &mut Option<Theme>) {
+        let mut accent = match theme {
+            Theme::Custom { accent } => accent,
+            _ => [0.26, 0.59, 0.98],
+        };
 
-    let x = color[0].powf(GAMMA);
-    let y = color[1].powf(GAMMA);
-    let z = color[2].powf(GAMMA);
-    let w = 1.0 - (1.0 - color[3]).powf(GAMMA);
+        for option in [Theme::Dark, Theme::Light, Theme::Custom { accent }] {
+            let selected = option.name() == theme.name();
+            if imgui::MenuItem::new(option.name()).selected(selected).build(ui) {
+                *pending_theme = Some(option);
+            }
+        }
 
-    [x, y, z, w]
+        if let Theme::Custom { .. } = theme {
+            if imgui::ColorEdit::new("Accent", &mut accent).build(ui) {
+                *pending_theme = Some(Theme::Custom { accent });
+            }
+        }
+    }
 }
+